@@ -1,8 +1,13 @@
 use eframe::egui;
 use egui_system_fonts::{
-    extend_auto, extend_with_region, set_auto, set_with_region, FontRegion, FontStyle,
+    extend_auto_report, extend_with_region, initial_font_definitions, region_label,
+    reset_system_fonts, set_auto_report, set_with_region, snapshot, style_label, FontRegion,
+    FontStyle, REGIONS, STYLES,
 };
 
+/// Key of a font the app adds itself, to show that resetting system fonts keeps it.
+const USER_FONT_KEY: &str = "demo-user-font";
+
 fn main() -> eframe::Result<()> {
     env_logger::init();
 
@@ -14,7 +19,27 @@ fn main() -> eframe::Result<()> {
     eframe::run_native(
         "System Fonts Fallback Demo",
         options,
-        Box::new(|_cc| Ok(Box::new(MyApp::default()))),
+        Box::new(|cc| {
+            let mut app = MyApp::default();
+            // Install system fonts before the first frame so the UI never renders with tofu.
+            let mut defs = match initial_font_definitions(FontStyle::Sans) {
+                Some((defs, report)) => {
+                    app.logs
+                        .push(format!("Startup fonts (Sans): {:?}", report.installed));
+                    defs
+                }
+                None => egui::FontDefinitions::default(),
+            };
+            // A font of the app's own (egui's Hack, under another key) as the last fallback.
+            let user_font = defs.font_data["Hack"].clone();
+            defs.font_data.insert(USER_FONT_KEY.to_owned(), user_font);
+            defs.families
+                .entry(egui::FontFamily::Proportional)
+                .or_default()
+                .push(USER_FONT_KEY.to_owned());
+            cc.egui_ctx.set_fonts(defs);
+            Ok(Box::new(app))
+        }),
     )
 }
 
@@ -85,62 +110,40 @@ impl eframe::App for MyApp {
                     .show(ui, |ui| {
                         ui.label("Target Region:");
                         egui::ComboBox::from_id_salt("region_combo")
-                            .selected_text(match self.selected_region {
-                                None => "Auto (System Locale)",
-                                Some(FontRegion::Korean) => "Korean",
-                                Some(FontRegion::Japanese) => "Japanese",
-                                Some(FontRegion::SimplifiedChinese) => "Chinese (Simplified)",
-                                Some(FontRegion::TraditionalChinese) => "Chinese (Traditional)",
-                                Some(FontRegion::Latin) => "Latin / English",
-                                Some(FontRegion::Cyrillic) => "Cyrillic",
-                                _ => "Unknown",
-                            })
+                            .selected_text(
+                                self.selected_region
+                                    .map_or("Auto (System Locale)", region_label),
+                            )
                             .show_ui(ui, |ui| {
                                 ui.selectable_value(
                                     &mut self.selected_region,
                                     None,
                                     "Auto (System Locale)",
                                 );
-                                ui.selectable_value(
-                                    &mut self.selected_region,
-                                    Some(FontRegion::Korean),
-                                    "Korean",
-                                );
-                                ui.selectable_value(
-                                    &mut self.selected_region,
-                                    Some(FontRegion::Japanese),
-                                    "Japanese",
-                                );
-                                ui.selectable_value(
-                                    &mut self.selected_region,
-                                    Some(FontRegion::SimplifiedChinese),
-                                    "Chinese (Simplified)",
-                                );
-                                ui.selectable_value(
-                                    &mut self.selected_region,
-                                    Some(FontRegion::TraditionalChinese),
-                                    "Chinese (Traditional)",
-                                );
+                                for &region in REGIONS {
+                                    if region == FontRegion::Unknown {
+                                        continue;
+                                    }
+                                    ui.selectable_value(
+                                        &mut self.selected_region,
+                                        Some(region),
+                                        region_label(region),
+                                    );
+                                }
                             });
                         ui.end_row();
 
                         ui.label("Font Style:");
                         egui::ComboBox::from_id_salt("style_combo")
-                            .selected_text(match self.selected_style {
-                                FontStyle::Sans => "Sans-serif",
-                                FontStyle::Serif => "Serif",
-                            })
+                            .selected_text(style_label(self.selected_style))
                             .show_ui(ui, |ui| {
-                                ui.selectable_value(
-                                    &mut self.selected_style,
-                                    FontStyle::Sans,
-                                    "Sans-serif",
-                                );
-                                ui.selectable_value(
-                                    &mut self.selected_style,
-                                    FontStyle::Serif,
-                                    "Serif",
-                                );
+                                for &style in STYLES {
+                                    ui.selectable_value(
+                                        &mut self.selected_style,
+                                        style,
+                                        style_label(style),
+                                    );
+                                }
                             });
                         ui.end_row();
                     });
@@ -151,11 +154,12 @@ impl eframe::App for MyApp {
 
                 ui.horizontal(|ui| {
                     if ui.button("Set (Replace All)").clicked() {
-                        self.add_log(format!("LANG={:?}", std::env::var("LANG")));
-                        self.add_log(format!("LC_ALL={:?}", std::env::var("LC_ALL")));
-                        self.add_log(format!("LC_CTYPE={:?}", std::env::var("LC_CTYPE")));
                         let installed = match self.selected_region {
-                            None => set_auto(ctx, self.selected_style),
+                            None => {
+                                let report = set_auto_report(ctx, self.selected_style);
+                                self.log_detection(&report);
+                                report.installed
+                            }
                             Some(region) => set_with_region(ctx, region, self.selected_style),
                         };
 
@@ -173,13 +177,15 @@ impl eframe::App for MyApp {
                     }
 
                     if ui.button("Extend (Fallback Only)").clicked() {
-                        self.add_log(format!("LANG={:?}", std::env::var("LANG")));
-                        self.add_log(format!("LC_ALL={:?}", std::env::var("LC_ALL")));
-                        self.add_log(format!("LC_CTYPE={:?}", std::env::var("LC_CTYPE")));
-                        let mut defs = egui::FontDefinitions::default();
+                        let mut defs = snapshot(ctx).definitions().clone();
 
                         let installed = match self.selected_region {
-                            None => extend_auto(ctx, &mut defs, self.selected_style),
+                            None => {
+                                let report =
+                                    extend_auto_report(ctx, &mut defs, self.selected_style);
+                                self.log_detection(&report);
+                                report.installed
+                            }
                             Some(region) => {
                                 extend_with_region(ctx, &mut defs, region, self.selected_style)
                             }
@@ -198,9 +204,14 @@ impl eframe::App for MyApp {
                         ));
                     }
 
-                    if ui.button("Reset (Default)").clicked() {
-                        ctx.set_fonts(egui::FontDefinitions::default());
-                        self.add_log("Reset to egui defaults.".to_string());
+                    if ui.button("Reset (Remove System Fonts)").clicked() {
+                        let mut defs = snapshot(ctx).definitions().clone();
+                        let changed = reset_system_fonts(ctx, &mut defs);
+                        self.add_log(format!(
+                            "Reset: changed={}, user font kept={}",
+                            changed,
+                            defs.font_data.contains_key(USER_FONT_KEY)
+                        ));
                     }
                 });
             });
@@ -254,4 +265,14 @@ impl MyApp {
         println!("{}", msg);
         self.logs.push(msg);
     }
+
+    fn log_detection(&mut self, report: &egui_system_fonts::FontReport) {
+        self.add_log(format!(
+            "Detected: locale={:?} -> region={:?}",
+            report.locale, report.region
+        ));
+        for key in &report.installed_keys {
+            self.add_log(format!("  + {key}"));
+        }
+    }
 }
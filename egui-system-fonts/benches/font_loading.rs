@@ -0,0 +1,127 @@
+//! Font loading benchmarks.
+//!
+//! Run with `cargo bench --bench font_loading`, then again with `--features mmap` to compare reading font
+//! files with mapping them. The fonts are generated: `egui`'s Hack, padded to the size of a large CJK font.
+//!
+//! The multi-font cases compare reading several files in parallel with the sequential path, which a size
+//! limit no font reaches falls back to.
+
+use egui_system_fonts::{
+    clear_font_cache, extend_with_options, FontOptions, FontPreset, FontProvider, FontStyle,
+    FoundFont, FoundFontSource,
+};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// Size of each generated font file.
+const FONT_BYTES: usize = 16 << 20;
+
+/// Fonts in the multi-font cases.
+const FONTS: usize = 8;
+
+/// Timed runs per case.
+const ITERATIONS: usize = 20;
+
+/// Serves the generated font files as Latin candidates.
+struct Files(Vec<FoundFont>);
+
+impl FontProvider for Files {
+    fn find(&self, _presets: &[FontPreset], _style: FontStyle) -> Vec<FoundFont> {
+        self.0.clone()
+    }
+
+    fn locale(&self) -> Option<String> {
+        None
+    }
+}
+
+/// Writes `count` distinct font files of [`FONT_BYTES`] into `dir`.
+fn write_fonts(dir: &Path, count: usize) -> Vec<FoundFont> {
+    let hack = egui::FontDefinitions::default().font_data["Hack"]
+        .font
+        .to_vec();
+    (0..count)
+        .map(|i| {
+            let mut bytes = hack.clone();
+            // Padding after the tables is ignored by font parsers; a different fill keeps files distinct.
+            bytes.resize(FONT_BYTES, i as u8 + 1);
+            let path = dir.join(format!("Bench-{i}.ttf"));
+            std::fs::write(&path, bytes).expect("write font");
+            let family = format!("Bench Sans {i}");
+            FoundFont {
+                key: format!("system:{family}:0"),
+                family,
+                source: FoundFontSource::Path(path),
+            }
+        })
+        .collect()
+}
+
+/// Sets up `fonts` on a fresh context with `options`, reading every font again.
+fn setup(fonts: &[FoundFont], options: FontOptions) {
+    clear_font_cache();
+    let options = options.provider(Files(fonts.to_vec()));
+    let ctx = egui::Context::default();
+    let mut defs = egui::FontDefinitions::default();
+    let report = extend_with_options(
+        &ctx,
+        &mut defs,
+        vec![FontPreset::Latin],
+        FontStyle::Sans,
+        &options,
+    )
+    .expect("set up fonts");
+    assert_eq!(report.installed.len(), fonts.len());
+}
+
+/// Runs `f` [`ITERATIONS`] times and prints the first and the median run.
+fn bench(name: &str, mut f: impl FnMut()) {
+    let mut times: Vec<Duration> = (0..ITERATIONS)
+        .map(|_| {
+            let started = Instant::now();
+            f();
+            started.elapsed()
+        })
+        .collect();
+    let first = times[0];
+    times.sort();
+    println!(
+        "{name:<44} first {first:>12.3?}   median {:>12.3?}",
+        times[ITERATIONS / 2]
+    );
+}
+
+fn main() {
+    let dir: PathBuf =
+        std::env::temp_dir().join(format!("egui-system-fonts-bench-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).expect("create bench directory");
+    let mode = if cfg!(feature = "mmap") {
+        "mmap"
+    } else {
+        "read"
+    };
+
+    let one = write_fonts(&dir, 1);
+    let FoundFontSource::Path(path) = &one[0].source else {
+        unreachable!()
+    };
+    bench("fs::read, 1 font (baseline)", || {
+        std::hint::black_box(std::fs::read(path).expect("read font"));
+    });
+    bench(&format!("setup, 1 font ({mode})"), || {
+        setup(&one, FontOptions::new());
+    });
+
+    let many = write_fonts(&dir, FONTS);
+    bench(
+        &format!("setup, {FONTS} fonts, sequential ({mode})"),
+        || {
+            setup(&many, FontOptions::new().max_total_bytes(u64::MAX));
+        },
+    );
+    bench(&format!("setup, {FONTS} fonts, parallel ({mode})"), || {
+        setup(&many, FontOptions::new());
+    });
+
+    std::fs::remove_dir_all(&dir).expect("remove bench directory");
+}
@@ -0,0 +1,84 @@
+//! Fetching font bytes asynchronously, for targets without a font directory (e.g. the web).
+
+use crate::candidates::preset_candidates;
+use crate::{
+    resolve, set_with_options, FontError, FontOptions, FontPreset, FontReport, FontSelection,
+    FontStyle, StaticProvider,
+};
+use std::future::Future;
+use std::pin::Pin;
+
+/// Fetches font files by family name, e.g. from the app's assets with the browser's fetch API or from
+/// IndexedDB.
+///
+/// The crate decides which fonts to load; the provider only knows how to load them. Return `None` for
+/// families it does not have.
+pub trait FontByteProvider {
+    /// Fetches the font file of the family named `key` (e.g. `"Noto Sans KR"`).
+    fn fetch(&self, key: &str) -> Pin<Box<dyn Future<Output = Option<Vec<u8>>>>>;
+}
+
+/// Replaces `egui`'s fonts with fonts fetched from `fetcher`, once all of them have arrived.
+///
+/// Resolves the presets of `selection` like [`set_with_options`] and asks `fetcher` for each preset's
+/// candidate families (see [`preset_candidates`](crate::preset_candidates)) in priority order; the first
+/// family it has is used for that preset. Keys are fetched one after another, so a provider backed by the
+/// network downloads one font per preset rather than every candidate. The fonts are installed together
+/// once every preset has its font or has run out of candidates, so the UI changes fonts once.
+///
+/// `options` applies as for [`set_with_options`], except that its [`provider`](FontOptions::provider) is
+/// only used for the locale of [`FontSelection::Auto`].
+///
+/// # Examples
+///
+/// ```no_run
+/// # use egui_system_fonts::{set_from_byte_provider, FontByteProvider, FontOptions, FontRegion, FontStyle};
+/// # async fn demo(ctx: &egui::Context, assets: &dyn FontByteProvider) {
+/// let options = FontOptions::new();
+/// match set_from_byte_provider(ctx, FontRegion::Korean, FontStyle::Sans, assets, &options).await {
+///     Ok(report) => log::info!("installed: {:?}", report.installed),
+///     Err(e) => log::warn!("{e}"),
+/// }
+/// # }
+/// ```
+pub async fn set_from_byte_provider(
+    ctx: &egui::Context,
+    selection: impl Into<FontSelection>,
+    style: FontStyle,
+    fetcher: &dyn FontByteProvider,
+    options: &FontOptions,
+) -> Result<FontReport, FontError> {
+    let Some(plan) = resolve::plan(
+        selection.into(),
+        style,
+        options.env.as_ref(),
+        options.font_provider(),
+        options.logs(),
+    ) else {
+        return Ok(FontReport::default());
+    };
+
+    let mut provider = StaticProvider::new();
+    for preset in &plan.presets {
+        let families = match preset {
+            FontPreset::Custom(families) => families.clone(),
+            preset => preset_candidates(preset, plan.style)
+                .into_iter()
+                .map(str::to_owned)
+                .collect(),
+        };
+        for family in families {
+            if options.cancelled() {
+                return Err(crate::cancelled(options));
+            }
+            if let Some(bytes) = fetcher.fetch(&family).await {
+                log::debug!("Fetched {:?} ({} bytes)", family, bytes.len());
+                provider = provider.font(preset.clone(), family, bytes);
+                break;
+            }
+        }
+    }
+
+    let options = options.clone().provider(provider);
+    set_with_options(ctx, plan.presets, plan.style, &options)
+}
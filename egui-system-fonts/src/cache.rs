@@ -0,0 +1,111 @@
+//! Persistent cache of resolved font files, to skip discovery on later runs.
+
+use crate::{FoundFont, FoundFontSource};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+const HEADER: &str = "egui-system-fonts cache v1";
+
+/// Returns the default cache file in the platform's cache directory, if it can be determined.
+///
+/// `$XDG_CACHE_HOME` or `~/.cache` on Linux and other Unix systems, `~/Library/Caches` on macOS and
+/// `%LOCALAPPDATA%` on Windows, each followed by `egui-system-fonts/fonts.cache`.
+pub fn default_cache_path() -> Option<PathBuf> {
+    let env_dir = |name: &str| {
+        std::env::var_os(name)
+            .filter(|v| !v.is_empty())
+            .map(PathBuf::from)
+    };
+    let base = if cfg!(windows) {
+        env_dir("LOCALAPPDATA")
+    } else if cfg!(target_os = "macos") {
+        env_dir("HOME").map(|home| home.join("Library/Caches"))
+    } else {
+        env_dir("XDG_CACHE_HOME").or_else(|| env_dir("HOME").map(|home| home.join(".cache")))
+    };
+    Some(base?.join("egui-system-fonts").join("fonts.cache"))
+}
+
+/// Loads the fonts recorded for `key`, if the manifest matches and every file is unchanged.
+pub(crate) fn load(path: &Path, key: &str) -> Option<Vec<FoundFont>> {
+    let text = fs::read_to_string(path).ok()?;
+    let mut lines = text.lines();
+    if lines.next() != Some(HEADER) || lines.next() != Some(key) {
+        log::debug!("Font cache {:?} does not match; resolving", path);
+        return None;
+    }
+
+    let mut fonts = Vec::new();
+    for line in lines {
+        let mut fields = line.splitn(5, '\t');
+        let (Some(family), Some(found_key), Some(size), Some(mtime), Some(file)) = (
+            fields.next(),
+            fields.next(),
+            fields.next(),
+            fields.next(),
+            fields.next(),
+        ) else {
+            return None;
+        };
+        let file = PathBuf::from(file);
+        if stamp(&file).ok()? != (size.parse().ok()?, mtime.parse().ok()?) {
+            log::debug!("Font cache {:?} is stale: {:?} changed", path, file);
+            return None;
+        }
+        fonts.push(FoundFont {
+            family: family.to_owned(),
+            key: found_key.to_owned(),
+            source: FoundFontSource::Path(file),
+        });
+    }
+    log::debug!("Loaded {} fonts from cache {:?}", fonts.len(), path);
+    Some(fonts)
+}
+
+/// Records `fonts` for `key`, replacing the previous manifest.
+///
+/// Nothing is cached (and an existing manifest is removed) when `fonts` is empty or holds in-memory fonts.
+pub(crate) fn store(path: &Path, key: &str, fonts: &[FoundFont]) -> io::Result<()> {
+    let mut text = format!("{HEADER}\n{key}\n");
+    for f in fonts {
+        let FoundFontSource::Path(file) = &f.source else {
+            text.clear();
+            break;
+        };
+        let Ok((size, mtime)) = stamp(file) else {
+            text.clear();
+            break;
+        };
+        text.push_str(&format!(
+            "{}\t{}\t{}\t{}\t{}\n",
+            f.family,
+            f.key,
+            size,
+            mtime,
+            file.display()
+        ));
+    }
+
+    if fonts.is_empty() || text.is_empty() {
+        match fs::remove_file(path) {
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+            other => other,
+        }
+    } else {
+        path.parent()
+            .map_or(Ok(()), fs::create_dir_all)
+            .and_then(|()| fs::write(path, text))
+    }
+}
+
+/// Size and modification time (nanoseconds since the Unix epoch) of a file.
+pub(crate) fn stamp(path: &Path) -> io::Result<(u64, u128)> {
+    let meta = fs::metadata(path)?;
+    let mtime = meta
+        .modified()?
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_nanos());
+    Ok((meta.len(), mtime))
+}
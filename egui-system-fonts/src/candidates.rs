@@ -0,0 +1,324 @@
+//! Candidate family names for each preset.
+//!
+//! Mirrors the tables `system-fonts` uses internally, so sources other than the system font database can
+//! match the same families in the same order.
+
+use crate::{FontPreset, FontRegion, FontStyle, STYLES};
+
+/// Returns candidate family names for `presets`, in priority order, without repeats.
+///
+/// Serif lookups try each preset's serif families first and fall back to its sans families.
+pub(crate) fn target_families(presets: &[FontPreset], style: FontStyle) -> Vec<String> {
+    indexed_target_families(presets, style)
+        .into_iter()
+        .map(|(_, family)| family)
+        .collect()
+}
+
+/// Like [`target_families`], with each family's position in the list before repeats were dropped.
+///
+/// `system-fonts` numbers its keys (`system:<family>:<n>`) by that position.
+pub(crate) fn indexed_target_families(
+    presets: &[FontPreset],
+    style: FontStyle,
+) -> Vec<(usize, String)> {
+    let mut targets: Vec<String> = Vec::new();
+    for preset in presets {
+        if style == FontStyle::Serif {
+            targets.extend(serif_families(preset));
+        }
+        targets.extend(sans_families(preset));
+    }
+
+    let mut out: Vec<(usize, String)> = Vec::new();
+    for (i, family) in targets.into_iter().enumerate() {
+        if !out.iter().any(|(_, f)| *f == family) {
+            out.push((i, family));
+        }
+    }
+    out
+}
+
+/// Returns the family names the matcher looks for with a built-in preset, in priority order.
+///
+/// Serif lookups try the preset's serif families first and fall back to its sans families. The lists
+/// are the same on every platform: families that are not installed are simply skipped.
+/// [`FontPreset::Custom`] presets name their own families, so they return an empty list here.
+///
+/// # Examples
+///
+/// ```
+/// # use egui_system_fonts::{preset_candidates, FontPreset, FontStyle, PRESETS, STYLES};
+/// let japanese = preset_candidates(&FontPreset::Japanese, FontStyle::Serif);
+/// assert_eq!(japanese[0], "Noto Serif JP");
+/// assert!(japanese.contains(&"Noto Sans JP"));
+///
+/// for preset in PRESETS {
+///     for &style in STYLES {
+///         let families = preset_candidates(preset, style);
+///         assert!(!families.is_empty(), "{preset:?} {style:?}");
+///         let mut unique = families.clone();
+///         unique.sort_unstable();
+///         unique.dedup();
+///         assert_eq!(unique.len(), families.len(), "{preset:?} {style:?}");
+///     }
+/// }
+/// ```
+pub fn preset_candidates(preset: &FontPreset, style: FontStyle) -> Vec<&'static str> {
+    let mut out: Vec<&'static str> = Vec::new();
+    let serif = if style == FontStyle::Serif {
+        serif_table(preset)
+    } else {
+        &[]
+    };
+    for &family in serif.iter().chain(sans_table(preset)) {
+        if !out.contains(&family) {
+            out.push(family);
+        }
+    }
+    out
+}
+
+/// Returns the styles the built-in candidate lists have families of their own for in `region`.
+///
+/// Only the region's own preset counts, as with [`is_region_available`](crate::is_region_available): a
+/// style missing here would only fall back to another style's or another script's fonts. This reads the
+/// candidate data, not the installed fonts, so it is the same on every system; pair it with
+/// `is_region_available` to also check the machine. Use it to offer only meaningful combinations in a
+/// picker.
+///
+/// # Examples
+///
+/// ```
+/// # use egui_system_fonts::{supported_styles, FontRegion, FontStyle, REGIONS};
+/// assert_eq!(supported_styles(FontRegion::Japanese), [FontStyle::Sans, FontStyle::Serif]);
+///
+/// for &region in REGIONS {
+///     assert!(supported_styles(region).contains(&FontStyle::Sans), "{region:?}");
+/// }
+/// ```
+pub fn supported_styles(region: FontRegion) -> Vec<FontStyle> {
+    let Some(primary) = system_fonts::presets_for_region(region).into_iter().next() else {
+        return Vec::new();
+    };
+    STYLES
+        .iter()
+        .copied()
+        .filter(|&style| match style {
+            FontStyle::Sans => !sans_table(&primary).is_empty(),
+            FontStyle::Serif => !serif_table(&primary).is_empty(),
+        })
+        .collect()
+}
+
+/// Returns the family names of pan-CJK fonts (one file covering Chinese, Japanese and Korean), in the
+/// priority order of `region`'s presets.
+///
+/// Each pan-CJK font is installed under one name per region, which only picks the default glyph forms, so
+/// the region's own name comes first. Serif lookups try the serif fonts first and fall back to sans.
+pub(crate) fn pan_cjk_families(region: FontRegion, style: FontStyle) -> Vec<String> {
+    let mut bases = vec!["Sans"];
+    if style == FontStyle::Serif {
+        bases.insert(0, "Serif");
+    }
+    let presets = system_fonts::presets_for_region(region);
+    let mut out = Vec::new();
+    for base in bases {
+        for preset in &presets {
+            let (noto, source_han): (&str, &[&str]) = match preset {
+                FontPreset::Korean => ("KR", &["K", "KR"]),
+                FontPreset::Japanese => ("JP", &["", "JP"]),
+                FontPreset::SimplifiedChinese => ("SC", &["SC", "CN"]),
+                FontPreset::TraditionalChinese => ("TC", &["TC", "TW"]),
+                _ => continue,
+            };
+            out.push(format!("Noto {base} CJK {noto}"));
+            for suffix in source_han {
+                out.push(format!("Source Han {base} {suffix}").trim_end().to_owned());
+            }
+        }
+    }
+    out
+}
+
+/// Returns the built-in preset whose candidate lists name `family`.
+///
+/// CJK presets are checked before Cyrillic and Latin, which share some families.
+pub(crate) fn builtin_preset_of(family: &str) -> Option<FontPreset> {
+    [
+        FontPreset::Korean,
+        FontPreset::Japanese,
+        FontPreset::SimplifiedChinese,
+        FontPreset::TraditionalChinese,
+        FontPreset::Cyrillic,
+        FontPreset::Latin,
+    ]
+    .into_iter()
+    .find(|preset| {
+        sans_families(preset)
+            .iter()
+            .chain(&serif_families(preset))
+            .chain(&monospace_families(preset))
+            .any(|f| f.eq_ignore_ascii_case(family))
+    })
+}
+
+/// Returns the style of the built-in candidate lists that name `family`, serif lists first.
+pub(crate) fn builtin_style_of(family: &str) -> Option<FontStyle> {
+    let builtin = [
+        FontPreset::Latin,
+        FontPreset::Korean,
+        FontPreset::SimplifiedChinese,
+        FontPreset::TraditionalChinese,
+        FontPreset::Japanese,
+        FontPreset::Cyrillic,
+    ];
+    let named = |families: Vec<String>| families.iter().any(|f| f.eq_ignore_ascii_case(family));
+    if builtin.iter().any(|p| named(serif_families(p))) {
+        Some(FontStyle::Serif)
+    } else if builtin.iter().any(|p| named(sans_families(p))) {
+        Some(FontStyle::Sans)
+    } else {
+        None
+    }
+}
+
+/// Returns fixed-pitch families for `p`'s script, for `FontFamily::Monospace`.
+///
+/// Only CJK presets have entries: Latin and Cyrillic are covered by `egui`'s own monospace font.
+/// Programming fonts whose CJK glyphs are exactly two ASCII cells wide (D2Coding, Sarasa Mono) come
+/// first, then the general monospaced CJK families.
+pub(crate) fn monospace_families(p: &FontPreset) -> Vec<String> {
+    monospace_table(p).iter().map(|f| f.to_string()).collect()
+}
+
+fn sans_families(p: &FontPreset) -> Vec<String> {
+    match p {
+        FontPreset::Custom(list) => list.clone(),
+        _ => sans_table(p).iter().map(|f| f.to_string()).collect(),
+    }
+}
+
+fn serif_families(p: &FontPreset) -> Vec<String> {
+    match p {
+        FontPreset::Custom(list) => list.clone(),
+        _ => serif_table(p).iter().map(|f| f.to_string()).collect(),
+    }
+}
+
+fn monospace_table(p: &FontPreset) -> &'static [&'static str] {
+    match p {
+        FontPreset::Korean => &[
+            "D2Coding",
+            "Sarasa Mono K",
+            "Noto Sans Mono CJK KR",
+            "NanumGothicCoding",
+            "GulimChe",
+        ],
+        FontPreset::SimplifiedChinese => &["Sarasa Mono SC", "Noto Sans Mono CJK SC", "NSimSun"],
+        FontPreset::TraditionalChinese => &["Sarasa Mono TC", "Noto Sans Mono CJK TC", "MingLiU"],
+        FontPreset::Japanese => &[
+            "Sarasa Mono J",
+            "Noto Sans Mono CJK JP",
+            "MS Gothic",
+            "Osaka-Mono",
+        ],
+        _ => &[],
+    }
+}
+
+fn sans_table(p: &FontPreset) -> &'static [&'static str] {
+    match p {
+        FontPreset::Latin => &[
+            "Noto Sans",
+            "Segoe UI",
+            "Arial",
+            "SF Pro Text",
+            "Helvetica Neue",
+            "DejaVu Sans",
+            "Liberation Sans",
+            "Roboto",
+        ],
+        FontPreset::Korean => &[
+            "Noto Sans KR",
+            "Noto Sans CJK KR",
+            "Malgun Gothic",
+            "Apple SD Gothic Neo",
+            "NanumGothic",
+        ],
+        FontPreset::SimplifiedChinese => &[
+            "Noto Sans SC",
+            "Noto Sans CJK SC",
+            "Microsoft YaHei",
+            "PingFang SC",
+            "SimHei",
+            "SimSun",
+        ],
+        FontPreset::TraditionalChinese => &[
+            "Noto Sans TC",
+            "Noto Sans CJK TC",
+            "Microsoft JhengHei",
+            "PingFang TC",
+        ],
+        FontPreset::Japanese => &[
+            "Noto Sans JP",
+            "Noto Sans CJK JP",
+            "Yu Gothic",
+            "Hiragino Sans",
+            "Meiryo",
+        ],
+        FontPreset::Cyrillic => &[
+            "Noto Sans",
+            "DejaVu Sans",
+            "Segoe UI",
+            "Arial",
+            "Tahoma",
+            "Times New Roman",
+        ],
+        _ => &[],
+    }
+}
+
+fn serif_table(p: &FontPreset) -> &'static [&'static str] {
+    match p {
+        FontPreset::Latin => &[
+            "Noto Serif",
+            "Times New Roman",
+            "Georgia",
+            "Liberation Serif",
+            "DejaVu Serif",
+            "Times",
+        ],
+        FontPreset::Korean => &[
+            "Noto Serif KR",
+            "Noto Serif CJK KR",
+            "Batang",
+            "AppleMyungjo",
+            "NanumMyeongjo",
+        ],
+        FontPreset::SimplifiedChinese => {
+            &["Noto Serif SC", "Noto Serif CJK SC", "Songti SC", "SimSun"]
+        }
+        FontPreset::TraditionalChinese => &[
+            "Noto Serif TC",
+            "Noto Serif CJK TC",
+            "Songti TC",
+            "PMingLiU",
+        ],
+        FontPreset::Japanese => &[
+            "Noto Serif JP",
+            "Noto Serif CJK JP",
+            "Yu Mincho",
+            "Hiragino Mincho ProN",
+            "MS Mincho",
+        ],
+        FontPreset::Cyrillic => &[
+            "Noto Serif",
+            "Times New Roman",
+            "Georgia",
+            "Liberation Serif",
+            "DejaVu Serif",
+        ],
+        _ => &[],
+    }
+}
@@ -0,0 +1,176 @@
+//! Read-only view of the fonts `egui` consults for each font family.
+
+use crate::{is_system_font_key, snapshot, FontKey, FoundFontSource};
+use egui::{FontDefinitions, FontFamily};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::{self, Write as _};
+use std::path::PathBuf;
+use std::sync::{OnceLock, PoisonError, RwLock};
+
+/// Where a font installed by this crate came from: its family and file (`None` for in-memory fonts).
+type Origins = RwLock<HashMap<String, (String, Option<PathBuf>)>>;
+
+fn origins() -> &'static Origins {
+    static ORIGINS: OnceLock<Origins> = OnceLock::new();
+    ORIGINS.get_or_init(Default::default)
+}
+
+/// Remembers where the font installed under `key` was read from, for [`describe_current`].
+pub(crate) fn record_origin(key: &str, family: &str, source: &FoundFontSource) {
+    let path = match source {
+        FoundFontSource::Path(path) => Some(path.clone()),
+        FoundFontSource::Bytes(_) => None,
+    };
+    origins()
+        .write()
+        .unwrap_or_else(PoisonError::into_inner)
+        .insert(key.to_owned(), (family.to_owned(), path));
+}
+
+/// Result of [`fallback_chain`]: the fonts of each font family, in the order `egui` consults them.
+///
+/// Its `Display` output is meant for logs and bug reports.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FallbackChain {
+    /// Fonts of each font family, in priority order.
+    pub families: BTreeMap<FontFamily, Vec<ChainEntry>>,
+}
+
+/// One font in a [`FallbackChain`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ChainEntry {
+    /// The `font_data` key.
+    pub key: FontKey,
+    /// Family name from the font's `name` table (typographic family if present), or `None` if the data
+    /// could not be parsed or the key has no data.
+    pub name: Option<String>,
+    /// Size of the font data in bytes (0 if the key has no data).
+    pub bytes: usize,
+}
+
+/// Lists the fonts `egui` will consult for each font family of `defs`, in order.
+///
+/// Works on any definitions, including ones built without this crate. Nothing is applied.
+///
+/// # Examples
+///
+/// ```
+/// # use egui_system_fonts::fallback_chain;
+/// println!("{}", fallback_chain(&egui::FontDefinitions::default()));
+/// ```
+pub fn fallback_chain(defs: &FontDefinitions) -> FallbackChain {
+    let families = defs
+        .families
+        .iter()
+        .map(|(family, keys)| {
+            let entries = keys
+                .iter()
+                .map(|key| {
+                    let data = defs.font_data.get(key);
+                    ChainEntry {
+                        key: FontKey::from(key.as_str()),
+                        name: data.and_then(|d| {
+                            let face = ttf_parser::Face::parse(&d.font, d.index).ok()?;
+                            family_name(&face)
+                        }),
+                        bytes: data.map_or(0, |d| d.font.len()),
+                    }
+                })
+                .collect();
+            (family.clone(), entries)
+        })
+        .collect();
+    FallbackChain { families }
+}
+
+/// Reads the family name from a face's `name` table, preferring the typographic family.
+pub(crate) fn family_name(face: &ttf_parser::Face<'_>) -> Option<String> {
+    first_name(
+        face,
+        &[
+            ttf_parser::name_id::TYPOGRAPHIC_FAMILY,
+            ttf_parser::name_id::FAMILY,
+        ],
+    )
+}
+
+/// Returns the first readable `name` table entry among `ids`, in order.
+pub(crate) fn first_name(face: &ttf_parser::Face<'_>, ids: &[u16]) -> Option<String> {
+    let names = face.names();
+    ids.iter().find_map(|&id| {
+        names
+            .into_iter()
+            .filter(|name| name.name_id == id)
+            .find_map(|name| name.to_string())
+    })
+}
+
+impl fmt::Display for FallbackChain {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (family, entries) in &self.families {
+            writeln!(f, "{family:?}:")?;
+            if entries.is_empty() {
+                writeln!(f, "  (no fonts)")?;
+            }
+            for (i, entry) in entries.iter().enumerate() {
+                let name = entry.name.as_deref().unwrap_or("<unreadable>");
+                writeln!(
+                    f,
+                    "  {}. {}: {} ({} bytes)",
+                    i + 1,
+                    entry.key,
+                    name,
+                    entry.bytes
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Describes the fonts currently applied to `ctx`, for pasting into bug reports.
+///
+/// Lists the Proportional and Monospace priority lists (see [`snapshot`](crate::snapshot())). Fonts
+/// installed by this crate show their family, source file and size; other fonts only their key.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use egui_system_fonts::{describe_current};
+/// # fn demo(ctx: &egui::Context) {
+/// log::debug!("fonts in use:\n{}", describe_current(ctx));
+/// # }
+/// ```
+pub fn describe_current(ctx: &egui::Context) -> String {
+    let snapshot = snapshot::snapshot(ctx);
+    let defs = snapshot.definitions();
+    let origins = origins().read().unwrap_or_else(PoisonError::into_inner);
+
+    let mut out = String::new();
+    for family in [FontFamily::Proportional, FontFamily::Monospace] {
+        let _ = writeln!(out, "{family:?}:");
+        let keys = defs.families.get(&family).map(Vec::as_slice).unwrap_or(&[]);
+        if keys.is_empty() {
+            let _ = writeln!(out, "  (no fonts)");
+        }
+        for (i, key) in keys.iter().enumerate() {
+            let bytes = defs.font_data.get(key).map_or(0, |d| d.font.len());
+            match origins.get(key).filter(|_| is_system_font_key(key)) {
+                Some((name, path)) => {
+                    let source = path
+                        .as_ref()
+                        .map_or_else(|| "<memory>".to_owned(), |p| p.display().to_string());
+                    let _ = writeln!(out, "  {}. {name} {source} ({bytes} bytes)", i + 1);
+                }
+                None => {
+                    let _ = writeln!(
+                        out,
+                        "  {}. {key} (not installed by egui-system-fonts)",
+                        i + 1
+                    );
+                }
+            }
+        }
+    }
+    out
+}
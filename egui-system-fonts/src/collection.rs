@@ -0,0 +1,194 @@
+//! Font collections (`.ttc`/`.otc`), and cutting the installed face out of one (`ttc-extract` feature).
+
+#[cfg(feature = "ttc-extract")]
+use crate::read::{read_u16, read_u32, validate_sfnt};
+#[cfg(feature = "ttc-extract")]
+use std::borrow::Cow;
+
+/// `ttcf`, the tag of a font collection.
+pub(crate) const COLLECTION_TAG: u32 = 0x7474_6366;
+
+/// `head`, the tag of the font header table.
+#[cfg(feature = "ttc-extract")]
+const HEAD_TAG: u32 = 0x6865_6164;
+
+/// Returns `bytes` as a standalone font holding only the first face, the one `egui` is given.
+///
+/// A collection can hold a dozen faces (Apple's `.ttc` files do), but `egui` keeps whatever it is handed in
+/// memory. Fonts that are not collections, and collections that cannot be split into a valid font, are
+/// returned as they are.
+#[cfg(feature = "ttc-extract")]
+pub(crate) fn first_face(bytes: Cow<'static, [u8]>, family: &str) -> Cow<'static, [u8]> {
+    if read_u32(&bytes, 0) != Some(COLLECTION_TAG) {
+        return bytes;
+    }
+    let extracted = extract_face(&bytes, 0).filter(|face| match validate_sfnt(face) {
+        Ok(()) => true,
+        Err(reason) => {
+            log::debug!("Extracted face of {:?} is invalid: {:?}", family, reason);
+            false
+        }
+    });
+    match extracted {
+        Some(face) => {
+            log::debug!(
+                "Extracted the first face of {:?} ({} of {} bytes)",
+                family,
+                face.len(),
+                bytes.len()
+            );
+            Cow::Owned(face)
+        }
+        None => {
+            log::debug!(
+                "Could not extract a face of {:?}; keeping the collection",
+                family
+            );
+            bytes
+        }
+    }
+}
+
+/// Copies the tables of face `index` of a collection into a new font file.
+///
+/// Tables shared with other faces are copied too, so the result stands on its own. The `head` table's
+/// checksum adjustment is recomputed for the new file.
+#[cfg(feature = "ttc-extract")]
+fn extract_face(data: &[u8], index: u32) -> Option<Vec<u8>> {
+    let faces = read_u32(data, 8)?;
+    if index >= faces {
+        return None;
+    }
+    let offset = read_u32(data, (index as usize).checked_mul(4)?.checked_add(12)?)? as usize;
+    let version = read_u32(data, offset)?;
+    let num_tables = read_u16(data, offset.checked_add(4)?)?;
+
+    let mut tables = Vec::with_capacity(usize::from(num_tables));
+    for i in 0..usize::from(num_tables) {
+        let record = offset.checked_add(12 + 16 * i)?;
+        let tag = read_u32(data, record)?;
+        let checksum = read_u32(data, record.checked_add(4)?)?;
+        let start = read_u32(data, record.checked_add(8)?)? as usize;
+        let len = read_u32(data, record.checked_add(12)?)? as usize;
+        let table = data.get(start..start.checked_add(len)?)?;
+        tables.push((tag, checksum, table));
+    }
+
+    // Binary search hints of the table directory (OpenType spec, "Table Directory").
+    let entry_selector = num_tables.checked_ilog2().unwrap_or(0) as u16;
+    let search_range = (1u16 << entry_selector).checked_mul(16)?;
+    let range_shift = num_tables.checked_mul(16)? - search_range;
+
+    let header_len = 12 + 16 * tables.len();
+    let body_len: usize = tables.iter().map(|(_, _, t)| padded(t.len())).sum();
+    let mut out = Vec::with_capacity(header_len + body_len);
+    out.extend_from_slice(&version.to_be_bytes());
+    for value in [num_tables, search_range, entry_selector, range_shift] {
+        out.extend_from_slice(&value.to_be_bytes());
+    }
+
+    let mut next = header_len;
+    for (tag, checksum, table) in &tables {
+        out.extend_from_slice(&tag.to_be_bytes());
+        out.extend_from_slice(&checksum.to_be_bytes());
+        out.extend_from_slice(&u32::try_from(next).ok()?.to_be_bytes());
+        out.extend_from_slice(&u32::try_from(table.len()).ok()?.to_be_bytes());
+        next += padded(table.len());
+    }
+    let mut head = None;
+    for (tag, _, table) in &tables {
+        if *tag == HEAD_TAG {
+            head = Some(out.len());
+        }
+        out.extend_from_slice(table);
+        out.resize(padded(out.len()), 0);
+    }
+    if let Some(head) = head {
+        set_checksum_adjustment(&mut out, head)?;
+    }
+    Some(out)
+}
+
+/// Sets `checkSumAdjustment` of the `head` table at `head` so the whole font sums to the magic value of
+/// the OpenType spec ("head - Font Header Table").
+#[cfg(feature = "ttc-extract")]
+fn set_checksum_adjustment(font: &mut [u8], head: usize) -> Option<()> {
+    let field = font.get_mut(head.checked_add(8)?..head.checked_add(12)?)?;
+    field.fill(0);
+    let sum = font
+        .chunks(4)
+        .map(|word| {
+            let mut padded = [0; 4];
+            padded[..word.len()].copy_from_slice(word);
+            u32::from_be_bytes(padded)
+        })
+        .fold(0u32, u32::wrapping_add);
+    let adjustment = 0xB1B0_AFBAu32.wrapping_sub(sum);
+    font[head + 8..head + 12].copy_from_slice(&adjustment.to_be_bytes());
+    Some(())
+}
+
+/// Tables start on 4-byte boundaries.
+#[cfg(feature = "ttc-extract")]
+fn padded(len: usize) -> usize {
+    len.div_ceil(4) * 4
+}
+
+#[cfg(all(test, feature = "ttc-extract"))]
+mod tests {
+    use super::*;
+
+    /// Builds a collection of `fonts` (single-face fonts), sharing no tables between them.
+    fn collection(fonts: &[&[u8]]) -> Vec<u8> {
+        let header_len = 12 + 4 * fonts.len();
+        let mut out = Vec::new();
+        out.extend_from_slice(&COLLECTION_TAG.to_be_bytes());
+        out.extend_from_slice(&0x0001_0000u32.to_be_bytes());
+        out.extend_from_slice(&(fonts.len() as u32).to_be_bytes());
+        out.resize(header_len, 0);
+        for (i, font) in fonts.iter().enumerate() {
+            let offset = out.len();
+            out[12 + 4 * i..16 + 4 * i].copy_from_slice(&(offset as u32).to_be_bytes());
+            let num_tables = usize::from(read_u16(font, 4).unwrap());
+            let directory_len = 12 + 16 * num_tables;
+            out.extend_from_slice(&font[..directory_len]);
+            for t in 0..num_tables {
+                let record = 12 + 16 * t;
+                let start = read_u32(font, record + 8).unwrap() as usize;
+                let len = read_u32(font, record + 12).unwrap() as usize;
+                let moved = out.len() as u32;
+                let field = offset + record + 8;
+                out[field..field + 4].copy_from_slice(&moved.to_be_bytes());
+                out.extend_from_slice(&font[start..start + len]);
+                out.resize(padded(out.len()), 0);
+            }
+        }
+        out
+    }
+
+    fn family(font: &[u8]) -> Option<String> {
+        let face = ttf_parser::Face::parse(font, 0).ok()?;
+        crate::chain::family_name(&face)
+    }
+
+    #[test]
+    fn extracts_first_face_of_two() {
+        let defaults = egui::FontDefinitions::default();
+        let hack = &defaults.font_data["Hack"].font;
+        let ubuntu = &defaults.font_data["Ubuntu-Light"].font;
+        let ttc = collection(&[hack, ubuntu]);
+        assert_eq!(ttf_parser::fonts_in_collection(&ttc), Some(2));
+
+        let face = first_face(Cow::Owned(ttc.clone()), "Hack");
+        assert!(face.len() < ttc.len());
+        assert_eq!(read_u32(&face, 0), Some(0x0001_0000));
+        assert!(validate_sfnt(&face).is_ok());
+        assert_eq!(family(&face), family(hack));
+
+        let sum = face
+            .chunks(4)
+            .map(|word| u32::from_be_bytes(word.try_into().unwrap()))
+            .fold(0u32, u32::wrapping_add);
+        assert_eq!(sum, 0xB1B0_AFBA);
+    }
+}
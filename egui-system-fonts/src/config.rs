@@ -0,0 +1,193 @@
+//! Declarative font setup, e.g. from a settings file.
+
+use crate::{
+    extend_with_options, global_defaults, lenient, set_with_options, snapshot, FontOptions,
+    FontRegion, FontReport, FontSelection, FontStyle,
+};
+
+/// Font setup declared as data.
+///
+/// With the `serde` feature this (de)serializes with every field optional, so older config files keep
+/// working as fields are added. Regions and styles are written by name (`"Korean"`, `"Serif"`); the region
+/// `"auto"` detects it from the system locale.
+///
+/// ```toml
+/// region = "Japanese"
+/// style = "Serif"
+/// mode = "extend"
+/// families = ["BIZ UDMincho"]
+/// max_bytes = 50_000_000
+/// monospace = false
+/// ```
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "serde")] {
+/// # use egui_system_fonts::{ConfigMode, FontConfig, FontRegion, FontStyle};
+/// let config: FontConfig = serde_json::from_str(r#"{ "region": "korean", "mode": "extend" }"#).unwrap();
+/// assert_eq!(config.region, Some(FontRegion::Korean));
+/// assert_eq!(config.style, FontStyle::Sans);
+/// assert_eq!(config.mode, ConfigMode::Extend);
+/// assert_eq!(config.monospace, None);
+///
+/// let json = serde_json::to_string(&config).unwrap();
+/// assert_eq!(serde_json::from_str::<FontConfig>(&json).unwrap(), config);
+/// # }
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct FontConfig {
+    /// Region to load fonts for; `None` (`"auto"`) detects it from the system locale.
+    #[cfg_attr(feature = "serde", serde(with = "serde_names::region"))]
+    pub region: Option<FontRegion>,
+    /// Preferred style.
+    #[cfg_attr(feature = "serde", serde(with = "serde_names::style"))]
+    pub style: FontStyle,
+    /// Whether to replace `egui`'s fonts or append fallbacks.
+    pub mode: ConfigMode,
+    /// Family names to try before the region's candidates.
+    pub families: Vec<String>,
+    /// Limit on the combined size of the installed fonts, in bytes.
+    pub max_bytes: Option<u64>,
+    /// Whether fonts are also added to the Monospace family; `None` leaves it to the style (see
+    /// [`FontOptions::monospace`]).
+    pub monospace: Option<bool>,
+}
+
+impl Default for FontConfig {
+    fn default() -> Self {
+        Self {
+            region: None,
+            style: FontStyle::Sans,
+            mode: ConfigMode::Replace,
+            families: Vec::new(),
+            max_bytes: None,
+            monospace: None,
+        }
+    }
+}
+
+/// How [`apply_config`] installs fonts.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum ConfigMode {
+    /// Replace `egui`'s fonts, like the `set_*` functions.
+    #[default]
+    Replace,
+    /// Append fallbacks to the fonts currently applied to the context, like the `extend_*` functions.
+    Extend,
+}
+
+/// Sets up fonts as described by `config`.
+///
+/// In [`ConfigMode::Extend`] the fonts are appended to the definitions currently applied to `ctx` (see
+/// [`snapshot`](crate::snapshot())).
+///
+/// # Examples
+///
+/// ```no_run
+/// # use egui_system_fonts::{apply_config, FontConfig};
+/// # fn demo(ctx: &egui::Context, settings: FontConfig) {
+/// let report = apply_config(ctx, &settings);
+/// println!("installed: {:?}", report.installed);
+/// # }
+/// ```
+pub fn apply_config(ctx: &egui::Context, config: &FontConfig) -> FontReport {
+    apply_config_with_options(ctx, config, &global_defaults())
+}
+
+/// Like [`apply_config`], with `config` layered on top of `options`.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use egui_system_fonts::{apply_config_with_options, FontConfig, FontOptions, FontRegion};
+/// # fn demo(ctx: &egui::Context) {
+/// let config = FontConfig { region: Some(FontRegion::Korean), ..Default::default() };
+/// let report = apply_config_with_options(ctx, &config, &FontOptions::new());
+/// println!("installed: {:?}", report.installed);
+/// # }
+/// ```
+pub fn apply_config_with_options(
+    ctx: &egui::Context,
+    config: &FontConfig,
+    options: &FontOptions,
+) -> FontReport {
+    let mut options = options
+        .clone()
+        .prefer_families(config.families.iter().cloned());
+    if let Some(monospace) = config.monospace {
+        options = options.monospace(monospace);
+    }
+    if let Some(max) = config.max_bytes {
+        options = options.max_total_bytes(max);
+    }
+    let selection = match config.region {
+        Some(region) => FontSelection::Region(region),
+        None => FontSelection::Auto,
+    };
+
+    match config.mode {
+        ConfigMode::Replace => lenient(set_with_options(ctx, selection, config.style, &options)),
+        ConfigMode::Extend => {
+            let mut defs = snapshot::snapshot(ctx).definitions().clone();
+            lenient(extend_with_options(
+                ctx,
+                &mut defs,
+                selection,
+                config.style,
+                &options,
+            ))
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+mod serde_names {
+    use serde::de::Error;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub(super) mod region {
+        use super::*;
+        use crate::names::parse_region;
+        use crate::FontRegion;
+
+        pub(crate) fn serialize<S: Serializer>(
+            region: &Option<FontRegion>,
+            s: S,
+        ) -> Result<S::Ok, S::Error> {
+            match region {
+                Some(region) => s.serialize_str(&format!("{region:?}")),
+                None => s.serialize_str("auto"),
+            }
+        }
+
+        pub(crate) fn deserialize<'de, D: Deserializer<'de>>(
+            d: D,
+        ) -> Result<Option<FontRegion>, D::Error> {
+            let name = String::deserialize(d)?;
+            if name.trim().eq_ignore_ascii_case("auto") {
+                return Ok(None);
+            }
+            parse_region(&name).map(Some).map_err(D::Error::custom)
+        }
+    }
+
+    pub(super) mod style {
+        use super::*;
+        use crate::names::parse_style;
+        use crate::FontStyle;
+
+        pub(crate) fn serialize<S: Serializer>(style: &FontStyle, s: S) -> Result<S::Ok, S::Error> {
+            s.serialize_str(&format!("{style:?}"))
+        }
+
+        pub(crate) fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<FontStyle, D::Error> {
+            let name = String::deserialize(d)?;
+            parse_style(&name).map_err(D::Error::custom)
+        }
+    }
+}
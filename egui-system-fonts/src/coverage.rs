@@ -0,0 +1,354 @@
+//! Glyph coverage queries against loaded font data and candidate font files.
+
+use crate::candidates::builtin_style_of;
+use crate::collection::COLLECTION_TAG;
+use crate::read::{read_u16, read_u32};
+use crate::{FontRegion, FontStyle, FoundFont, FoundFontSource};
+use egui::{FontData, FontDefinitions, FontFamily};
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock, PoisonError};
+
+/// Parsed faces of a font chain, queried by character.
+pub(crate) struct FaceSet<'a> {
+    faces: Vec<ttf_parser::Face<'a>>,
+}
+
+impl<'a> FaceSet<'a> {
+    /// Parses every font in `data`. Fonts that fail to parse are skipped.
+    pub(crate) fn new(data: impl IntoIterator<Item = &'a FontData>) -> Self {
+        let faces = data
+            .into_iter()
+            .filter_map(|d| ttf_parser::Face::parse(&d.font, d.index).ok())
+            .collect();
+        Self { faces }
+    }
+
+    /// Parses the fonts that `family` lists in `defs`, in priority order.
+    pub(crate) fn for_family(defs: &'a FontDefinitions, family: &FontFamily) -> Self {
+        let keys = defs.families.get(family).map(Vec::as_slice).unwrap_or(&[]);
+        Self::new(
+            keys.iter()
+                .filter_map(|key| defs.font_data.get(key))
+                .map(|data| &**data),
+        )
+    }
+
+    /// Returns `true` if any face maps `c` to a glyph.
+    pub(crate) fn covers(&self, c: char) -> bool {
+        self.faces.iter().any(|face| face.glyph_index(c).is_some())
+    }
+}
+
+/// Returns `true` if `data` is a fixed-pitch font.
+///
+/// Checks the `post` table's `isFixedPitch` flag, then compares the advances of a few ASCII glyphs: CJK
+/// monospace fonts often leave the flag unset because their ideographs are double width.
+pub(crate) fn is_fixed_pitch(data: &FontData) -> bool {
+    ttf_parser::Face::parse(&data.font, data.index).is_ok_and(|face| face_is_fixed_pitch(&face))
+}
+
+/// [`is_fixed_pitch`] for a parsed face.
+pub(crate) fn face_is_fixed_pitch(face: &ttf_parser::Face<'_>) -> bool {
+    const SAMPLE: &str = "iMW0.";
+    if face.is_monospaced() {
+        return true;
+    }
+    let advances: Vec<u16> = SAMPLE
+        .chars()
+        .filter_map(|c| face.glyph_hor_advance(face.glyph_index(c)?))
+        .collect();
+    advances.len() == SAMPLE.len() && advances[0] > 0 && advances.iter().all(|&a| a == advances[0])
+}
+
+/// Returns `true` for characters that never need a glyph.
+pub(crate) fn is_ignorable(c: char) -> bool {
+    c.is_control() || c.is_whitespace()
+}
+
+/// Returns the characters of `text` that no font in `family` can render.
+///
+/// Every font in the family's priority list is checked via its `cmap` table, using the bytes already held in
+/// `defs.font_data`. Control characters and whitespace are ignored; each missing character is reported
+/// once, in order of first appearance.
+///
+/// # Examples
+///
+/// ```
+/// # use egui_system_fonts::uncovered_chars;
+/// let defs = egui::FontDefinitions::default();
+/// let missing = uncovered_chars(&defs, &egui::FontFamily::Proportional, "Hello\n한글 𝔘");
+/// assert_eq!(missing, ['한', '글', '𝔘']);
+/// ```
+pub fn uncovered_chars(defs: &FontDefinitions, family: &FontFamily, text: &str) -> Vec<char> {
+    let faces = FaceSet::for_family(defs, family);
+
+    let mut missing = Vec::new();
+    for c in text.chars() {
+        if is_ignorable(c) || missing.contains(&c) || faces.covers(c) {
+            continue;
+        }
+        missing.push(c);
+    }
+    missing
+}
+
+/// Returns the share (0.0 to 1.0) of a representative character sample for `region` that the
+/// Proportional fonts of `defs` can render.
+///
+/// Works like [`uncovered_chars`]: faces are parsed in place from the bytes already in `defs.font_data`,
+/// which only reads their table directories, so probing a dozen regions in one frame is cheap. Regions
+/// without a sample of their own (such as `FontRegion::Unknown`) use the Latin one.
+///
+/// # Examples
+///
+/// ```
+/// # use egui_system_fonts::{script_coverage, FontRegion};
+/// let defs = egui::FontDefinitions::default();
+/// assert_eq!(script_coverage(&defs, FontRegion::Latin), 1.0);
+/// assert!(script_coverage(&defs, FontRegion::Korean) < 0.5);
+/// ```
+pub fn script_coverage(defs: &FontDefinitions, region: FontRegion) -> f32 {
+    let faces = FaceSet::for_family(defs, &FontFamily::Proportional);
+    let sample = region_sample(region);
+    let covered = sample.chars().filter(|&c| faces.covers(c)).count();
+    covered as f32 / sample.chars().count() as f32
+}
+
+/// Returns `true` if the Proportional fonts of `defs` render every character of `region`'s sample.
+///
+/// See [`script_coverage`] for the partial measure.
+///
+/// # Examples
+///
+/// ```
+/// # use egui_system_fonts::{is_script_covered, FontRegion};
+/// let defs = egui::FontDefinitions::default();
+/// assert!(is_script_covered(&defs, FontRegion::Latin));
+/// assert!(!is_script_covered(&defs, FontRegion::Japanese));
+/// ```
+pub fn is_script_covered(defs: &FontDefinitions, region: FontRegion) -> bool {
+    script_coverage(defs, region) >= 1.0
+}
+
+/// The characters of `region`'s sample that no Proportional font of `defs` renders.
+pub(crate) fn missing_sample(defs: &FontDefinitions, region: FontRegion) -> Vec<char> {
+    let faces = FaceSet::for_family(defs, &FontFamily::Proportional);
+    region_sample(region)
+        .chars()
+        .filter(|&c| !faces.covers(c))
+        .collect()
+}
+
+/// Common characters of each region's script, without whitespace.
+fn region_sample(region: FontRegion) -> &'static str {
+    match region {
+        FontRegion::Korean => "한글가나다라마바사아자차카타파하의는을",
+        FontRegion::Japanese => "あいうえおかなをアイウエオカナヲ日本語漢字",
+        FontRegion::SimplifiedChinese => "简体中文汉字这个们说时国见长",
+        FontRegion::TraditionalChinese => "繁體中文漢字這個們說時國見長",
+        FontRegion::Cyrillic => {
+            "АБВГДЕЁЖЗИЙКЛМНОПРСТУФХЦЧШЩЪЫЬЭЮЯабвгдеёжзийклмнопрстуфхцчшщъыьэюя"
+        }
+        _ => "AaBbGgMmQqWwZz0123456789.,!?",
+    }
+}
+
+/// Code points a font maps to glyphs, as sorted, disjoint ranges.
+#[derive(Debug, Default)]
+struct Charset(Vec<(u32, u32)>);
+
+impl Charset {
+    fn parse(bytes: &[u8]) -> Self {
+        match ttf_parser::Face::parse(bytes, 0) {
+            Ok(face) => Self::from_cmap(face.tables().cmap),
+            Err(_) => Self::default(),
+        }
+    }
+
+    fn from_cmap(cmap: Option<ttf_parser::cmap::Table<'_>>) -> Self {
+        let mut points = Vec::new();
+        for subtable in cmap.iter().flat_map(|cmap| cmap.subtables) {
+            if subtable.is_unicode() {
+                subtable.codepoints(|cp| {
+                    if subtable.glyph_index(cp).is_some_and(|g| g.0 != 0) {
+                        points.push(cp);
+                    }
+                });
+            }
+        }
+        points.sort_unstable();
+
+        let mut ranges: Vec<(u32, u32)> = Vec::new();
+        for cp in points {
+            match ranges.last_mut() {
+                Some((_, end)) if cp <= *end + 1 => *end = (*end).max(cp),
+                _ => ranges.push((cp, cp)),
+            }
+        }
+        Self(ranges)
+    }
+
+    fn contains(&self, c: char) -> bool {
+        let cp = c as u32;
+        let i = self.0.partition_point(|&(_, end)| end < cp);
+        self.0.get(i).is_some_and(|&(start, _)| start <= cp)
+    }
+}
+
+/// Charsets of font files, with the size and modification time they were read at.
+type CharsetCache = Mutex<HashMap<PathBuf, (Option<(u64, u128)>, Arc<Charset>)>>;
+
+fn charset_cache() -> &'static CharsetCache {
+    static CACHE: OnceLock<CharsetCache> = OnceLock::new();
+    CACHE.get_or_init(Default::default)
+}
+
+/// The charset of a candidate font, read from its file once and reread when the file changes.
+fn charset_of(font: &FoundFont) -> Arc<Charset> {
+    let path = match &font.source {
+        FoundFontSource::Bytes(bytes) => return Arc::new(Charset::parse(bytes)),
+        FoundFontSource::Path(path) => path,
+    };
+    let stamp = crate::cache::stamp(path).ok();
+    let cached = charset_cache()
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get(path)
+        .filter(|(cached_stamp, _)| *cached_stamp == stamp)
+        .map(|(_, charset)| Arc::clone(charset));
+    if let Some(charset) = cached {
+        return charset;
+    }
+
+    let charset = match crate::embedded::lookup(path) {
+        Some(bytes) => Charset::parse(bytes),
+        None => match read_cmap(path) {
+            Ok(table) => {
+                Charset::from_cmap(table.as_deref().and_then(ttf_parser::cmap::Table::parse))
+            }
+            Err(e) => {
+                log::debug!("Failed to read {:?} for coverage: {}", path, e);
+                Charset::default()
+            }
+        },
+    };
+    let charset = Arc::new(charset);
+    charset_cache()
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .insert(path.clone(), (stamp, Arc::clone(&charset)));
+    charset
+}
+
+/// Reads the `cmap` table of the first face in the font file at `path`, and nothing else.
+///
+/// Returns `None` if the font has no `cmap`.
+fn read_cmap(path: &Path) -> io::Result<Option<Vec<u8>>> {
+    let invalid = || io::Error::new(io::ErrorKind::InvalidData, "malformed table directory");
+    let mut file = File::open(path)?;
+    let file_len = file.metadata()?.len();
+    let mut header = [0; 12];
+    file.read_exact(&mut header)?;
+    if read_u32(&header, 0) == Some(COLLECTION_TAG) {
+        // The offset of the first face follows the collection header.
+        let mut first = [0; 4];
+        file.read_exact(&mut first)?;
+        let offset = u32::from_be_bytes(first);
+        file.seek(SeekFrom::Start(u64::from(offset)))?;
+        file.read_exact(&mut header)?;
+    }
+
+    let num_tables = read_u16(&header, 4).ok_or_else(invalid)?;
+    let mut records = vec![0; 16 * usize::from(num_tables)];
+    file.read_exact(&mut records)?;
+    for record in records.chunks_exact(16) {
+        if &record[..4] != b"cmap" {
+            continue;
+        }
+        let start = u64::from(read_u32(record, 8).ok_or_else(invalid)?);
+        let len = u64::from(read_u32(record, 12).ok_or_else(invalid)?);
+        if start.saturating_add(len) > file_len {
+            return Err(invalid());
+        }
+        let mut table = vec![0; len as usize];
+        file.seek(SeekFrom::Start(start))?;
+        file.read_exact(&mut table)?;
+        return Ok(Some(table));
+    }
+    Ok(None)
+}
+
+/// Orders `fonts` by how many of `chars` they have glyphs for, most first, dropping fonts that have none.
+///
+/// Among fonts covering as many characters, those of `style` come first; otherwise the order of `fonts`
+/// is kept. Only the `cmap` of each font file is read (see [`missing_glyphs`]).
+pub(crate) fn rank_by_coverage(
+    fonts: Vec<FoundFont>,
+    chars: &[char],
+    style: FontStyle,
+) -> Vec<FoundFont> {
+    let mut scored: Vec<(usize, bool, FoundFont)> = fonts
+        .into_iter()
+        .map(|font| {
+            let charset = charset_of(&font);
+            let covered = chars.iter().filter(|&&c| charset.contains(c)).count();
+            (covered, style_of(&font.family) != style, font)
+        })
+        .filter(|(covered, _, _)| *covered > 0)
+        .collect();
+    scored.sort_by_key(|&(covered, other_style, _)| (Reverse(covered), other_style));
+    scored.into_iter().map(|(_, _, font)| font).collect()
+}
+
+/// The characters of `chars` that `font` has glyphs for.
+pub(crate) fn covered_chars(font: &FoundFont, chars: &[char]) -> Vec<char> {
+    let charset = charset_of(font);
+    chars
+        .iter()
+        .copied()
+        .filter(|&c| charset.contains(c))
+        .collect()
+}
+
+/// The style of a family from the built-in lists, or guessed from its name.
+fn style_of(family: &str) -> FontStyle {
+    builtin_style_of(family).unwrap_or_else(|| {
+        if family.contains("Serif") && !family.contains("Sans") {
+            FontStyle::Serif
+        } else {
+            FontStyle::Sans
+        }
+    })
+}
+
+/// Returns the characters of `text` that the candidate `font` has no glyph for, without installing it.
+///
+/// The font's `cmap` is read once per file and kept for the rest of the process, so checking many strings
+/// or ranking many candidates is cheap after the first call. A file whose size or modification time
+/// changes is read again. Control characters and whitespace are ignored; each missing character is
+/// reported once, in order of first appearance.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use egui_system_fonts::{missing_glyphs, FoundFont};
+/// # fn demo(font: &FoundFont) {
+/// let missing = missing_glyphs(font, "안녕하세요 ─ hello");
+/// println!("{} lacks {missing:?}", font.family);
+/// # }
+/// ```
+pub fn missing_glyphs(font: &FoundFont, text: &str) -> Vec<char> {
+    let charset = charset_of(font);
+    let mut missing = Vec::new();
+    for c in text.chars() {
+        if is_ignorable(c) || missing.contains(&c) || charset.contains(c) {
+            continue;
+        }
+        missing.push(c);
+    }
+    missing
+}
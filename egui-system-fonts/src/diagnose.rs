@@ -0,0 +1,219 @@
+//! Dry-run explanation of font resolution.
+
+use crate::resolve;
+use crate::{
+    global_defaults, FontPreset, FontProvider, FontRegion, FontSelection, FontStyle,
+    FoundFontSource, SkipReason, SystemProvider,
+};
+use std::collections::HashSet;
+use std::fmt;
+
+/// Result of [`diagnose`]: how a selection would resolve, without applying anything.
+#[derive(Clone, Debug)]
+pub struct Diagnosis {
+    /// Locale the region was derived from, if any.
+    pub locale: Option<String>,
+    /// Region the presets were derived from, if any.
+    pub region: Option<FontRegion>,
+    /// Effective style (after environment overrides).
+    pub style: FontStyle,
+    /// `true` if `EGUI_SYSTEM_FONTS_DISABLE` turned the `*_auto` functions off.
+    pub disabled: bool,
+    /// Presets in priority order.
+    pub presets: Vec<PresetDiagnosis>,
+}
+
+/// Candidates matched for one preset.
+#[derive(Clone, Debug)]
+pub struct PresetDiagnosis {
+    /// The preset.
+    pub preset: FontPreset,
+    /// Installed fonts matching the preset, in priority order.
+    ///
+    /// Candidate family names that are not installed at all do not appear here.
+    pub candidates: Vec<CandidateDiagnosis>,
+}
+
+/// One matched candidate and whether it would be installed.
+#[derive(Clone, Debug)]
+pub struct CandidateDiagnosis {
+    /// Font family name.
+    pub family: String,
+    /// Where the font data comes from.
+    pub source: FoundFontSource,
+    /// Size in bytes, if it could be determined.
+    pub size: Option<u64>,
+    /// Whether the candidate would be installed.
+    pub status: CandidateStatus,
+}
+
+/// Verdict for a [`CandidateDiagnosis`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CandidateStatus {
+    /// The font exists and is readable.
+    Accepted,
+    /// The family was already picked by a higher-priority preset.
+    Duplicate,
+    /// The font would be skipped.
+    Rejected(SkipReason),
+}
+
+/// Explains how [`set_auto`](crate::set_auto) would resolve fonts, without touching any `egui::Context`.
+///
+/// Runs locale detection (including environment overrides), region mapping, preset expansion and a
+/// cheap existence/readability/size check for every matched candidate. Font files are opened but not read.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use egui_system_fonts::{diagnose, FontStyle};
+/// let diagnosis = diagnose(FontStyle::Sans);
+/// println!("{diagnosis}");
+/// ```
+pub fn diagnose(style: FontStyle) -> Diagnosis {
+    diagnose_selection(FontSelection::Auto, style)
+}
+
+/// Like [`diagnose`], but for an explicit region or preset list.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use egui_system_fonts::{diagnose_selection, FontRegion, FontStyle};
+/// let diagnosis = diagnose_selection(FontRegion::Japanese, FontStyle::Serif);
+/// for preset in &diagnosis.presets {
+///     println!("{:?}: {} candidates", preset.preset, preset.candidates.len());
+/// }
+/// ```
+pub fn diagnose_selection(selection: impl Into<FontSelection>, style: FontStyle) -> Diagnosis {
+    let Some(plan) = resolve::plan(selection.into(), style, None, &SystemProvider, true) else {
+        return Diagnosis {
+            locale: None,
+            region: None,
+            style,
+            disabled: true,
+            presets: Vec::new(),
+        };
+    };
+
+    let mut seen_family = HashSet::<String>::new();
+    let presets = plan
+        .presets
+        .iter()
+        .map(|preset| {
+            let candidates = SystemProvider
+                .find(std::slice::from_ref(preset), plan.style)
+                .into_iter()
+                .map(|f| {
+                    let (size, checked) = check_source(&f.source);
+                    let status = if !seen_family.insert(f.family.clone()) {
+                        CandidateStatus::Duplicate
+                    } else {
+                        match checked {
+                            Ok(()) => CandidateStatus::Accepted,
+                            Err(reason) => CandidateStatus::Rejected(reason),
+                        }
+                    };
+                    CandidateDiagnosis {
+                        family: f.family,
+                        source: f.source,
+                        size,
+                        status,
+                    }
+                })
+                .collect();
+            PresetDiagnosis {
+                preset: preset.clone(),
+                candidates,
+            }
+        })
+        .collect();
+
+    Diagnosis {
+        locale: plan.locale,
+        region: plan.region,
+        style: plan.style,
+        disabled: false,
+        presets,
+    }
+}
+
+/// Returns `true` if fonts for `region`'s own script are installed and readable, without applying anything.
+///
+/// Only the region's own preset counts, not its fallbacks (Latin for a Japanese region), matching
+/// [`FontOptions::strict`](crate::FontOptions::strict). Lookups share the system font database, which is
+/// loaded once per process, and files are only opened, not read, so this is cheap enough to call for every
+/// region at startup, e.g. to gray out languages in a picker.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use egui_system_fonts::{is_region_available, FontRegion, FontStyle};
+/// for region in [FontRegion::Korean, FontRegion::Japanese, FontRegion::Cyrillic] {
+///     let enabled = is_region_available(region, FontStyle::Sans);
+///     println!("{region:?}: {}", if enabled { "ok" } else { "no fonts" });
+/// }
+/// ```
+pub fn is_region_available(region: FontRegion, style: FontStyle) -> bool {
+    let options = global_defaults();
+    let Some(primary) = system_fonts::presets_for_region(region).into_iter().next() else {
+        return false;
+    };
+    resolve::find(&[primary], style, &options)
+        .iter()
+        .any(|font| check_source(&font.source).1.is_ok())
+}
+
+fn check_source(source: &FoundFontSource) -> (Option<u64>, Result<(), SkipReason>) {
+    match source {
+        FoundFontSource::Path(path) => {
+            if let Some(bytes) = crate::embedded::lookup(path) {
+                return (Some(bytes.len() as u64), Ok(()));
+            }
+            let size = std::fs::metadata(path).ok().map(|m| m.len());
+            let readable = std::fs::File::open(path)
+                .map(|_| ())
+                .map_err(|e| SkipReason::ReadFailed(e.to_string()));
+            (size, readable)
+        }
+        FoundFontSource::Bytes(b) => (Some(b.len() as u64), Ok(())),
+    }
+}
+
+impl fmt::Display for Diagnosis {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "egui-system-fonts diagnosis")?;
+        if self.disabled {
+            return writeln!(f, "  disabled by {}", crate::ENV_DISABLE);
+        }
+        writeln!(
+            f,
+            "  locale: {:?}, region: {:?}, style: {:?}",
+            self.locale, self.region, self.style
+        )?;
+
+        for preset in &self.presets {
+            writeln!(f, "  preset {:?}:", preset.preset)?;
+            if preset.candidates.is_empty() {
+                writeln!(f, "    (no installed candidates)")?;
+            }
+            for c in &preset.candidates {
+                let status = match &c.status {
+                    CandidateStatus::Accepted => "ok".to_owned(),
+                    CandidateStatus::Duplicate => "duplicate".to_owned(),
+                    CandidateStatus::Rejected(reason) => format!("rejected: {reason:?}"),
+                };
+                let source = match &c.source {
+                    FoundFontSource::Path(path) => path.display().to_string(),
+                    FoundFontSource::Bytes(_) => "<memory>".to_owned(),
+                };
+                let size = match c.size {
+                    Some(bytes) => format!("{bytes} bytes"),
+                    None => "size unknown".to_owned(),
+                };
+                writeln!(f, "    [{status}] {} ({source}, {size})", c.family)?;
+            }
+        }
+        Ok(())
+    }
+}
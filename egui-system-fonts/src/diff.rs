@@ -0,0 +1,113 @@
+//! Comparing two font definitions.
+
+use egui::{FontDefinitions, FontFamily};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+/// Result of [`diff_definitions`]: what changed between two font definitions.
+///
+/// Its `Display` output lists added (`+`), removed (`-`) and replaced (`~`) keys, then each changed
+/// priority list before and after.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DefinitionsDiff {
+    /// `font_data` keys only in the new definitions, sorted.
+    pub added: Vec<String>,
+    /// `font_data` keys only in the old definitions, sorted.
+    pub removed: Vec<String>,
+    /// `font_data` keys in both whose data (bytes, face index or tweak) differs, sorted.
+    pub replaced: Vec<String>,
+    /// Font families whose priority list changed. A family missing from one side counts as empty there.
+    pub families: BTreeMap<FontFamily, FamilyChange>,
+}
+
+/// A changed priority list in a [`DefinitionsDiff`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FamilyChange {
+    /// Keys of the family in the old definitions, in priority order.
+    pub before: Vec<String>,
+    /// Keys of the family in the new definitions, in priority order.
+    pub after: Vec<String>,
+}
+
+impl DefinitionsDiff {
+    /// Whether the definitions are the same.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty()
+            && self.removed.is_empty()
+            && self.replaced.is_empty()
+            && self.families.is_empty()
+    }
+}
+
+/// Lists the keys and priority lists that differ between `before` and `after`.
+///
+/// Works on any definitions, e.g. to check what an `extend_*` call added and where. Nothing is applied.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use egui_system_fonts::{diff_definitions, extend_auto, snapshot, FontStyle};
+/// # fn demo(ctx: &egui::Context) {
+/// let before = snapshot(ctx).definitions().clone();
+/// let mut after = before.clone();
+/// extend_auto(ctx, &mut after, FontStyle::Sans);
+/// println!("{}", diff_definitions(&before, &after));
+/// # }
+/// ```
+pub fn diff_definitions(before: &FontDefinitions, after: &FontDefinitions) -> DefinitionsDiff {
+    let mut diff = DefinitionsDiff::default();
+    for (key, data) in &after.font_data {
+        match before.font_data.get(key) {
+            None => diff.added.push(key.clone()),
+            Some(old) if old != data => diff.replaced.push(key.clone()),
+            Some(_) => {}
+        }
+    }
+    diff.removed = before
+        .font_data
+        .keys()
+        .filter(|key| !after.font_data.contains_key(*key))
+        .cloned()
+        .collect();
+
+    let families: BTreeSet<&FontFamily> = before
+        .families
+        .keys()
+        .chain(after.families.keys())
+        .collect();
+    for family in families {
+        let old = before.families.get(family).map_or(&[][..], Vec::as_slice);
+        let new = after.families.get(family).map_or(&[][..], Vec::as_slice);
+        if old != new {
+            let change = FamilyChange {
+                before: old.to_vec(),
+                after: new.to_vec(),
+            };
+            diff.families.insert(family.clone(), change);
+        }
+    }
+    diff
+}
+
+impl fmt::Display for DefinitionsDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            return writeln!(f, "(no changes)");
+        }
+        for key in &self.added {
+            writeln!(f, "+ {key}")?;
+        }
+        for key in &self.removed {
+            writeln!(f, "- {key}")?;
+        }
+        for key in &self.replaced {
+            writeln!(f, "~ {key}")?;
+        }
+        for (family, change) in &self.families {
+            writeln!(f, "{family:?}:")?;
+            writeln!(f, "  before: {:?}", change.before)?;
+            writeln!(f, "  after:  {:?}", change.after)?;
+        }
+        Ok(())
+    }
+}
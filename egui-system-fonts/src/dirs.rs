@@ -0,0 +1,177 @@
+//! Fonts read through `fontdb` directly: caller-supplied directories, and system faces by weight.
+
+use crate::candidates::target_families;
+use crate::{FontPreset, FontStyle, FoundFont, FoundFontSource};
+use fontdb::{Database, Family, Query, Source};
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// Subdirectories nested deeper than this below a font directory are not scanned.
+pub(crate) const MAX_DEPTH: usize = 8;
+
+/// Finds the candidate families of `presets` among the fonts in `dirs`, in priority order.
+pub(crate) fn find_in_dirs(
+    dirs: &[PathBuf],
+    presets: &[FontPreset],
+    style: FontStyle,
+) -> Vec<FoundFont> {
+    let mut db = Database::new();
+    let mut seen = HashSet::new();
+    for dir in dirs {
+        load_path(&mut db, dir, 0, &mut seen);
+    }
+    if db.is_empty() {
+        return Vec::new();
+    }
+
+    target_families(presets, style)
+        .into_iter()
+        .enumerate()
+        .filter_map(|(i, family)| {
+            let families = [Family::Name(&family)];
+            let query = Query {
+                families: &families,
+                ..Default::default()
+            };
+            let face = db.face(db.query(&query)?)?;
+            let Source::File(path) = &face.source else {
+                return None;
+            };
+            let source = FoundFontSource::Path(path.clone());
+            Some(FoundFont {
+                key: format!("system:{}:{}", family, i),
+                family,
+                source,
+            })
+        })
+        .collect()
+}
+
+/// Finds the system font face of `font`'s family with exactly `weight`, keeping `font`'s key.
+///
+/// Only the first face of a file qualifies, as installed fonts are always read from the first face.
+pub(crate) fn find_system_weight(font: &FoundFont, weight: u16) -> Option<FoundFont> {
+    let db = system_database();
+    let families = [Family::Name(&font.family)];
+    let query = Query {
+        families: &families,
+        weight: fontdb::Weight(weight),
+        ..Default::default()
+    };
+    let face = db.face(db.query(&query)?)?;
+    if face.weight.0 != weight || face.index != 0 {
+        return None;
+    }
+    let Source::File(path) = &face.source else {
+        return None;
+    };
+    Some(FoundFont {
+        family: font.family.clone(),
+        key: font.key.clone(),
+        source: FoundFontSource::Path(path.clone()),
+    })
+}
+
+/// Finds system fonts with glyphs for any of `chars`, ranked by coverage.
+///
+/// Every family is considered once, through its upright face closest to regular weight (the first by path
+/// among equals). Families covering as much are ordered by name. Only the `cmap` of each file is read, and
+/// cached for the process.
+pub(crate) fn find_system_for_chars(chars: &[char], style: FontStyle) -> Vec<FoundFont> {
+    let mut best: BTreeMap<&str, (u16, &Path)> = BTreeMap::new();
+    for face in system_database().faces() {
+        let (Source::File(path), Some((family, _))) = (&face.source, face.families.first()) else {
+            continue;
+        };
+        if face.index != 0 || face.style != fontdb::Style::Normal {
+            continue;
+        }
+        let distance = face.weight.0.abs_diff(400);
+        let entry = best.entry(family).or_insert((distance, path));
+        if (distance, path.as_path()) < *entry {
+            *entry = (distance, path);
+        }
+    }
+
+    let fonts = best
+        .into_iter()
+        .map(|(family, (_, path))| FoundFont {
+            family: family.to_owned(),
+            key: String::new(),
+            source: FoundFontSource::Path(path.to_owned()),
+        })
+        .collect();
+    crate::coverage::rank_by_coverage(fonts, chars, style)
+        .into_iter()
+        .enumerate()
+        .map(|(i, font)| FoundFont {
+            key: format!("system:{}:{}", font.family, i),
+            ..font
+        })
+        .collect()
+}
+
+/// The system font database, loaded on first use and kept for the process.
+fn system_database() -> &'static Database {
+    static SYSTEM: OnceLock<Database> = OnceLock::new();
+    SYSTEM.get_or_init(|| {
+        let mut db = Database::new();
+        db.load_system_fonts();
+        db
+    })
+}
+
+/// Loads a font file, or the font files below a directory.
+///
+/// Symlinks are followed; each canonical path is visited once, which also breaks symlink cycles. Missing
+/// or unreadable paths are skipped with a debug log.
+fn load_path(db: &mut Database, path: &Path, depth: usize, seen: &mut HashSet<PathBuf>) {
+    let canonical = match fs::canonicalize(path) {
+        Ok(canonical) => canonical,
+        Err(e) => {
+            log::debug!("Skipping font path {:?}: {}", path, e);
+            return;
+        }
+    };
+    if !seen.insert(canonical.clone()) {
+        return;
+    }
+
+    if canonical.is_file() {
+        if is_font_file(&canonical) {
+            if let Err(e) = db.load_font_file(&canonical) {
+                log::debug!("Failed to load font file {:?}: {}", canonical, e);
+            }
+        }
+        return;
+    }
+
+    if depth > MAX_DEPTH {
+        log::debug!("Not scanning {:?}: nested too deep", path);
+        return;
+    }
+    let entries = match fs::read_dir(&canonical) {
+        Ok(entries) => entries,
+        Err(e) => {
+            log::debug!("Skipping font dir {:?}: {}", path, e);
+            return;
+        }
+    };
+    // Sorted, so that of several equally good faces the first by path wins on every machine.
+    let mut paths: Vec<PathBuf> = entries.flatten().map(|entry| entry.path()).collect();
+    paths.sort_unstable();
+    for path in paths {
+        load_path(db, &path, depth + 1, seen);
+    }
+}
+
+fn is_font_file(path: &Path) -> bool {
+    let Some(ext) = path.extension().and_then(|e| e.to_str()) else {
+        return false;
+    };
+    ["ttf", "ttc", "otf", "otc"]
+        .iter()
+        .any(|known| ext.eq_ignore_ascii_case(known))
+}
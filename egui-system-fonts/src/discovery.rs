@@ -0,0 +1,141 @@
+//! Lower-level font discovery.
+//!
+//! These are the functions the `set_*`/`extend_*` helpers use to resolve candidates. Combine them with
+//! [`apply_found_fonts`](crate::apply_found_fonts) to inspect or filter candidates before installing them.
+//!
+//! ```no_run
+//! use egui_system_fonts::discovery::{find_from_presets, presets_for_region};
+//! use egui_system_fonts::{FontRegion, FontStyle};
+//!
+//! let fonts = find_from_presets(presets_for_region(FontRegion::Japanese), FontStyle::Sans);
+//! for f in &fonts {
+//!     println!("{} ({})", f.family, f.key);
+//! }
+//! ```
+//!
+//! The mapping from locale to region is pure and can be checked anywhere:
+//!
+//! ```
+//! use egui_system_fonts::discovery::region_from_locale;
+//! use egui_system_fonts::FontRegion;
+//!
+//! assert_eq!(region_from_locale("ja_JP.UTF-8"), FontRegion::Japanese);
+//! assert_eq!(region_from_locale("zh-Hant-TW"), FontRegion::TraditionalChinese);
+//! ```
+pub use system_fonts::{
+    find_for_locale, find_for_system_locale, find_from_presets, presets_for_region,
+    region_from_locale, system_locale, FoundFont, FoundFontSource,
+};
+
+use crate::candidates::indexed_target_families;
+use crate::{global_defaults, resolve, FontPreset, FontRegion, FontSelection, FontStyle};
+
+/// Lazily yields the candidates [`find_from_presets`] would return, in the same priority order and with
+/// the same keys.
+///
+/// Each candidate family is looked up only when the iterator reaches it, and files are never read:
+/// [`FoundFontSource::Path`] stays a path until the consumer reads it. Useful to show the first match
+/// right away, or to bound work with `take`. On wasm, yields nothing.
+///
+/// # Examples
+///
+/// ```no_run
+/// use egui_system_fonts::discovery::{find_iter, presets_for_region};
+/// use egui_system_fonts::{FontRegion, FontStyle};
+///
+/// let first = find_iter(presets_for_region(FontRegion::Japanese), FontStyle::Sans).next();
+/// println!("first match: {:?}", first.map(|f| f.family));
+/// ```
+pub fn find_iter<I>(presets: I, style: FontStyle) -> impl Iterator<Item = FoundFont>
+where
+    I: IntoIterator<Item = FontPreset>,
+{
+    let presets: Vec<FontPreset> = presets.into_iter().collect();
+    indexed_target_families(&presets, style)
+        .into_iter()
+        .filter_map(move |(i, family)| {
+            let query = [FontPreset::Custom(vec![family])];
+            let found = find_from_presets(query, style).into_iter().next()?;
+            let key = format!("system:{}:{}", found.family, i);
+            Some(FoundFont { key, ..found })
+        })
+}
+
+/// [`find_iter`] for a region's presets: lazily yields the candidates the `set_*` functions would try for
+/// `region`, in priority order.
+///
+/// # Examples
+///
+/// Stop at the first candidate that covers a sample, reading only the files up to it:
+///
+/// ```no_run
+/// use egui_system_fonts::discovery::candidates_iter;
+/// use egui_system_fonts::{missing_glyphs, FontRegion, FontStyle};
+///
+/// let sample = "한국어 텍스트";
+/// let good = candidates_iter(FontRegion::Korean, FontStyle::Sans).find(|f| missing_glyphs(f, sample).is_empty());
+/// println!("first font covering the sample: {:?}", good.map(|f| f.family));
+/// ```
+pub fn candidates_iter(region: FontRegion, style: FontStyle) -> impl Iterator<Item = FoundFont> {
+    find_iter(presets_for_region(region), style)
+}
+
+/// Finds installed fonts with glyphs for any of `chars`, regardless of presets, for characters no preset
+/// covers (mathematical alphanumerics, rare Han characters, historic scripts).
+///
+/// Fonts covering the most of `chars` come first; among those, fonts of `style`, then (with the system
+/// provider) by family name, so the order does not depend on how the filesystem lists fonts. Each family
+/// appears once, through its upright face closest to regular weight; equal faces are picked by path.
+/// Only the `cmap` table of each font file is read and it is cached for the process, but the first call still
+/// opens every installed font, so run it off the UI thread on systems with many fonts. Fonts are looked up
+/// through the [`provider`](crate::FontOptions::provider) of the
+/// [`global_defaults`]. On wasm, finds nothing.
+///
+/// # Examples
+///
+/// ```no_run
+/// use egui_system_fonts::discovery::find_for_chars;
+/// use egui_system_fonts::FontStyle;
+///
+/// let chars: Vec<char> = "𝔘𝔫𝔦𝔠𝔬𝔡𝔢".chars().collect();
+/// for font in find_for_chars(&chars, FontStyle::Sans).iter().take(3) {
+///     println!("{} ({:?})", font.family, font.source);
+/// }
+/// ```
+pub fn find_for_chars(chars: &[char], style: FontStyle) -> Vec<FoundFont> {
+    global_defaults()
+        .font_provider()
+        .find_for_chars(chars, style)
+}
+
+/// Returns the locale, region and candidates [`set_auto`](crate::set_auto) would use, without reading or
+/// applying anything.
+///
+/// Unlike [`find_for_system_locale`], this goes through the same detection as the `*_auto` functions:
+/// [`EnvOverrides`](crate::EnvOverrides), [`set_locale_region_overrides`](crate::set_locale_region_overrides)
+/// and the [`global_defaults`] (their provider, preferred families, weight and so
+/// on). The locale is `None` when the region was forced; everything is empty when
+/// [`ENV_DISABLE`](crate::ENV_DISABLE) is set. Pass the candidates, filtered or reordered, to
+/// [`apply_found_fonts`](crate::apply_found_fonts) to build a custom flow.
+///
+/// # Examples
+///
+/// ```no_run
+/// use egui_system_fonts::discovery::found_for_system_locale;
+/// use egui_system_fonts::FontStyle;
+///
+/// let (locale, region, fonts) = found_for_system_locale(FontStyle::Sans);
+/// println!("{locale:?} -> {region:?}: {} fonts", fonts.len());
+/// ```
+pub fn found_for_system_locale(
+    style: FontStyle,
+) -> (Option<String>, Option<FontRegion>, Vec<FoundFont>) {
+    match resolve::resolve(FontSelection::Auto, style, &global_defaults()) {
+        Some(resolution) => (
+            resolution.plan.locale,
+            resolution.plan.region,
+            resolution.fonts,
+        ),
+        None => (None, None, Vec::new()),
+    }
+}
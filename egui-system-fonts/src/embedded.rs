@@ -0,0 +1,45 @@
+//! Fonts compiled into the binary, addressed by virtual paths.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{OnceLock, PoisonError, RwLock};
+
+type Registry = RwLock<HashMap<PathBuf, &'static [u8]>>;
+
+fn registry() -> &'static Registry {
+    static REGISTRY: OnceLock<Registry> = OnceLock::new();
+    REGISTRY.get_or_init(Default::default)
+}
+
+/// Registers embedded font data under a virtual path.
+///
+/// Whenever a candidate's [`FoundFontSource::Path`](crate::FoundFontSource::Path) equals `name`, its bytes
+/// are taken from here instead of the filesystem, so fonts included with `include_bytes!` go through the
+/// same resolution flow as installed ones (e.g. from a custom [`FontProvider`](crate::FontProvider)).
+/// The data is not copied: the installed [`FontData`](egui::FontData) borrows it, once for all font families
+/// listing it. Registering the same name again replaces the data.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use egui_system_fonts::register_embedded;
+/// # fn demo(noto_sans_kr: &'static [u8]) {
+/// // let noto_sans_kr = include_bytes!("../assets/NotoSansKR-Regular.otf");
+/// register_embedded("embedded://NotoSansKR-Regular.otf", noto_sans_kr);
+/// # }
+/// ```
+pub fn register_embedded(name: &str, bytes: &'static [u8]) {
+    registry()
+        .write()
+        .unwrap_or_else(PoisonError::into_inner)
+        .insert(PathBuf::from(name), bytes);
+}
+
+/// Returns the embedded data registered for `path`, if any.
+pub(crate) fn lookup(path: &Path) -> Option<&'static [u8]> {
+    registry()
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get(path)
+        .copied()
+}
@@ -0,0 +1,92 @@
+//! Environment-variable overrides for the `*_auto` functions.
+
+use crate::names::{parse_region, parse_style};
+use crate::{FontRegion, FontStyle};
+
+/// Overrides the detected region (e.g. `Japanese`, `simplified-chinese`, `zh-Hant`; see [`parse_region`]).
+pub const ENV_REGION: &str = "EGUI_SYSTEM_FONTS_REGION";
+/// Overrides the detected locale string (e.g. `ja-JP`). Ignored when [`ENV_REGION`] is set.
+pub const ENV_LOCALE: &str = "EGUI_SYSTEM_FONTS_LOCALE";
+/// Overrides the style argument (`Sans` or `Serif`).
+pub const ENV_STYLE: &str = "EGUI_SYSTEM_FONTS_STYLE";
+/// When set to `1` (or `true`), the `*_auto` functions become no-ops.
+pub const ENV_DISABLE: &str = "EGUI_SYSTEM_FONTS_DISABLE";
+
+/// A snapshot of the `EGUI_SYSTEM_FONTS_*` environment variables.
+///
+/// [`set_auto`](crate::set_auto) and [`extend_auto`](crate::extend_auto) read this from the process
+/// environment on every call, unless [`FontOptions::env_overrides`](crate::FontOptions::env_overrides)
+/// supplies it. Unparsable values are logged and ignored.
+///
+/// # Examples
+///
+/// ```
+/// # use egui_system_fonts::{EnvOverrides, FontRegion, FontStyle};
+/// let env = EnvOverrides::from_vars([
+///     ("EGUI_SYSTEM_FONTS_REGION", "Japanese"),
+///     ("EGUI_SYSTEM_FONTS_STYLE", "serif"),
+/// ]);
+/// assert_eq!(env.region, Some(FontRegion::Japanese));
+/// assert_eq!(env.style, Some(FontStyle::Serif));
+/// assert!(!env.disabled);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct EnvOverrides {
+    /// Region forced by [`ENV_REGION`].
+    pub region: Option<FontRegion>,
+    /// Locale forced by [`ENV_LOCALE`].
+    pub locale: Option<String>,
+    /// Style forced by [`ENV_STYLE`].
+    pub style: Option<FontStyle>,
+    /// Set by [`ENV_DISABLE`].
+    pub disabled: bool,
+}
+
+impl EnvOverrides {
+    /// Reads the overrides from the process environment.
+    pub fn from_env() -> Self {
+        Self::from_vars(std::env::vars())
+    }
+
+    /// Reads the overrides from an explicit set of variables.
+    ///
+    /// Variables that are not `EGUI_SYSTEM_FONTS_*` are ignored.
+    pub fn from_vars<I, K, V>(vars: I) -> Self
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: AsRef<str>,
+        V: AsRef<str>,
+    {
+        let mut out = Self::default();
+
+        for (key, value) in vars {
+            let value = value.as_ref().trim();
+            if value.is_empty() {
+                continue;
+            }
+
+            match key.as_ref() {
+                ENV_REGION => match parse_region(value) {
+                    Ok(region) => out.region = Some(region),
+                    Err(e) => log::warn!("Ignoring {}: {}", ENV_REGION, e),
+                },
+                ENV_LOCALE => out.locale = Some(value.to_owned()),
+                ENV_STYLE => match parse_style(value) {
+                    Ok(style) => out.style = Some(style),
+                    Err(e) => log::warn!("Ignoring {}: {}", ENV_STYLE, e),
+                },
+                ENV_DISABLE => {
+                    out.disabled = value == "1" || value.eq_ignore_ascii_case("true");
+                }
+                _ => {}
+            }
+        }
+
+        out
+    }
+
+    /// Returns `true` if any override is active.
+    pub fn is_active(&self) -> bool {
+        self.region.is_some() || self.locale.is_some() || self.style.is_some() || self.disabled
+    }
+}
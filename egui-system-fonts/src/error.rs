@@ -0,0 +1,75 @@
+//! Errors returned by the fallible `*_with_options` functions and [`read_metadata`](crate::read_metadata).
+
+use crate::{FontRegion, FontStyle, SkippedFont};
+use std::fmt;
+
+/// Error returned when font setup cannot satisfy the requested options, or a font cannot be read.
+#[non_exhaustive]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum FontError {
+    /// [`FontOptions::strict`](crate::FontOptions::strict) is set and the region's own preset has no
+    /// installed font on this system.
+    RegionUnsupportedOnSystem(FontRegion),
+    /// [`FontOptions::strict`](crate::FontOptions::strict) is set and none of the candidates could be
+    /// installed (or, with a region, the fonts in place still lack the region's script).
+    NoFontsInstalled {
+        /// Region the fonts were resolved for, if any.
+        region: Option<FontRegion>,
+        /// Style that was requested.
+        style: FontStyle,
+        /// Every candidate that was tried, with the reason it was not installed.
+        candidates_tried: Vec<SkippedFont>,
+    },
+    /// [`FontOptions::strict`](crate::FontOptions::strict) is set and the installed fonts cannot draw
+    /// common characters of the region's script.
+    RegionNotCovered {
+        /// Region whose script is not covered.
+        region: FontRegion,
+        /// Sample characters no Proportional font has a glyph for.
+        missing: Vec<char>,
+    },
+    /// The call was stopped through [`FontOptions::cancel_token`](crate::FontOptions::cancel_token);
+    /// nothing was applied.
+    Cancelled,
+    /// The font file could not be read.
+    ReadFailed(String),
+    /// The data is not a font `egui` can use (not TrueType/OpenType, or a damaged file).
+    InvalidFont(String),
+}
+
+impl fmt::Display for FontError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::RegionUnsupportedOnSystem(region) => {
+                write!(f, "no system font found for region {region:?}")
+            }
+            Self::NoFontsInstalled {
+                region,
+                style,
+                candidates_tried,
+            } => {
+                match region {
+                    Some(region) => write!(f, "no {style:?} font installed for region {region:?}")?,
+                    None => write!(f, "no {style:?} font installed")?,
+                }
+                if candidates_tried.is_empty() {
+                    return write!(f, "; no candidate fonts were found");
+                }
+                write!(f, "; tried:")?;
+                for skipped in candidates_tried {
+                    write!(f, "\n  {}: {:?}", skipped.family, skipped.reason)?;
+                }
+                Ok(())
+            }
+            Self::RegionNotCovered { region, missing } => {
+                let missing: String = missing.iter().collect();
+                write!(f, "installed fonts lack {region:?} characters: {missing}")
+            }
+            Self::Cancelled => write!(f, "font setup was cancelled"),
+            Self::ReadFailed(e) => write!(f, "failed to read font: {e}"),
+            Self::InvalidFont(e) => write!(f, "invalid font data: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for FontError {}
@@ -0,0 +1,97 @@
+//! Per-call control over what the crate logs, and structured events for the caller's own diagnostics.
+
+use crate::{FontKey, FontRegion, FontStyle, SkippedFont};
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// How much a call writes to the global logger (the `log` crate).
+///
+/// Set with [`FontOptions::verbosity`](crate::FontOptions::verbosity). Events sent to a
+/// [`log_sink`](crate::FontOptions::log_sink) are not affected.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Verbosity {
+    /// Logs nothing.
+    Silent,
+    /// Logs the outcome of each call (detected region, installed families) and warnings.
+    #[default]
+    Normal,
+    /// Also logs every candidate: installed ones with their size and read time, skipped ones with the
+    /// reason.
+    Verbose,
+}
+
+/// Something that happened while setting up fonts, sent to a [`LogSink`].
+#[non_exhaustive]
+#[derive(Clone, Debug, PartialEq)]
+pub enum LogEvent {
+    /// The selection was expanded into `candidates` fonts to try.
+    Resolved {
+        /// Detected locale (only for [`FontSelection::Auto`](crate::FontSelection::Auto)).
+        locale: Option<String>,
+        /// Region the candidates were picked for, if known.
+        region: Option<FontRegion>,
+        /// Style after environment overrides.
+        style: FontStyle,
+        /// Number of candidates found.
+        candidates: usize,
+    },
+    /// A candidate is about to be checked and read.
+    Candidate {
+        /// Family name of the candidate.
+        family: String,
+        /// `font_data` key it would be installed under.
+        key: FontKey,
+    },
+    /// A candidate was skipped; see [`SkippedFont::reason`].
+    Skipped(SkippedFont),
+    /// A candidate was read and will be installed.
+    Installed {
+        /// Family name of the font.
+        family: String,
+        /// `font_data` key it is installed under.
+        key: FontKey,
+        /// Size of the installed font data.
+        bytes: u64,
+        /// Time spent reading (and subsetting) the font.
+        elapsed: Duration,
+    },
+    /// A problem that did not stop the call, e.g. a missing weight or a cache file that could not be
+    /// written.
+    Warning(String),
+}
+
+/// Receives the [`LogEvent`]s of a call; see [`FontOptions::log_sink`](crate::FontOptions::log_sink).
+pub type LogSink = dyn Fn(&LogEvent) + Send + Sync;
+
+/// Where a call is in its work, sent to a [`ProgressCallback`] so a splash screen can show it.
+#[non_exhaustive]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Progress {
+    /// Looking up installed fonts. The first lookup of a process scans the system font directories, which
+    /// can take a while on a cold cache.
+    Resolving,
+    /// Scanning `count` directories added with [`FontOptions::add_font_dirs`](crate::FontOptions::add_font_dirs).
+    ScanningDirectories {
+        /// Number of directories (or files) to scan.
+        count: usize,
+    },
+    /// About to read candidate `index` (counting from 0) of `total`.
+    ReadingFont {
+        /// Family name of the candidate.
+        family: String,
+        /// File the font is read from; `None` for in-memory fonts.
+        path: Option<PathBuf>,
+        /// Position of the candidate.
+        index: usize,
+        /// Number of candidates to read.
+        total: usize,
+    },
+    /// Every candidate has been read (or skipped).
+    Done {
+        /// Number of fonts that will be installed.
+        installed: usize,
+    },
+}
+
+/// Receives the [`Progress`] of a call; see [`FontOptions::progress`](crate::FontOptions::progress).
+pub type ProgressCallback = dyn Fn(&Progress) + Send + Sync;
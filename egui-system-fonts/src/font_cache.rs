@@ -0,0 +1,92 @@
+//! Sharing installed font data between calls that read the same font.
+
+use egui::{FontData, FontTweak};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, PoisonError, Weak};
+use system_fonts::FoundFontSource;
+
+/// Where cached font data was read from.
+enum Origin {
+    /// A file, by canonical path, with the size and modification time it was read at.
+    File(PathBuf, Option<(u64, u128)>),
+    /// In-memory bytes, by allocation.
+    Bytes(Weak<[u8]>),
+}
+
+/// Font data still in use somewhere, by origin.
+///
+/// Entries hold the data weakly: fonts are shared while some `FontDefinitions` use them and freed with the
+/// last one, so the cache never keeps fonts alive by itself. Dead entries are dropped on insertion.
+static CACHE: Mutex<Vec<(Origin, Weak<FontData>)>> = Mutex::new(Vec::new());
+
+fn origin(source: &FoundFontSource) -> Origin {
+    match source {
+        FoundFontSource::Path(path) => {
+            let canonical = std::fs::canonicalize(path).unwrap_or_else(|_| path.clone());
+            let stamp = crate::cache::stamp(&canonical).ok();
+            Origin::File(canonical, stamp)
+        }
+        FoundFontSource::Bytes(bytes) => Origin::Bytes(Arc::downgrade(bytes)),
+    }
+}
+
+fn same_origin(a: &Origin, b: &Origin) -> bool {
+    match (a, b) {
+        (Origin::File(a, a_stamp), Origin::File(b, b_stamp)) => a == b && a_stamp == b_stamp,
+        (Origin::Bytes(a), Origin::Bytes(b)) => a.ptr_eq(b) && a.strong_count() > 0,
+        _ => false,
+    }
+}
+
+/// Returns the data installed earlier from `source` with the tweak `tweak` picks for its bytes, if it is
+/// still in use.
+pub(crate) fn get(
+    source: &FoundFontSource,
+    tweak: impl Fn(&[u8]) -> FontTweak,
+) -> Option<Arc<FontData>> {
+    let origin = origin(source);
+    let cache = CACHE.lock().unwrap_or_else(PoisonError::into_inner);
+    cache
+        .iter()
+        .filter(|(cached, _)| same_origin(cached, &origin))
+        .filter_map(|(_, data)| data.upgrade())
+        .find(|data| data.tweak == tweak(&data.font))
+}
+
+/// Whether data installed earlier from `source` is still in use, with any tweak.
+pub(crate) fn contains(source: &FoundFontSource) -> bool {
+    let origin = origin(source);
+    let cache = CACHE.lock().unwrap_or_else(PoisonError::into_inner);
+    cache
+        .iter()
+        .any(|(cached, data)| same_origin(cached, &origin) && data.strong_count() > 0)
+}
+
+/// Remembers that `data` was read from `source`.
+pub(crate) fn insert(source: &FoundFontSource, data: &Arc<FontData>) {
+    let origin = origin(source);
+    let mut cache = CACHE.lock().unwrap_or_else(PoisonError::into_inner);
+    cache.retain(|(_, data)| data.strong_count() > 0);
+    cache.push((origin, Arc::downgrade(data)));
+}
+
+/// Forgets which fonts were read, so the next calls read every font again.
+///
+/// Fonts read from the same file (or the same in-memory bytes) with the same tweak are shared between
+/// calls, e.g. between two contexts, or a font set up again after a [`restore`](crate::restore). Font data
+/// is only shared while some font definitions still use it, so clearing never frees memory; use it after
+/// replacing a font file in place without changing its size or modification time.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use egui_system_fonts::{clear_font_cache, set_auto, FontStyle};
+/// # fn demo(ctx: &egui::Context) {
+/// // New fonts were installed: read them from disk again.
+/// clear_font_cache();
+/// set_auto(ctx, FontStyle::Sans);
+/// # }
+/// ```
+pub fn clear_font_cache() {
+    CACHE.lock().unwrap_or_else(PoisonError::into_inner).clear();
+}
@@ -0,0 +1,182 @@
+//! Noticing fonts installed or removed while the app runs (`watch` feature).
+
+use notify::{Event, EventKind, RecursiveMode, Watcher};
+use std::io;
+use std::path::PathBuf;
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+/// How long [`watch_font_dirs`] waits for a burst of changes to settle.
+const DEFAULT_DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// Calls `on_change` when fonts are added to, changed in or removed from the system font directories.
+///
+/// Watches, where they exist:
+///
+/// - Linux and other Unix systems: `/usr/share/fonts`, `/usr/local/share/fonts`, `~/.fonts` and
+///   `~/.local/share/fonts` (or `$XDG_DATA_HOME/fonts`).
+/// - macOS: `/System/Library/Fonts`, `/Library/Fonts` and `~/Library/Fonts`.
+/// - Windows: `%WINDIR%\Fonts` and `%LOCALAPPDATA%\Microsoft\Windows\Fonts`.
+///
+/// A burst of changes (a font pack being unpacked) results in a single call, half a second after the last
+/// change. `on_change` runs on a background thread; re-apply fonts from it with e.g.
+/// [`set_auto`](crate::set_auto) or flag the UI to do so. Dropping the returned watcher stops it. Fails if
+/// the platform's file watching cannot be set up.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use egui_system_fonts::{set_auto, watch_font_dirs, FontStyle};
+/// # fn demo(ctx: &egui::Context) -> std::io::Result<()> {
+/// let ctx = ctx.clone();
+/// let _watcher = watch_font_dirs(move || {
+///     set_auto(&ctx, FontStyle::Sans);
+///     ctx.request_repaint();
+/// })?;
+/// // Keep `_watcher` alive as long as the app runs.
+/// # Ok(())
+/// # }
+/// ```
+pub fn watch_font_dirs(on_change: impl Fn() + Send + 'static) -> io::Result<FontDirWatcher> {
+    watch_font_dirs_with(system_font_dirs(), DEFAULT_DEBOUNCE, on_change)
+}
+
+/// Like [`watch_font_dirs`], for the given directories and waiting `debounce` for changes to settle.
+///
+/// Directories that do not exist are ignored.
+///
+/// # Examples
+///
+/// ```
+/// # use egui_system_fonts::watch_font_dirs_with;
+/// # use std::sync::mpsc;
+/// # use std::time::Duration;
+/// let dir = std::env::temp_dir().join(format!("egui-system-fonts-watch-{}", std::process::id()));
+/// std::fs::create_dir_all(&dir).unwrap();
+///
+/// let (tx, rx) = mpsc::channel();
+/// let watcher = watch_font_dirs_with([dir.clone()], Duration::from_millis(50), move || {
+///     let _ = tx.send(());
+/// })
+/// .unwrap();
+///
+/// std::fs::write(dir.join("new.ttf"), b"font data").unwrap();
+/// assert!(rx.recv_timeout(Duration::from_secs(10)).is_ok());
+///
+/// drop(watcher);
+/// std::fs::remove_dir_all(&dir).unwrap();
+/// ```
+pub fn watch_font_dirs_with(
+    dirs: impl IntoIterator<Item = PathBuf>,
+    debounce: Duration,
+    on_change: impl Fn() + Send + 'static,
+) -> io::Result<FontDirWatcher> {
+    let (tx, rx) = mpsc::channel::<notify::Result<Event>>();
+    let mut watcher = notify::recommended_watcher(tx).map_err(io::Error::other)?;
+    let mut watched = Vec::new();
+    for dir in dirs.into_iter().filter(|dir| dir.is_dir()) {
+        match watcher.watch(&dir, RecursiveMode::Recursive) {
+            Ok(()) => watched.push(dir),
+            Err(e) => log::debug!("Not watching font directory {:?}: {}", dir, e),
+        }
+    }
+    log::debug!("Watching font directories {:?}", watched);
+
+    let thread = std::thread::Builder::new()
+        .name("egui-system-fonts-watch".to_owned())
+        .spawn(move || {
+            // Ends when the watcher is dropped, which closes the channel.
+            while let Ok(event) = rx.recv() {
+                if !is_change(&event) {
+                    continue;
+                }
+                loop {
+                    match rx.recv_timeout(debounce) {
+                        Ok(_) => continue,
+                        Err(RecvTimeoutError::Timeout) => break,
+                        Err(RecvTimeoutError::Disconnected) => return,
+                    }
+                }
+                if crate::global_defaults().logs() {
+                    log::info!("Font directories changed");
+                }
+                on_change();
+            }
+        })?;
+
+    Ok(FontDirWatcher {
+        watcher: Some(watcher),
+        thread: Some(thread),
+        dirs: watched,
+    })
+}
+
+/// Watches font directories until dropped; see [`watch_font_dirs`].
+pub struct FontDirWatcher {
+    watcher: Option<notify::RecommendedWatcher>,
+    thread: Option<JoinHandle<()>>,
+    dirs: Vec<PathBuf>,
+}
+
+impl FontDirWatcher {
+    /// The directories being watched.
+    pub fn dirs(&self) -> &[PathBuf] {
+        &self.dirs
+    }
+}
+
+impl Drop for FontDirWatcher {
+    fn drop(&mut self) {
+        // Dropping the watcher closes the event channel, which ends the thread (after a running callback).
+        self.watcher = None;
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+impl std::fmt::Debug for FontDirWatcher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FontDirWatcher")
+            .field("dirs", &self.dirs)
+            .finish()
+    }
+}
+
+/// Whether `event` may have added, changed or removed a font (rather than just opened one).
+fn is_change(event: &notify::Result<Event>) -> bool {
+    match event {
+        Ok(event) => !matches!(event.kind, EventKind::Access(_)),
+        Err(e) => {
+            log::debug!("Font directory watch error: {}", e);
+            false
+        }
+    }
+}
+
+/// The platform's font directories.
+fn system_font_dirs() -> Vec<PathBuf> {
+    let home = std::env::var_os("HOME").map(PathBuf::from);
+    let mut dirs = Vec::new();
+    if cfg!(target_os = "windows") {
+        let windir = std::env::var_os("WINDIR").map_or_else(|| "C:\\Windows".into(), PathBuf::from);
+        dirs.push(windir.join("Fonts"));
+        if let Some(local) = std::env::var_os("LOCALAPPDATA") {
+            dirs.push(PathBuf::from(local).join("Microsoft\\Windows\\Fonts"));
+        }
+    } else if cfg!(target_os = "macos") {
+        dirs.push("/System/Library/Fonts".into());
+        dirs.push("/Library/Fonts".into());
+        dirs.extend(home.map(|home| home.join("Library/Fonts")));
+    } else {
+        dirs.push("/usr/share/fonts".into());
+        dirs.push("/usr/local/share/fonts".into());
+        let data_home = std::env::var_os("XDG_DATA_HOME")
+            .map(PathBuf::from)
+            .or_else(|| home.as_ref().map(|home| home.join(".local/share")));
+        dirs.extend(data_home.map(|data| data.join("fonts")));
+        dirs.extend(home.map(|home| home.join(".fonts")));
+    }
+    dirs
+}
@@ -0,0 +1,100 @@
+//! Keeping a region's own CJK fonts ahead of other CJK fonts.
+//!
+//! Chinese, Japanese and Korean fonts all cover the unified Han ideographs but draw many of them
+//! differently. `egui` has no per-script or per-language font runs: a character is drawn with the first
+//! font in the family list that has a glyph for it. So the only lever is the order of that list.
+
+use crate::candidates::builtin_preset_of;
+use crate::names::family_from_key;
+use crate::provider::same_preset;
+use crate::{is_system_font_key, snapshot, FontPreset, FontRegion};
+use egui::FontDefinitions;
+
+/// Moves the CJK fonts of `region` ahead of other CJK fonts installed by this crate, and reapplies `defs`.
+///
+/// With several CJK fonts installed (say a Chinese font from one call and a Japanese font from another),
+/// Han characters are drawn by whichever comes first, so a Japanese user may get Chinese glyph shapes.
+/// This reorders only the CJK fonts installed by this crate, each within the slots they already occupy:
+/// other fonts keep their positions. CJK families are recognized by the built-in candidate lists.
+///
+/// Returns `true` if anything moved; otherwise `ctx` is left alone. Regions without a CJK preset never
+/// change anything. See also [`FontOptions::pin_han`](crate::FontOptions::pin_han).
+///
+/// # Examples
+///
+/// ```no_run
+/// # use egui_system_fonts::{pin_han_region, snapshot, FontRegion};
+/// # fn demo(ctx: &egui::Context) {
+/// // The user switched the document language to Japanese.
+/// let mut defs = snapshot(ctx).definitions().clone();
+/// pin_han_region(ctx, &mut defs, FontRegion::Japanese);
+/// # }
+/// ```
+pub fn pin_han_region(ctx: &egui::Context, defs: &mut FontDefinitions, region: FontRegion) -> bool {
+    if !pin(defs, region) {
+        return false;
+    }
+    snapshot::install(ctx, defs.clone());
+    true
+}
+
+/// Reorders the CJK fonts in every family list of `defs` so those of `region` come first.
+///
+/// Returns `true` if anything moved.
+pub(crate) fn pin(defs: &mut FontDefinitions, region: FontRegion) -> bool {
+    let Some(primary) = cjk_preset(region) else {
+        return false;
+    };
+
+    let mut changed = false;
+    for keys in defs.families.values_mut() {
+        let positions: Vec<(usize, bool)> = keys
+            .iter()
+            .enumerate()
+            .filter_map(|(i, key)| Some((i, same_preset(&cjk_preset_of_key(key)?, &primary))))
+            .collect();
+
+        let mut order = positions.clone();
+        order.sort_by_key(|&(_, own)| !own);
+        if order == positions {
+            continue;
+        }
+
+        let reordered: Vec<String> = order.iter().map(|&(i, _)| keys[i].clone()).collect();
+        for (&(i, _), key) in positions.iter().zip(reordered) {
+            keys[i] = key;
+        }
+        changed = true;
+    }
+
+    if changed {
+        log::debug!("Pinned {:?} CJK fonts first", region);
+    }
+    changed
+}
+
+fn cjk_preset(region: FontRegion) -> Option<FontPreset> {
+    match region {
+        FontRegion::Korean => Some(FontPreset::Korean),
+        FontRegion::Japanese => Some(FontPreset::Japanese),
+        FontRegion::SimplifiedChinese => Some(FontPreset::SimplifiedChinese),
+        FontRegion::TraditionalChinese => Some(FontPreset::TraditionalChinese),
+        _ => None,
+    }
+}
+
+/// The CJK preset of a font installed by this crate, if its family is a known CJK family.
+fn cjk_preset_of_key(key: &str) -> Option<FontPreset> {
+    if !is_system_font_key(key) {
+        return None;
+    }
+    let preset = builtin_preset_of(family_from_key(key)?)?;
+    matches!(
+        preset,
+        FontPreset::Korean
+            | FontPreset::Japanese
+            | FontPreset::SimplifiedChinese
+            | FontPreset::TraditionalChinese
+    )
+    .then_some(preset)
+}
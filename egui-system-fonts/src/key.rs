@@ -0,0 +1,121 @@
+//! The typed `font_data` key.
+
+use crate::is_system_font_key;
+use crate::names::family_from_key;
+use std::borrow::Borrow;
+use std::fmt;
+use std::ops::Deref;
+
+/// A key of [`FontDefinitions::font_data`](egui::FontDefinitions::font_data), as opposed to a font family
+/// name.
+///
+/// Keys created by this crate look like `egui-system-fonts/system:Noto Sans KR:0` and are what reports,
+/// events and [`installed_fonts`](crate::installed_fonts) hand out. A `FontKey` derefs to `str`, so it can
+/// be used wherever `egui` or this crate takes a key as `&str` (e.g. [`Anchor`](crate::Anchor)), and it
+/// compares equal to strings.
+///
+/// # Examples
+///
+/// ```
+/// # use egui_system_fonts::FontKey;
+/// let key = FontKey::from("egui-system-fonts/system:Noto Sans KR:0");
+/// assert_eq!(key, "egui-system-fonts/system:Noto Sans KR:0");
+/// assert_eq!(key.family(), Some("Noto Sans KR"));
+/// assert!(key.is_system());
+///
+/// let egui_key = FontKey::from("Hack");
+/// assert_eq!(egui_key.family(), None);
+/// assert_eq!(String::from(egui_key), "Hack");
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct FontKey(String);
+
+impl FontKey {
+    /// The key as a string slice.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Unwraps the key, e.g. to insert into `font_data`.
+    pub fn into_string(self) -> String {
+        self.0
+    }
+
+    /// Family name of a font installed by this crate, or `None` for keys this crate did not create.
+    pub fn family(&self) -> Option<&str> {
+        family_from_key(&self.0)
+    }
+
+    /// Whether the key was created by this crate (see [`is_system_font_key`]).
+    pub fn is_system(&self) -> bool {
+        is_system_font_key(&self.0)
+    }
+}
+
+impl Deref for FontKey {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl AsRef<str> for FontKey {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Borrow<str> for FontKey {
+    fn borrow(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for FontKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl From<String> for FontKey {
+    fn from(key: String) -> Self {
+        Self(key)
+    }
+}
+
+impl From<&str> for FontKey {
+    fn from(key: &str) -> Self {
+        Self(key.to_owned())
+    }
+}
+
+impl From<FontKey> for String {
+    fn from(key: FontKey) -> Self {
+        key.0
+    }
+}
+
+impl PartialEq<str> for FontKey {
+    fn eq(&self, other: &str) -> bool {
+        self.0 == other
+    }
+}
+
+impl PartialEq<&str> for FontKey {
+    fn eq(&self, other: &&str) -> bool {
+        self.0 == *other
+    }
+}
+
+impl PartialEq<String> for FontKey {
+    fn eq(&self, other: &String) -> bool {
+        &self.0 == other
+    }
+}
+
+impl PartialEq<FontKey> for String {
+    fn eq(&self, other: &FontKey) -> bool {
+        *self == other.0
+    }
+}
@@ -23,15 +23,114 @@
 //! # }
 //! ```
 //!
+//! # Environment overrides
+//!
+//! [`set_auto`] and [`extend_auto`] honor the `EGUI_SYSTEM_FONTS_REGION`, `EGUI_SYSTEM_FONTS_LOCALE`,
+//! `EGUI_SYSTEM_FONTS_STYLE` and `EGUI_SYSTEM_FONTS_DISABLE` environment variables. See [`EnvOverrides`].
+//!
 use egui::{FontData, FontDefinitions, FontFamily};
 use std::collections::BTreeMap;
-use system_fonts::FoundFontSource;
+use std::sync::Arc;
 pub use system_fonts::{FontPreset, FontRegion, FontStyle};
 
+mod byte_provider;
+mod cache;
+mod candidates;
+mod chain;
+mod collection;
+mod config;
+mod coverage;
+mod diagnose;
+mod diff;
+#[cfg(not(target_arch = "wasm32"))]
+mod dirs;
+pub mod discovery;
+mod embedded;
+mod env;
+mod error;
+mod events;
+mod font_cache;
+#[cfg(all(feature = "watch", not(target_arch = "wasm32")))]
+mod font_watch;
+mod han;
+mod key;
+mod locale;
+mod metadata;
+#[cfg(all(feature = "mmap", not(target_arch = "wasm32")))]
+mod mmap;
+mod names;
+mod options;
+mod provider;
+mod read;
+mod report;
+mod resolve;
+mod script;
+mod session;
+mod snapshot;
+#[cfg(feature = "subset")]
+mod subset;
+mod task;
+#[cfg(feature = "testing")]
+mod testing;
+#[cfg(test)]
+mod tests;
+mod text_styles;
+mod tweak;
+mod ui_font;
+mod watch;
+mod weights;
+
+pub use byte_provider::{set_from_byte_provider, FontByteProvider};
+pub use cache::default_cache_path;
+pub use candidates::{preset_candidates, supported_styles};
+pub use chain::{describe_current, fallback_chain, ChainEntry, FallbackChain};
+pub use config::{apply_config, apply_config_with_options, ConfigMode, FontConfig};
+pub use coverage::{is_script_covered, missing_glyphs, script_coverage, uncovered_chars};
+pub use diagnose::{
+    diagnose, diagnose_selection, is_region_available, CandidateDiagnosis, CandidateStatus,
+    Diagnosis, PresetDiagnosis,
+};
+pub use diff::{diff_definitions, DefinitionsDiff, FamilyChange};
+pub use discovery::{FoundFont, FoundFontSource};
+pub use embedded::register_embedded;
+pub use env::{EnvOverrides, ENV_DISABLE, ENV_LOCALE, ENV_REGION, ENV_STYLE};
+pub use error::FontError;
+pub use events::{LogEvent, LogSink, Progress, ProgressCallback, Verbosity};
+pub use font_cache::clear_font_cache;
+#[cfg(all(feature = "watch", not(target_arch = "wasm32")))]
+pub use font_watch::{watch_font_dirs, watch_font_dirs_with, FontDirWatcher};
+pub use han::pin_han_region;
+pub use key::FontKey;
+pub use locale::{region_for_locale, set_locale_region_overrides};
+pub use metadata::{read_metadata, FontMeta};
+pub use names::{
+    normalize_family_name, parse_region, parse_style, preset_label, region_label, region_name,
+    style_label, style_name, ParseNameError, PRESETS, REGIONS, STYLES,
+};
+pub use options::{
+    global_defaults, set_global_defaults, Anchor, CancelToken, CandidateFilter, FontOptions,
+    FontSelection, OrderPolicy, PrimaryScript, StyleClassifier,
+};
+pub use provider::{FontProvider, StaticProvider, SystemProvider};
+pub use report::{FontReport, SkipReason, SkippedFont};
+pub use script::{detect_scripts, Script, TextReport};
+pub use session::FontSession;
+pub use snapshot::{installed_fonts, restore, snapshot, FontSnapshot, InstalledFont};
+#[cfg(feature = "subset")]
+pub use subset::region_chars;
+pub use task::{set_async_with_options, set_auto_async, FontTask};
+#[cfg(feature = "testing")]
+pub use testing::{clear_test_fonts, set_test_fonts};
+pub use text_styles::apply_text_style_fonts;
+pub use tweak::{default_tweak, TweakOverride};
+pub use watch::{watch_locale, LocaleWatcher};
+pub use weights::{install_weight_variants, install_weight_variants_with_options, FontWeight};
+
 /// Replaces `egui` font definitions with system fonts detected from the current system locale.
 ///
 /// This overwrites the default `egui` fonts. If no matching fonts are found, the context is left unchanged
-/// and an empty list is returned.
+/// and an empty list is returned. Environment overrides (see [`EnvOverrides`]) take precedence over the
+/// detected locale and the `style` argument.
 ///
 /// # Examples
 ///
@@ -42,15 +141,341 @@ pub use system_fonts::{FontPreset, FontRegion, FontStyle};
 /// # }
 /// ```
 pub fn set_auto(ctx: &egui::Context, style: FontStyle) -> Vec<String> {
-    let (locale, region, fonts) = system_fonts::find_for_system_locale(style);
-    log::info!(
-        "Detected locale: {:?}, region: {:?}, style: {:?}, candidates: {}",
-        locale,
-        region,
+    set_auto_report(ctx, style).installed
+}
+
+/// Like [`set_auto`], but also returns the detected locale and region (see [`FontReport`]).
+///
+/// # Examples
+///
+/// ```no_run
+/// # use egui_system_fonts::{set_auto_report, FontStyle};
+/// # fn demo(ctx: &egui::Context) {
+/// let report = set_auto_report(ctx, FontStyle::Sans);
+/// println!("Detected: {:?} -> {:?}, installed {:?}", report.locale, report.region, report.installed);
+/// # }
+/// ```
+pub fn set_auto_report(ctx: &egui::Context, style: FontStyle) -> FontReport {
+    lenient(set_with_options(
+        ctx,
+        FontSelection::Auto,
+        style,
+        &global_defaults(),
+    ))
+}
+
+/// Builds the font definitions [`set_auto`] would apply, without needing an `egui::Context`.
+///
+/// Meant for startup code that runs before a context exists or before the first frame, such as the
+/// `eframe::run_native` app creator, so the first frame already uses the system fonts. Returns `None` if
+/// no matching system fonts were found (or [`EnvOverrides`] disabled the lookup); keep `egui`'s defaults in
+/// that case.
+///
+/// ```no_run
+/// # use egui_system_fonts::{initial_font_definitions, FontStyle};
+/// # fn app_creator(egui_ctx: &egui::Context) {
+/// // In the `eframe::run_native` app creator, with `egui_ctx = &cc.egui_ctx`:
+/// if let Some((defs, _report)) = initial_font_definitions(FontStyle::Sans) {
+///     egui_ctx.set_fonts(defs);
+/// }
+/// # }
+/// ```
+///
+/// # Examples
+///
+/// ```
+/// # use egui_system_fonts::{initial_font_definitions, is_system_font_key, FontStyle};
+/// if let Some((defs, report)) = initial_font_definitions(FontStyle::Sans) {
+///     let proportional = &defs.families[&egui::FontFamily::Proportional];
+///     assert!(is_system_font_key(&proportional[0]));
+///     assert_eq!(proportional.iter().filter(|k| is_system_font_key(k)).count(), report.installed.len());
+/// }
+/// ```
+pub fn initial_font_definitions(style: FontStyle) -> Option<(FontDefinitions, FontReport)> {
+    let options = global_defaults();
+    let resolution = resolve::resolve(FontSelection::Auto, style, &options)?;
+    let mut report = FontReport::default();
+    let slots = options.slots(resolution.plan.style);
+    let defs = replacement_definitions(
+        resolution.fonts,
+        &options,
+        slots,
+        &mut report,
+        &mut |_, _, _| {},
+    )?;
+    Some((defs, report))
+}
+
+/// Like [`set_auto`], but caches the resolved font files in `cache_path` to speed up later startups.
+///
+/// See [`FontOptions::cache_file`] for how the cache is validated.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use egui_system_fonts::{default_cache_path, set_auto, set_auto_cached, FontStyle};
+/// # fn demo(ctx: &egui::Context) {
+/// match default_cache_path() {
+///     Some(path) => set_auto_cached(ctx, FontStyle::Sans, path),
+///     None => set_auto(ctx, FontStyle::Sans),
+/// };
+/// # }
+/// ```
+pub fn set_auto_cached(
+    ctx: &egui::Context,
+    style: FontStyle,
+    cache_path: impl Into<std::path::PathBuf>,
+) -> Vec<String> {
+    let options = global_defaults().cache_file(cache_path);
+    lenient(set_with_options(ctx, FontSelection::Auto, style, &options)).installed
+}
+
+/// Like [`set_auto`], calling `on_progress(family, index, total)` as each font file is read.
+///
+/// `total` is the number of resolved candidates and `index` the position (from 0) of the one just read;
+/// candidates that are skipped don't report. The fonts are still applied once, after all reads.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use egui_system_fonts::{set_auto_with_progress, FontStyle};
+/// # fn demo(ctx: &egui::Context) {
+/// set_auto_with_progress(ctx, FontStyle::Sans, |family, index, total| {
+///     println!("[{}/{}] loaded {}", index + 1, total, family);
+/// });
+/// # }
+/// ```
+pub fn set_auto_with_progress(
+    ctx: &egui::Context,
+    style: FontStyle,
+    mut on_progress: impl FnMut(&str, usize, usize),
+) -> Vec<String> {
+    let options = global_defaults();
+    let placement = Placement {
+        progress: Some(&mut on_progress),
+        ..Placement::at(Anchor::Front)
+    };
+    lenient(set_resolved(
+        ctx,
+        FontSelection::Auto,
+        style,
+        &options,
+        placement,
+    ))
+    .installed
+}
+
+/// Replaces `egui` font definitions with the platform's own UI font (Segoe UI on Windows, San Francisco
+/// on macOS, the fontconfig default on Linux), backed by system fonts for the current locale.
+///
+/// The UI font is tried first and the region's fonts (as for [`set_auto`]) are stacked behind it, so text
+/// looks native and other scripts still render. Where no UI font can be determined, this behaves like
+/// [`set_auto`].
+///
+/// # Examples
+///
+/// ```no_run
+/// # use egui_system_fonts::set_system_ui;
+/// # fn demo(ctx: &egui::Context) {
+/// set_system_ui(ctx);
+/// # }
+/// ```
+pub fn set_system_ui(ctx: &egui::Context) -> Vec<String> {
+    lenient(set_system_ui_with_options(ctx, &global_defaults())).installed
+}
+
+/// Like [`set_system_ui`], with options. The UI font is queried through the options'
+/// [`provider`](FontOptions::provider) and tried after any [`FontOptions::prefer_families`].
+///
+/// # Examples
+///
+/// ```no_run
+/// # use egui_system_fonts::{set_system_ui_with_options, FontOptions};
+/// # fn demo(ctx: &egui::Context) {
+/// let report = set_system_ui_with_options(ctx, &FontOptions::new()).unwrap();
+/// println!("installed: {:?}", report.installed);
+/// # }
+/// ```
+pub fn set_system_ui_with_options(
+    ctx: &egui::Context,
+    options: &FontOptions,
+) -> Result<FontReport, FontError> {
+    let ui_families = options.font_provider().ui_families();
+    if options.logs() {
+        log::info!("System UI font: {:?}", ui_families);
+    }
+    let options = options.clone().prefer_families(ui_families);
+    set_with_options(ctx, FontSelection::Auto, FontStyle::Sans, &options)
+}
+
+/// Replaces `egui` font definitions with a single pan-CJK font (Noto Sans CJK, Source Han Sans) plus Latin
+/// fonts, instead of one font per CJK region.
+///
+/// A pan-CJK font draws Chinese, Japanese and Korean from one file, so an app that shows all of them keeps
+/// one large font in memory rather than four. The name matching the locale's region (`Noto Sans CJK JP`
+/// for Japanese) is tried first. Without a pan-CJK font, this falls back to stacking the regional fonts like
+/// [`set_auto`].
+///
+/// Glyph forms of shared Han characters follow the face that is installed, and fonts are always installed
+/// from the first face of their file. Per-language files (`NotoSansCJKjp-Regular.otf`,
+/// `SourceHanSansSC-Regular.otf`) give the region's forms. A collection holding every language
+/// (`NotoSansCJK-Regular.ttc`) gives the forms of its first face, whatever the locale.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use egui_system_fonts::{set_pan_cjk, FontStyle};
+/// # fn demo(ctx: &egui::Context) {
+/// let installed = set_pan_cjk(ctx, FontStyle::Sans);
+/// println!("installed: {installed:?}");
+/// # }
+/// ```
+pub fn set_pan_cjk(ctx: &egui::Context, style: FontStyle) -> Vec<String> {
+    lenient(set_pan_cjk_with_options(ctx, style, &global_defaults())).installed
+}
+
+/// Like [`set_pan_cjk`], with options. Fonts and the locale come from the options'
+/// [`provider`](FontOptions::provider).
+///
+/// # Examples
+///
+/// ```no_run
+/// # use egui_system_fonts::{set_pan_cjk_with_options, FontOptions, FontStyle};
+/// # fn demo(ctx: &egui::Context) {
+/// let report = set_pan_cjk_with_options(ctx, FontStyle::Sans, &FontOptions::new()).unwrap();
+/// println!("installed: {:?}", report.installed);
+/// # }
+/// ```
+pub fn set_pan_cjk_with_options(
+    ctx: &egui::Context,
+    style: FontStyle,
+    options: &FontOptions,
+) -> Result<FontReport, FontError> {
+    let plan = resolve::plan(
+        FontSelection::Auto,
+        style,
+        options.env.as_ref(),
+        options.font_provider(),
+        false,
+    );
+    let Some(plan) = plan else {
+        return set_with_options(ctx, FontSelection::Auto, style, options);
+    };
+    let region = plan.region.unwrap_or(FontRegion::Unknown);
+    let pan = candidates::pan_cjk_families(region, plan.style)
+        .into_iter()
+        .map(|family| FontPreset::Custom(vec![family]))
+        .find(|preset| {
+            !resolve::find(std::slice::from_ref(preset), plan.style, options).is_empty()
+        });
+
+    match pan {
+        Some(preset) => {
+            if options.logs() {
+                log::info!("Using pan-CJK font {:?}", preset);
+            }
+            let presets = vec![preset, FontPreset::Latin];
+            set_with_options(ctx, presets, plan.style, options)
+        }
+        None => {
+            if options.logs() {
+                log::info!("No pan-CJK font installed; stacking regional fonts");
+            }
+            set_with_options(ctx, FontSelection::Auto, style, options)
+        }
+    }
+}
+
+/// Sets up the app's own font (usually bundled with `include_bytes!`) with system fonts as fallback, in one
+/// call.
+///
+/// The result, applied to `ctx`:
+///
+/// - `FontFamily::Proportional`: `primary` (stored under `primary_name`), then `egui`'s default fonts, then
+///   the system fonts for the locale, as [`extend_auto`] adds them.
+/// - `FontFamily::Monospace`: `egui`'s default fonts, then only the system fonts that are fixed-pitch (see
+///   [`FontOptions::monospace_fixed_pitch_only`]), so code keeps lining up.
+///
+/// `primary` is applied even if no system font is found. Returns the report of the system fonts.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use egui_system_fonts::{setup_with_primary, FontStyle};
+/// # fn demo(ctx: &egui::Context, brand_bytes: &'static [u8]) {
+/// // let brand_bytes = include_bytes!("../assets/Brand-Regular.ttf");
+/// let brand = egui::FontData::from_static(brand_bytes);
+/// setup_with_primary(ctx, brand, "brand", FontStyle::Sans);
+/// # }
+/// ```
+pub fn setup_with_primary(
+    ctx: &egui::Context,
+    primary: FontData,
+    primary_name: &str,
+    style: FontStyle,
+) -> FontReport {
+    let options = global_defaults();
+    lenient(setup_with_primary_with_options(
+        ctx,
+        primary,
+        primary_name,
         style,
-        fonts.len()
+        &options,
+    ))
+}
+
+/// Like [`setup_with_primary`], with options. With [`FontOptions::primary_monospace`], `primary` also
+/// leads `FontFamily::Monospace`. [`FontOptions::order`] is ignored: system fonts always come last.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use egui_system_fonts::{setup_with_primary_with_options, FontOptions, FontStyle};
+/// # fn demo(ctx: &egui::Context, brand_bytes: &'static [u8]) {
+/// // let brand_bytes = include_bytes!("../assets/Brand-Regular.ttf");
+/// let brand = egui::FontData::from_static(brand_bytes);
+/// let options = FontOptions::new().primary_monospace(true);
+/// setup_with_primary_with_options(ctx, brand, "brand", FontStyle::Sans, &options).unwrap();
+/// # }
+/// ```
+pub fn setup_with_primary_with_options(
+    ctx: &egui::Context,
+    primary: FontData,
+    primary_name: &str,
+    style: FontStyle,
+    options: &FontOptions,
+) -> Result<FontReport, FontError> {
+    let mut defs = FontDefinitions::default();
+    defs.font_data
+        .insert(primary_name.to_owned(), Arc::new(primary));
+    insert_front(
+        &mut defs.families,
+        FontFamily::Proportional,
+        primary_name.to_owned(),
     );
-    set_found_fonts(ctx, fonts)
+    if options.primary_monospace {
+        insert_front(
+            &mut defs.families,
+            FontFamily::Monospace,
+            primary_name.to_owned(),
+        );
+    }
+
+    let options = options.clone().monospace_fixed_pitch_only(true);
+    let mut report = extend_with_options_at(
+        ctx,
+        &mut defs,
+        Anchor::Back,
+        FontSelection::Auto,
+        style,
+        &options,
+    )?;
+    if report.applications == 0 && !report.unchanged {
+        // No system font was added; the primary font still has to be applied.
+        snapshot::install(ctx, defs);
+        report.applications += 1;
+    }
+    Ok(report)
 }
 
 /// Replaces `egui` font definitions with system fonts for the given region.
@@ -67,8 +492,141 @@ pub fn set_auto(ctx: &egui::Context, style: FontStyle) -> Vec<String> {
 /// # }
 /// ```
 pub fn set_with_region(ctx: &egui::Context, region: FontRegion, style: FontStyle) -> Vec<String> {
-    let presets = system_fonts::presets_for_region(region);
-    set_with_presets(ctx, presets, style)
+    lenient(set_with_options(ctx, region, style, &global_defaults())).installed
+}
+
+/// Replaces `egui` font definitions with system fonts for `region`, resolving `FontFamily::Proportional`
+/// with one style and `FontFamily::Monospace` with another, e.g. serif body text with sans code.
+///
+/// Both resolutions feed one set of definitions, applied once. Returns the installed family names of
+/// each font family; if nothing is found for either, the context is left unchanged.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use egui_system_fonts::{set_split, FontRegion, FontStyle};
+/// # use egui::FontFamily;
+/// # fn demo(ctx: &egui::Context) {
+/// let installed = set_split(ctx, FontRegion::Japanese, FontStyle::Serif, FontStyle::Sans);
+/// println!("body: {:?}", installed[&FontFamily::Proportional]);
+/// println!("code: {:?}", installed[&FontFamily::Monospace]);
+/// # }
+/// ```
+pub fn set_split(
+    ctx: &egui::Context,
+    region: FontRegion,
+    proportional: FontStyle,
+    monospace: FontStyle,
+) -> BTreeMap<FontFamily, Vec<String>> {
+    let reports = set_split_with_options(ctx, region, proportional, monospace, &global_defaults())
+        .unwrap_or_default();
+    reports
+        .into_iter()
+        .map(|(family, report)| (family, report.installed))
+        .collect()
+}
+
+/// Like [`set_split`], with explicit options and a report for each font family.
+///
+/// [`FontOptions::monospace`] does not apply: each family gets the fonts of its own style. Both reports
+/// count the single application in [`FontReport::applications`].
+///
+/// # Examples
+///
+/// ```no_run
+/// # use egui_system_fonts::{set_split_with_options, FontOptions, FontRegion, FontStyle};
+/// # fn demo(ctx: &egui::Context) {
+/// let options = FontOptions::new();
+/// let reports =
+///     set_split_with_options(ctx, FontRegion::Japanese, FontStyle::Serif, FontStyle::Sans, &options).unwrap();
+/// for (family, report) in &reports {
+///     println!("{family:?}: {:?}", report.installed);
+/// }
+/// # }
+/// ```
+pub fn set_split_with_options(
+    ctx: &egui::Context,
+    selection: impl Into<FontSelection>,
+    proportional: FontStyle,
+    monospace: FontStyle,
+    options: &FontOptions,
+) -> Result<BTreeMap<FontFamily, FontReport>, FontError> {
+    let selection = selection.into();
+    let mut defs = FontDefinitions::default();
+    let mut reports = BTreeMap::new();
+    let mut changed = false;
+    let mut han_region = None;
+
+    for (slot, style) in [
+        (FontFamily::Proportional, proportional),
+        (FontFamily::Monospace, monospace),
+    ] {
+        let mut report = FontReport::default();
+        if let Some(resolution) = resolve::resolve(selection.clone(), style, options) {
+            if options.strict {
+                resolve::check_strict(&resolution.plan, options)?;
+            }
+            report.locale = resolution.plan.locale.clone();
+            report.region = resolution.plan.region;
+            han_region = han_region.or(resolution.plan.region.filter(|_| options.pin_han));
+            let slots = std::slice::from_ref(&slot);
+            let no_progress = &mut |_: &str, _, _| {};
+            if let Some(slot_defs) =
+                replacement_definitions(resolution.fonts, options, slots, &mut report, no_progress)
+            {
+                defs.font_data.extend(slot_defs.font_data);
+                if let Some(list) = slot_defs.families.get(&slot) {
+                    defs.families.insert(slot.clone(), list.clone());
+                }
+                changed = true;
+            }
+        }
+        reports.insert(slot, report);
+    }
+
+    if !changed {
+        return Ok(reports);
+    }
+    if let Some(region) = han_region {
+        han::pin(&mut defs, region);
+    }
+    let unchanged = !options.force && snapshot::is_applied(ctx, &defs);
+    if !unchanged {
+        snapshot::install_replacement(ctx, defs);
+    }
+    for report in reports.values_mut() {
+        report.unchanged = unchanged;
+        report.applications = usize::from(!unchanged);
+    }
+    Ok(reports)
+}
+
+/// Like [`set_with_region`], but trims each font to the glyphs needed for `chars`.
+///
+/// Useful for memory-constrained deployments that display a known, small character set: a full CJK font
+/// is tens of megabytes, a subset for a few hundred characters is a few hundred kilobytes. Characters
+/// outside `chars` will not render with these fonts. Requires the `subset` feature; see
+/// [`FontOptions::subset`] to combine subsetting with other options.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use egui_system_fonts::{set_with_subset, FontRegion, FontStyle};
+/// # use std::collections::HashSet;
+/// # fn demo(ctx: &egui::Context) {
+/// let chars: HashSet<char> = include_str!("../README.md").chars().collect();
+/// set_with_subset(ctx, FontRegion::Korean, FontStyle::Sans, &chars);
+/// # }
+/// ```
+#[cfg(feature = "subset")]
+pub fn set_with_subset(
+    ctx: &egui::Context,
+    region: FontRegion,
+    style: FontStyle,
+    chars: &std::collections::HashSet<char>,
+) -> Vec<String> {
+    let options = global_defaults().subset(chars.iter().copied());
+    lenient(set_with_options(ctx, region, style, &options)).installed
 }
 
 /// Replaces `egui` font definitions with system fonts resolved from the given presets.
@@ -89,8 +647,40 @@ pub fn set_with_presets<I>(ctx: &egui::Context, presets: I, style: FontStyle) ->
 where
     I: IntoIterator<Item = FontPreset>,
 {
-    let fonts = system_fonts::find_from_presets(presets, style);
-    set_found_fonts(ctx, fonts)
+    let selection = FontSelection::Presets(presets.into_iter().collect());
+    lenient(set_with_options(ctx, selection, style, &global_defaults())).installed
+}
+
+/// Replaces `egui` font definitions with system fonts for several regions, in the given order.
+///
+/// Each region expands to its default presets; the lists are concatenated and repeated presets (such as
+/// the Latin fallback every region ends with) are kept only at their first position. This lets a
+/// Japanese-first app put Japanese before Korean without listing presets by hand. Returns the installed
+/// family names in priority order.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use egui_system_fonts::{set_with_regions, FontRegion, FontStyle};
+/// # fn demo(ctx: &egui::Context) {
+/// set_with_regions(ctx, &[FontRegion::Japanese, FontRegion::Korean], FontStyle::Sans);
+/// # }
+/// ```
+pub fn set_with_regions(
+    ctx: &egui::Context,
+    regions: &[FontRegion],
+    style: FontStyle,
+) -> Vec<String> {
+    let mut presets: Vec<FontPreset> = Vec::new();
+    for preset in regions
+        .iter()
+        .flat_map(|&region| system_fonts::presets_for_region(region))
+    {
+        if !presets.iter().any(|p| provider::same_preset(p, &preset)) {
+            presets.push(preset);
+        }
+    }
+    set_with_presets(ctx, presets, style)
 }
 
 /// Appends system fonts as fallback families to an existing `FontDefinitions`.
@@ -99,7 +689,7 @@ where
 /// If at least one font is added, the updated definitions are applied to `ctx`.
 ///
 /// Returns the newly added font family names (in priority order). If nothing is added, returns an empty list
-/// and does not modify the context.
+/// and does not modify the context. Environment overrides (see [`EnvOverrides`]) apply as in [`set_auto`].
 ///
 /// # Examples
 ///
@@ -115,19 +705,51 @@ pub fn extend_auto(
     defs: &mut FontDefinitions,
     style: FontStyle,
 ) -> Vec<String> {
-    let (locale, region, fonts) = system_fonts::find_for_system_locale(style);
-    log::info!(
-        "Detected locale: {:?}, region: {:?}, style: {:?}, candidates: {}",
-        locale,
-        region,
+    extend_auto_report(ctx, defs, style).installed
+}
+
+/// Like [`extend_auto`], but also returns the detected locale and region (see [`FontReport`]).
+pub fn extend_auto_report(
+    ctx: &egui::Context,
+    defs: &mut FontDefinitions,
+    style: FontStyle,
+) -> FontReport {
+    let options = global_defaults();
+    lenient(extend_with_options(
+        ctx,
+        defs,
+        FontSelection::Auto,
         style,
-        fonts.len()
-    );
-    let installed = append_found_fonts(defs, fonts);
-    if !installed.is_empty() {
-        ctx.set_fonts(defs.clone());
-    }
-    installed
+        &options,
+    ))
+}
+
+/// Puts system fonts for the system locale first in `defs`, keeping every font already there.
+///
+/// Like [`set_auto`], the system fonts take priority over `egui`'s defaults; unlike it, nothing is
+/// replaced, so fonts you registered earlier stay as fallbacks. Pass the definitions currently in effect,
+/// e.g. from [`snapshot`](crate::snapshot()), since `egui` has no getter for them. This is
+/// [`extend_with_options`] with [`OrderPolicy::PreferSystem`].
+///
+/// Returns the installed font family names (in priority order). If nothing is added, returns an empty list
+/// and does not modify the context.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use egui_system_fonts::{set_auto_merging, snapshot, FontStyle};
+/// # fn demo(ctx: &egui::Context) {
+/// let mut defs = snapshot(ctx).definitions().clone();
+/// let installed = set_auto_merging(ctx, &mut defs, FontStyle::Sans);
+/// println!("installed in front of the existing fonts: {installed:?}");
+/// # }
+/// ```
+pub fn set_auto_merging(
+    ctx: &egui::Context,
+    defs: &mut FontDefinitions,
+    style: FontStyle,
+) -> Vec<String> {
+    extend_auto_front(ctx, defs, style)
 }
 
 /// Appends system fonts for the given region as fallback families to an existing `FontDefinitions`.
@@ -150,8 +772,14 @@ pub fn extend_with_region(
     region: FontRegion,
     style: FontStyle,
 ) -> Vec<String> {
-    let presets = system_fonts::presets_for_region(region);
-    extend_with_presets(ctx, defs, presets, style)
+    lenient(extend_with_options(
+        ctx,
+        defs,
+        region,
+        style,
+        &global_defaults(),
+    ))
+    .installed
 }
 
 /// Appends system fonts resolved from the given presets as fallback families to an existing `FontDefinitions`.
@@ -178,108 +806,1174 @@ pub fn extend_with_presets<I>(
 where
     I: IntoIterator<Item = FontPreset>,
 {
-    let fonts = system_fonts::find_from_presets(presets, style);
-    let installed = append_found_fonts(defs, fonts);
-    if !installed.is_empty() {
-        ctx.set_fonts(defs.clone());
-    }
-    installed
-}
-
-fn set_found_fonts(ctx: &egui::Context, fonts: Vec<system_fonts::FoundFont>) -> Vec<String> {
-    let mut defs = FontDefinitions::default();
-
-    let mut installed_names: Vec<String> = Vec::new();
-    let mut keys_in_priority: Vec<String> = Vec::new();
-
-    for f in fonts {
-        let Some(bytes) = read_font_bytes(f.source) else {
-            continue;
-        };
-
-        defs.font_data
-            .insert(f.key.clone(), FontData::from_owned(bytes).into());
-
-        keys_in_priority.push(f.key.clone());
-        installed_names.push(f.family);
-    }
-
-    if installed_names.is_empty() {
-        log::warn!("No matching system fonts found.");
-        return vec![];
-    }
-
-    for key in keys_in_priority.into_iter().rev() {
-        insert_front(&mut defs.families, FontFamily::Proportional, key.clone());
-        insert_front(&mut defs.families, FontFamily::Monospace, key);
-    }
-
-    ctx.set_fonts(defs);
-    log::info!("Set fonts (family names): {:?}", installed_names);
-
-    installed_names
+    let selection = FontSelection::Presets(presets.into_iter().collect());
+    lenient(extend_with_options(
+        ctx,
+        defs,
+        selection,
+        style,
+        &global_defaults(),
+    ))
+    .installed
 }
 
-fn append_found_fonts(
+/// Like [`extend_auto`], but puts the system fonts in front of the fonts already in each family.
+///
+/// Nothing is removed or replaced (except an older copy of the same font family, which moves to the new
+/// position); the system fonts just take priority, so they are used even for characters the existing
+/// fonts cover. This is [`extend_with_options`] with [`OrderPolicy::PreferSystem`].
+///
+/// # Examples
+///
+/// ```no_run
+/// # use egui_system_fonts::{extend_auto_front, snapshot, FontStyle};
+/// # fn demo(ctx: &egui::Context) {
+/// let mut defs = snapshot(ctx).definitions().clone();
+/// extend_auto_front(ctx, &mut defs, FontStyle::Sans);
+/// # }
+/// ```
+pub fn extend_auto_front(
+    ctx: &egui::Context,
     defs: &mut FontDefinitions,
-    fonts: Vec<system_fonts::FoundFont>,
+    style: FontStyle,
 ) -> Vec<String> {
-    let mut installed_names: Vec<String> = Vec::new();
-    let mut keys_in_priority: Vec<String> = Vec::new();
+    extend_front(ctx, defs, FontSelection::Auto, style)
+}
 
-    for f in fonts {
-        if defs.font_data.contains_key(&f.key) {
-            continue;
+/// Like [`extend_with_region`], but puts the system fonts in front of the fonts already in each family
+/// (see [`extend_auto_front`]).
+///
+/// # Examples
+///
+/// ```no_run
+/// # use egui_system_fonts::{extend_with_region_front, FontRegion, FontStyle};
+/// # fn demo(ctx: &egui::Context) {
+/// let mut defs = egui::FontDefinitions::default();
+/// extend_with_region_front(ctx, &mut defs, FontRegion::Japanese, FontStyle::Sans);
+/// # }
+/// ```
+pub fn extend_with_region_front(
+    ctx: &egui::Context,
+    defs: &mut FontDefinitions,
+    region: FontRegion,
+    style: FontStyle,
+) -> Vec<String> {
+    extend_front(ctx, defs, region.into(), style)
+}
+
+/// Like [`extend_with_presets`], but puts the system fonts in front of the fonts already in each family
+/// (see [`extend_auto_front`]). The fonts keep their priority order among themselves.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use egui_system_fonts::{extend_with_presets_front, FontPreset, FontStyle};
+/// # fn demo(ctx: &egui::Context) {
+/// let mut defs = egui::FontDefinitions::default();
+/// let presets = [FontPreset::Japanese, FontPreset::Latin];
+/// extend_with_presets_front(ctx, &mut defs, presets, FontStyle::Sans);
+/// # }
+/// ```
+pub fn extend_with_presets_front<I>(
+    ctx: &egui::Context,
+    defs: &mut FontDefinitions,
+    presets: I,
+    style: FontStyle,
+) -> Vec<String>
+where
+    I: IntoIterator<Item = FontPreset>,
+{
+    let selection = FontSelection::Presets(presets.into_iter().collect());
+    extend_front(ctx, defs, selection, style)
+}
+
+fn extend_front(
+    ctx: &egui::Context,
+    defs: &mut FontDefinitions,
+    selection: FontSelection,
+    style: FontStyle,
+) -> Vec<String> {
+    let options = global_defaults().order(OrderPolicy::PreferSystem);
+    lenient(extend_with_options(ctx, defs, selection, style, &options)).installed
+}
+
+/// Resolves and reads system fonts for a region without touching `egui`.
+///
+/// Returns `(key, bytes, family)` tuples in priority order, where `key` is the same namespaced key the
+/// `set_*` functions would insert into `font_data`. Useful for renderers other than `egui` that want the
+/// same system font resolution.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use egui_system_fonts::{resolve_bytes, FontRegion, FontStyle};
+/// for (key, bytes, family) in resolve_bytes(FontRegion::Korean, FontStyle::Sans) {
+///     println!("{family} ({key}): {} bytes", bytes.len());
+/// }
+/// ```
+pub fn resolve_bytes(region: FontRegion, style: FontStyle) -> Vec<(FontKey, Vec<u8>, String)> {
+    let options = global_defaults();
+    let Some(resolution) = resolve::resolve(FontSelection::Region(region), style, &options) else {
+        return Vec::new();
+    };
+    let mut report = FontReport::default();
+    let mut installed = read::Installed::default();
+    read::read_found_fonts(
+        resolution.fonts,
+        &options,
+        &mut report,
+        &mut installed,
+        &mut |_, _, _| {},
+    )
+    .into_iter()
+    .map(|font| (font.key, font.data.font.to_vec(), font.family))
+    .collect()
+}
+
+/// Resolves and reads system fonts for a region into shared [`FontData`], without touching `egui`.
+///
+/// Returns `(key, data)` pairs in priority order, with the keys and tweaks the `set_*` functions would use.
+/// `egui` keeps font bytes in [`FontData`] rather than `Arc<[u8]>`, but stores that behind an `Arc` in
+/// [`FontDefinitions::font_data`], so inserting `data` there and handing the same `Arc` to, say, a PDF
+/// exporter (which reads `data.font`) keeps a single copy of each font in memory. Fonts are looked up like
+/// [`set_with_region`], through the [`global_defaults`].
+///
+/// # Examples
+///
+/// ```no_run
+/// # use egui_system_fonts::{resolve_shared, FontRegion, FontStyle};
+/// # fn demo(defs: &mut egui::FontDefinitions) {
+/// for (key, data) in resolve_shared(FontRegion::Korean, FontStyle::Sans) {
+///     defs.font_data.insert(key.to_string(), data);
+/// }
+/// # }
+/// ```
+pub fn resolve_shared(region: FontRegion, style: FontStyle) -> Vec<(FontKey, Arc<FontData>)> {
+    let options = global_defaults();
+    let Some(resolution) = resolve::resolve(FontSelection::Region(region), style, &options) else {
+        return Vec::new();
+    };
+    let mut report = FontReport::default();
+    read::read_found_fonts(
+        resolution.fonts,
+        &options,
+        &mut report,
+        &mut read::Installed::default(),
+        &mut |_, _, _| {},
+    )
+    .into_iter()
+    .map(|font| (font.key, font.data))
+    .collect()
+}
+
+/// Like [`set_with_options`], but only installs candidates accepted by `filter`.
+///
+/// `filter` runs before any font file is read.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use egui_system_fonts::{set_filtered, FontSelection, FontStyle};
+/// # fn demo(ctx: &egui::Context) {
+/// let denylist = ["NanumGothic", "SimHei"];
+/// set_filtered(ctx, FontSelection::Auto, FontStyle::Sans, move |f| {
+///     !denylist.contains(&f.family.as_str())
+/// });
+/// # }
+/// ```
+pub fn set_filtered(
+    ctx: &egui::Context,
+    selection: impl Into<FontSelection>,
+    style: FontStyle,
+    filter: impl Fn(&FoundFont) -> bool + Send + Sync + 'static,
+) -> FontReport {
+    let options = global_defaults().filter(filter);
+    lenient(set_with_options(ctx, selection, style, &options))
+}
+
+/// Like [`extend_with_options`], but only installs candidates accepted by `filter`.
+///
+/// `filter` runs before any font file is read.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use egui_system_fonts::{extend_filtered, FontRegion, FontStyle};
+/// # fn demo(ctx: &egui::Context) {
+/// let mut defs = egui::FontDefinitions::default();
+/// extend_filtered(ctx, &mut defs, FontRegion::Korean, FontStyle::Sans, |f| {
+///     f.family.starts_with("Noto")
+/// });
+/// # }
+/// ```
+pub fn extend_filtered(
+    ctx: &egui::Context,
+    defs: &mut FontDefinitions,
+    selection: impl Into<FontSelection>,
+    style: FontStyle,
+    filter: impl Fn(&FoundFont) -> bool + Send + Sync + 'static,
+) -> FontReport {
+    let options = global_defaults().filter(filter);
+    lenient(extend_with_options(ctx, defs, selection, style, &options))
+}
+
+/// Like [`extend_with_presets`], but appends to a single font family only.
+///
+/// With `target: Some(family)` the fonts are appended to that family only (for example only
+/// `FontFamily::Proportional`, keeping Monospace lean). `None` appends to the families
+/// [`extend_with_presets`] would use: both Proportional and Monospace, or only Proportional for serif
+/// fonts unless [`FontOptions::monospace`] says otherwise. Returns the newly added font family names (in
+/// priority order).
+///
+/// # Examples
+///
+/// ```no_run
+/// # use egui_system_fonts::{extend_with_presets_target, FontPreset, FontStyle};
+/// # fn demo(ctx: &egui::Context) {
+/// let mut defs = egui::FontDefinitions::default();
+/// let presets = [FontPreset::Korean];
+/// let target = Some(egui::FontFamily::Proportional);
+/// extend_with_presets_target(ctx, &mut defs, presets, FontStyle::Sans, target);
+/// # }
+/// ```
+pub fn extend_with_presets_target<I>(
+    ctx: &egui::Context,
+    defs: &mut FontDefinitions,
+    presets: I,
+    style: FontStyle,
+    target: Option<FontFamily>,
+) -> Vec<String>
+where
+    I: IntoIterator<Item = FontPreset>,
+{
+    let options = global_defaults();
+    let selection = FontSelection::Presets(presets.into_iter().collect());
+    let target = target.map(|family| [family]);
+    let placement = Placement {
+        slots: target.as_ref().map(|family| family.as_slice()),
+        ..Placement::at(options.order.anchor())
+    };
+    lenient(extend_resolved(
+        ctx, defs, selection, style, &options, placement,
+    ))
+    .installed
+}
+
+/// Appends system fonts for only the scripts that appear in `text`.
+///
+/// Scans the text's code points, maps the detected [`Script`]s to presets (Hangul to Korean, kana to
+/// Japanese, Cyrillic to Cyrillic; Han follows kana/Hangul in the same text, otherwise the system locale)
+/// and extends `defs` with those presets only. Text that `egui`'s default fonts already cover (Latin,
+/// digits, punctuation) is a cheap no-op that does not touch `ctx`.
+///
+/// # Examples
+///
+/// ```
+/// # use egui_system_fonts::{extend_for_text, FontStyle};
+/// let ctx = egui::Context::default();
+/// let mut defs = egui::FontDefinitions::default();
+/// let report = extend_for_text(&ctx, &mut defs, "Nothing special here.", FontStyle::Sans);
+/// assert!(report.scripts.is_empty());
+/// assert!(report.installed.is_empty());
+/// ```
+///
+/// ```no_run
+/// # use egui_system_fonts::{extend_for_text, FontStyle};
+/// # fn demo(ctx: &egui::Context, user_text: &str) {
+/// let mut defs = egui::FontDefinitions::default();
+/// let report = extend_for_text(ctx, &mut defs, user_text, FontStyle::Sans);
+/// println!("scripts: {:?}, added: {:?}", report.scripts, report.installed);
+/// # }
+/// ```
+pub fn extend_for_text(
+    ctx: &egui::Context,
+    defs: &mut FontDefinitions,
+    text: &str,
+    style: FontStyle,
+) -> TextReport {
+    let scripts = detect_scripts(text);
+    if scripts.is_empty() {
+        return TextReport::default();
+    }
+
+    let presets = script::presets_for_scripts(&scripts, style);
+    let options = global_defaults();
+    let installed = lenient(extend_with_options(ctx, defs, presets, style, &options)).installed;
+    TextReport { scripts, installed }
+}
+
+/// Appends the installed fonts needed to render `chars`, whatever their script.
+///
+/// Characters the Proportional fonts of `defs` already cover are ignored. For the rest, fonts are taken in
+/// the order of [`discovery::find_for_chars`] as long as each one covers a character the fonts before it
+/// do not, so a few well-chosen fonts are installed rather than every font that has a glyph. Returns the
+/// installed family names, like [`extend_with_region`].
+///
+/// # Examples
+///
+/// ```no_run
+/// # use egui_system_fonts::{extend_for_chars, FontStyle};
+/// # fn demo(ctx: &egui::Context, defs: &mut egui::FontDefinitions, pasted: &str) {
+/// let chars: Vec<char> = pasted.chars().collect();
+/// let added = extend_for_chars(ctx, defs, &chars, FontStyle::Sans);
+/// println!("added {added:?}");
+/// # }
+/// ```
+pub fn extend_for_chars(
+    ctx: &egui::Context,
+    defs: &mut FontDefinitions,
+    chars: &[char],
+    style: FontStyle,
+) -> Vec<String> {
+    let options = global_defaults();
+    lenient(extend_for_chars_with_options(
+        ctx, defs, chars, style, &options,
+    ))
+    .installed
+}
+
+/// Like [`extend_for_chars`], with options. Fonts are looked up through the options'
+/// [`provider`](FontOptions::provider) and inserted as [`FontOptions::order`] says.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use egui_system_fonts::{extend_for_chars_with_options, snapshot, FontOptions, FontStyle};
+/// # fn demo(ctx: &egui::Context) {
+/// let mut defs = snapshot(ctx).definitions().clone();
+/// let chars: Vec<char> = "→ ⌘ 한".chars().collect();
+/// extend_for_chars_with_options(ctx, &mut defs, &chars, FontStyle::Sans, &FontOptions::new()).unwrap();
+/// # }
+/// ```
+pub fn extend_for_chars_with_options(
+    ctx: &egui::Context,
+    defs: &mut FontDefinitions,
+    chars: &[char],
+    style: FontStyle,
+    options: &FontOptions,
+) -> Result<FontReport, FontError> {
+    let faces = coverage::FaceSet::for_family(defs, &FontFamily::Proportional);
+    let mut missing: Vec<char> = Vec::new();
+    for &c in chars {
+        if !coverage::is_ignorable(c) && !missing.contains(&c) && !faces.covers(c) {
+            missing.push(c);
         }
+    }
+    drop(faces);
+    if missing.is_empty() {
+        return Ok(FontReport::default());
+    }
 
-        let Some(bytes) = read_font_bytes(f.source) else {
+    let mut fonts = Vec::new();
+    for font in options.font_provider().find_for_chars(&missing, style) {
+        let covered = coverage::covered_chars(&font, &missing);
+        if covered.is_empty() {
             continue;
+        }
+        missing.retain(|c| !covered.contains(c));
+        fonts.push(font);
+        if missing.is_empty() {
+            break;
+        }
+    }
+    if !missing.is_empty() && options.logs() {
+        log::info!("No installed font covers {:?}", missing);
+    }
+    apply_fonts(
+        ctx,
+        fonts,
+        ApplyMode::Extend(defs),
+        options,
+        None,
+        Placement::at(options.order.anchor()),
+    )
+}
+
+/// Replaces `egui` font definitions with system fonts, using explicit options.
+///
+/// Behaves like the other `set_*` functions, but returns a [`FontReport`] that also lists the
+/// candidates that were skipped and why. Fails only when [`FontOptions::strict`] is set.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use egui_system_fonts::{set_with_options, FontOptions, FontRegion, FontStyle};
+/// # use std::time::Duration;
+/// # fn demo(ctx: &egui::Context) {
+/// let options = FontOptions::new().candidate_timeout(Duration::from_secs(1));
+/// let report = set_with_options(ctx, FontRegion::Korean, FontStyle::Sans, &options).unwrap();
+/// println!("installed: {:?}", report.installed);
+/// # }
+/// ```
+pub fn set_with_options(
+    ctx: &egui::Context,
+    selection: impl Into<FontSelection>,
+    style: FontStyle,
+    options: &FontOptions,
+) -> Result<FontReport, FontError> {
+    let placement = Placement::at(Anchor::Front);
+    set_resolved(ctx, selection.into(), style, options, placement)
+}
+
+/// Resolves `selection` and replaces the definitions of `ctx` with the fonts found; see [`apply_fonts`].
+fn set_resolved(
+    ctx: &egui::Context,
+    selection: FontSelection,
+    style: FontStyle,
+    options: &FontOptions,
+    placement: Placement<'_>,
+) -> Result<FontReport, FontError> {
+    let Some(resolution) = resolve::resolve(selection, style, options) else {
+        return Ok(FontReport::default());
+    };
+    if options.strict {
+        resolve::check_strict(&resolution.plan, options)?;
+    }
+    apply_fonts(
+        ctx,
+        resolution.fonts,
+        ApplyMode::Replace,
+        options,
+        Some(&resolution.plan),
+        placement,
+    )
+}
+
+/// Appends system fonts as fallback families to an existing `FontDefinitions`, using explicit options.
+///
+/// Behaves like the other `extend_*` functions, but returns a [`FontReport`] that also lists the
+/// candidates that were skipped and why. Fails only when [`FontOptions::strict`] is set.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use egui_system_fonts::{extend_with_options, FontOptions, FontSelection, FontStyle};
+/// # fn demo(ctx: &egui::Context) {
+/// let mut defs = egui::FontDefinitions::default();
+/// let options = FontOptions::new();
+/// let report = extend_with_options(ctx, &mut defs, FontSelection::Auto, FontStyle::Sans, &options).unwrap();
+/// println!("added: {:?}", report.installed);
+/// # }
+/// ```
+pub fn extend_with_options(
+    ctx: &egui::Context,
+    defs: &mut FontDefinitions,
+    selection: impl Into<FontSelection>,
+    style: FontStyle,
+    options: &FontOptions,
+) -> Result<FontReport, FontError> {
+    extend_with_options_at(ctx, defs, options.order.anchor(), selection, style, options)
+}
+
+/// Like [`extend_with_options`], but inserts the fonts at `anchor` in each font family.
+///
+/// With [`Anchor::Before`] or [`Anchor::After`] the new fonts are inserted next to an existing
+/// `font_data` key, contiguously and in priority order. A family list without that key gets the fonts
+/// appended instead, with a note in [`FontReport::warnings`]. `anchor` takes the place of
+/// [`FontOptions::order`].
+///
+/// # Examples
+///
+/// ```no_run
+/// # use egui_system_fonts::{extend_with_options_at, snapshot, Anchor, FontOptions, FontRegion, FontStyle};
+/// # fn demo(ctx: &egui::Context) {
+/// // Keep the brand font first, then the system fonts, then egui's own.
+/// let mut defs = snapshot(ctx).definitions().clone();
+/// let options = FontOptions::new();
+/// extend_with_options_at(ctx, &mut defs, Anchor::After("brand"), FontRegion::Korean, FontStyle::Sans, &options)
+///     .unwrap();
+/// # }
+/// ```
+pub fn extend_with_options_at(
+    ctx: &egui::Context,
+    defs: &mut FontDefinitions,
+    anchor: Anchor<'_>,
+    selection: impl Into<FontSelection>,
+    style: FontStyle,
+    options: &FontOptions,
+) -> Result<FontReport, FontError> {
+    let placement = Placement::at(anchor);
+    extend_resolved(ctx, defs, selection.into(), style, options, placement)
+}
+
+/// Like [`extend_auto`], but takes `defs` by value, so applying them does not copy them.
+///
+/// For callers that build definitions only to extend and apply them. If nothing is added, `ctx` is left
+/// unchanged and `defs` is dropped. [`snapshot`](crate::snapshot()) returns the applied definitions.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use egui_system_fonts::{extend_auto_into, FontStyle};
+/// # fn demo(ctx: &egui::Context) {
+/// extend_auto_into(ctx, egui::FontDefinitions::default(), FontStyle::Sans);
+/// # }
+/// ```
+pub fn extend_auto_into(
+    ctx: &egui::Context,
+    defs: FontDefinitions,
+    style: FontStyle,
+) -> Vec<String> {
+    lenient(extend_with_options_into(
+        ctx,
+        defs,
+        FontSelection::Auto,
+        style,
+        &global_defaults(),
+    ))
+    .installed
+}
+
+/// Like [`extend_with_options`], but takes `defs` by value, so applying them does not copy them.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use egui_system_fonts::{extend_with_options_into, FontOptions, FontRegion, FontStyle};
+/// # fn demo(ctx: &egui::Context) {
+/// let defs = egui::FontDefinitions::default();
+/// let options = FontOptions::new();
+/// extend_with_options_into(ctx, defs, FontRegion::Korean, FontStyle::Sans, &options).unwrap();
+/// # }
+/// ```
+pub fn extend_with_options_into(
+    ctx: &egui::Context,
+    mut defs: FontDefinitions,
+    selection: impl Into<FontSelection>,
+    style: FontStyle,
+    options: &FontOptions,
+) -> Result<FontReport, FontError> {
+    let placement = Placement {
+        handoff: Handoff::Move,
+        ..Placement::at(options.order.anchor())
+    };
+    extend_resolved(ctx, &mut defs, selection.into(), style, options, placement)
+}
+
+/// Resolves `selection` and extends `defs` with the fonts found; see [`apply_fonts`].
+fn extend_resolved(
+    ctx: &egui::Context,
+    defs: &mut FontDefinitions,
+    selection: FontSelection,
+    style: FontStyle,
+    options: &FontOptions,
+    placement: Placement<'_>,
+) -> Result<FontReport, FontError> {
+    let Some(resolution) = resolve::resolve(selection, style, options) else {
+        return Ok(FontReport::default());
+    };
+    if options.strict {
+        resolve::check_strict(&resolution.plan, options)?;
+    }
+    apply_fonts(
+        ctx,
+        resolution.fonts,
+        ApplyMode::Extend(defs),
+        options,
+        Some(&resolution.plan),
+        placement,
+    )
+}
+
+/// Unwraps the result of an option-based call whose options cannot fail.
+///
+/// Only [`FontOptions::strict`] produces errors.
+fn lenient(result: Result<FontReport, FontError>) -> FontReport {
+    result.unwrap_or_default()
+}
+
+/// How [`apply_found_fonts`] installs fonts.
+#[derive(Debug)]
+pub enum ApplyMode<'a> {
+    /// Start from `FontDefinitions::default()` and put the fonts first (like `set_*`).
+    Replace,
+    /// Append the fonts as fallbacks to existing definitions (like `extend_*`).
+    Extend(&'a mut FontDefinitions),
+}
+
+/// Reads and installs an explicit list of found fonts.
+///
+/// This is the second half of every `set_*`/`extend_*` call, so a list obtained from the
+/// [`discovery`] functions can be inspected or filtered before it is applied.
+///
+/// # Examples
+///
+/// Drop user-installed fonts before applying:
+///
+/// ```no_run
+/// # use egui_system_fonts::{apply_found_fonts, discovery, ApplyMode, FontOptions, FontStyle, FoundFontSource};
+/// # fn demo(ctx: &egui::Context) {
+/// let (_locale, _region, mut fonts) = discovery::find_for_system_locale(FontStyle::Sans);
+/// fonts.retain(|f| match &f.source {
+///     FoundFontSource::Path(path) => !path.starts_with("/home"),
+///     FoundFontSource::Bytes(_) => true,
+/// });
+/// apply_found_fonts(ctx, fonts, ApplyMode::Replace, &FontOptions::new());
+/// # }
+/// ```
+pub fn apply_found_fonts(
+    ctx: &egui::Context,
+    fonts: Vec<FoundFont>,
+    mode: ApplyMode<'_>,
+    options: &FontOptions,
+) -> FontReport {
+    lenient(apply_fonts(
+        ctx,
+        fonts,
+        mode,
+        options,
+        None,
+        Placement::at(options.order.anchor()),
+    ))
+}
+
+/// [`apply_found_fonts`] for fonts resolved from `plan`, whose style picks the slots and whose region is
+/// pinned first with [`FontOptions::pin_han`]. Without a plan the fonts are treated as sans. `placement`
+/// says where the fonts go in [`ApplyMode::Extend`] and who is told about each font read.
+///
+/// With [`FontOptions::strict`] and a plan, fails (leaving `ctx` and the definitions alone) if the result
+/// does not pass [`check_installed`].
+fn apply_fonts(
+    ctx: &egui::Context,
+    fonts: Vec<FoundFont>,
+    mode: ApplyMode<'_>,
+    options: &FontOptions,
+    plan: Option<&resolve::Plan>,
+    placement: Placement<'_>,
+) -> Result<FontReport, FontError> {
+    let mut report = plan_report(plan);
+    let handoff = placement.handoff;
+    match mode {
+        ApplyMode::Replace => {
+            let mut defs = FontDefinitions::empty();
+            let built = prepare_fonts(
+                &mut defs,
+                true,
+                fonts,
+                options,
+                plan,
+                placement,
+                &mut report,
+            )?;
+            let applied = built
+                && install_fonts(
+                    ctx,
+                    &mut defs,
+                    Handoff::Move,
+                    true,
+                    options.force,
+                    &mut report,
+                );
+            if applied && options.logs() {
+                log::info!("Set fonts (family names): {:?}", report.installed);
+            }
+        }
+        ApplyMode::Extend(defs) => {
+            if prepare_fonts(defs, false, fonts, options, plan, placement, &mut report)? {
+                install_fonts(ctx, defs, handoff, false, options.force, &mut report);
+            }
+        }
+    }
+    Ok(report)
+}
+
+/// An empty report for fonts resolved from `plan`.
+fn plan_report(plan: Option<&resolve::Plan>) -> FontReport {
+    FontReport {
+        locale: plan.and_then(|plan| plan.locale.clone()),
+        region: plan.and_then(|plan| plan.region),
+        trimmed: plan.map(|plan| plan.trimmed.clone()).unwrap_or_default(),
+        ..Default::default()
+    }
+}
+
+/// The part of [`apply_fonts`] that does not touch `ctx`: reads `fonts` into `defs` and pins Han fonts.
+///
+/// With `replace`, `defs` becomes `egui`'s defaults with the fonts first; otherwise the fonts are inserted
+/// into `defs` as `placement` says. Returns whether `defs` changed. Stops with [`FontError::Cancelled`]
+/// when the cancel token fires, and with a plan and [`FontOptions::strict`] fails if the result does not
+/// pass [`check_installed`]; `defs` is left as it was either way.
+fn prepare_fonts(
+    defs: &mut FontDefinitions,
+    replace: bool,
+    fonts: Vec<FoundFont>,
+    options: &FontOptions,
+    plan: Option<&resolve::Plan>,
+    placement: Placement<'_>,
+    report: &mut FontReport,
+) -> Result<bool, FontError> {
+    let Placement {
+        anchor,
+        slots,
+        progress,
+        ..
+    } = placement;
+    let no_progress = &mut |_: &str, _, _| {};
+    let progress = progress.unwrap_or(no_progress);
+    let slots =
+        slots.unwrap_or_else(|| options.slots(plan.map_or(FontStyle::Sans, |plan| plan.style)));
+    let han_region = plan
+        .and_then(|plan| plan.region)
+        .filter(|_| options.pin_han);
+    let strict = plan.filter(|_| options.strict);
+    if options.cancelled() {
+        return Err(cancelled(options));
+    }
+
+    if replace {
+        let built = replacement_definitions(fonts, options, slots, report, progress);
+        if options.cancelled() {
+            return Err(cancelled(options));
+        }
+        if let Some(plan) = strict {
+            check_installed(plan, report, built.as_ref())?;
+        }
+        let Some(built) = built else {
+            return Ok(false);
         };
+        *defs = built;
+    } else {
+        let before = (strict.is_some() || options.cancel.is_some()).then(|| defs.clone());
+        let loaded = load_at(defs, fonts, options, slots, anchor, report, progress);
+        if options.cancelled() {
+            if let Some(before) = before {
+                *defs = before;
+            }
+            return Err(cancelled(options));
+        }
+        if let (Some(plan), Some(before)) = (strict, before) {
+            if let Err(e) = check_installed(plan, report, Some(defs)) {
+                *defs = before;
+                return Err(e);
+            }
+        }
+        if !loaded {
+            return Ok(false);
+        }
+    }
+    if let Some(region) = han_region {
+        han::pin(defs, region);
+    }
+    Ok(true)
+}
+
+/// Applies definitions built by [`prepare_fonts`] to `ctx`, unless they are in effect already and `force`
+/// is off, in which case `report` says they are unchanged.
+///
+/// `replacement` marks definitions that replace the context's, whose predecessors
+/// [`reset_system_fonts`] brings back. Returns whether the definitions were applied.
+fn install_fonts(
+    ctx: &egui::Context,
+    defs: &mut FontDefinitions,
+    handoff: Handoff,
+    replacement: bool,
+    force: bool,
+    report: &mut FontReport,
+) -> bool {
+    if !force && snapshot::is_applied(ctx, defs) {
+        report.unchanged = true;
+        return false;
+    }
+    let defs = match handoff {
+        Handoff::Copy => defs.clone(),
+        Handoff::Move => std::mem::replace(defs, FontDefinitions::empty()),
+    };
+    if replacement {
+        snapshot::install_replacement(ctx, defs);
+    } else {
+        snapshot::install(ctx, defs);
+    }
+    report.applications += 1;
+    true
+}
+
+/// Where [`apply_fonts`] puts the fonts, and how it hands them over.
+struct Placement<'a> {
+    /// Where the fonts go in each family in [`ApplyMode::Extend`].
+    anchor: Anchor<'a>,
+    handoff: Handoff,
+    /// Families the fonts join, in place of [`FontOptions::slots`] for the style.
+    slots: Option<&'a [FontFamily]>,
+    /// Told about each font read.
+    progress: Option<&'a mut ReadProgress<'a>>,
+}
 
-        defs.font_data
-            .insert(f.key.clone(), FontData::from_owned(bytes).into());
+/// Called with the family, its position among the candidates and their count after each font is read.
+type ReadProgress<'a> = dyn FnMut(&str, usize, usize) + 'a;
 
-        keys_in_priority.push(f.key.clone());
-        installed_names.push(f.family);
+impl<'a> Placement<'a> {
+    /// Fonts inserted at `anchor`, copied into `ctx`, with no progress reports.
+    fn at(anchor: Anchor<'a>) -> Self {
+        Self {
+            anchor,
+            handoff: Handoff::Copy,
+            slots: None,
+            progress: None,
+        }
     }
+}
+
+/// What [`apply_fonts`] does with definitions it extended and applies.
+#[derive(Clone, Copy)]
+enum Handoff {
+    /// The caller keeps using them, so `ctx` gets a copy.
+    Copy,
+    /// The caller is done with them, so they are moved into `ctx`, leaving the caller's empty.
+    Move,
+}
 
-    if installed_names.is_empty() {
-        return vec![];
+/// The error of a call stopped through [`FontOptions::cancel_token`], logged as `options` asks.
+fn cancelled(options: &FontOptions) -> FontError {
+    if options.logs() {
+        log::info!("Font setup cancelled");
     }
+    FontError::Cancelled
+}
+
+/// The post-install check of [`FontOptions::strict`].
+///
+/// With a region, the Proportional fonts of `defs` (the definitions that would be applied; `None` if none
+/// were built) must render the region's sample characters. Without one, at least one font must have been
+/// installed or already be in place.
+fn check_installed(
+    plan: &resolve::Plan,
+    report: &FontReport,
+    defs: Option<&FontDefinitions>,
+) -> Result<(), FontError> {
+    let no_fonts = || FontError::NoFontsInstalled {
+        region: plan.region,
+        style: plan.style,
+        candidates_tried: report.skipped.clone(),
+    };
+    let Some(defs) = defs else {
+        return Err(no_fonts());
+    };
 
-    for key in keys_in_priority.into_iter() {
-        insert_back(&mut defs.families, FontFamily::Proportional, key.clone());
-        insert_back(&mut defs.families, FontFamily::Monospace, key);
+    match plan.region.filter(|&region| region != FontRegion::Unknown) {
+        Some(region) => {
+            let missing = coverage::missing_sample(defs, region);
+            if missing.is_empty() {
+                Ok(())
+            } else if report.installed.is_empty() {
+                Err(no_fonts())
+            } else {
+                Err(FontError::RegionNotCovered { region, missing })
+            }
+        }
+        None => {
+            let in_place = report.skipped.iter().any(|s| {
+                matches!(
+                    s.reason,
+                    SkipReason::AlreadyInstalled
+                        | SkipReason::DuplicateFamily(_)
+                        | SkipReason::DuplicateFile(_)
+                )
+            });
+            if report.installed.is_empty() && !in_place {
+                Err(no_fonts())
+            } else {
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Builds `egui`'s default definitions with `fonts` first in each family in `slots`, or `None` if none
+/// could be loaded.
+fn replacement_definitions(
+    fonts: Vec<FoundFont>,
+    options: &FontOptions,
+    slots: &[FontFamily],
+    report: &mut FontReport,
+    progress: &mut dyn FnMut(&str, usize, usize),
+) -> Option<FontDefinitions> {
+    let mut defs = FontDefinitions::default();
+    let keys_in_priority = load_found_fonts(&mut defs, fonts, options, report, progress);
+
+    if keys_in_priority.is_empty() {
+        options.warn("No matching system fonts found.".to_owned());
+        return None;
     }
 
-    installed_names
+    for key in keys_in_priority.into_iter().rev() {
+        for slot in slots {
+            if joins_slot(&defs, options, slot, &key) {
+                insert_front(&mut defs.families, slot.clone(), key.clone());
+            }
+        }
+    }
+    Some(defs)
 }
 
-fn read_font_bytes(source: FoundFontSource) -> Option<Vec<u8>> {
-    match source {
-        FoundFontSource::Path(path) => match std::fs::read(&path) {
-            Ok(b) => Some(b),
-            Err(e) => {
-                log::debug!("Failed to read font file {:?}: {}", path, e);
-                None
+/// Loads `fonts` into `defs` and inserts their keys, in priority order, at `anchor` in each family in
+/// `slots`.
+///
+/// A family without the anchor key gets the fonts appended, with a warning in `report`. `progress` is called
+/// as each font is read. Returns `false` if nothing was loaded.
+fn load_at(
+    defs: &mut FontDefinitions,
+    fonts: Vec<FoundFont>,
+    options: &FontOptions,
+    slots: &[FontFamily],
+    anchor: Anchor<'_>,
+    report: &mut FontReport,
+    progress: &mut dyn FnMut(&str, usize, usize),
+) -> bool {
+    let keys_in_priority = load_found_fonts(defs, fonts, options, report, progress);
+
+    if keys_in_priority.is_empty() {
+        return false;
+    }
+
+    for slot in slots {
+        let keys: Vec<&String> = keys_in_priority
+            .iter()
+            .filter(|key| joins_slot(defs, options, slot, key))
+            .collect();
+        let (existing, after) = match anchor {
+            Anchor::Front => {
+                for key in keys.into_iter().rev() {
+                    insert_front(&mut defs.families, slot.clone(), key.clone());
+                }
+                continue;
+            }
+            Anchor::Back => {
+                for key in keys {
+                    insert_back(&mut defs.families, slot.clone(), key.clone());
+                }
+                continue;
+            }
+            Anchor::Before(existing) => (existing, false),
+            Anchor::After(existing) => (existing, true),
+        };
+
+        let list = defs.families.entry(slot.clone()).or_default();
+        let index = match list.iter().position(|k| k == existing) {
+            Some(i) => i + usize::from(after),
+            None => {
+                let warning =
+                    format!("Anchor {existing:?} is not in {slot:?}; appending the fonts");
+                options.warn(warning.clone());
+                report.warnings.push(warning);
+                list.len()
             }
-        },
-        FoundFontSource::Bytes(b) => Some(b.as_ref().to_vec()),
+        };
+        let new: Vec<String> = keys
+            .into_iter()
+            .filter(|key| !list.iter().any(|k| same_family(k, key)))
+            .cloned()
+            .collect();
+        list.splice(index..index, new);
+    }
+    true
+}
+
+/// Whether the font under `key` goes into `slot`.
+///
+/// With [`FontOptions::monospace_fixed_pitch_only`], proportional fonts stay out of `FontFamily::Monospace`.
+fn joins_slot(defs: &FontDefinitions, options: &FontOptions, slot: &FontFamily, key: &str) -> bool {
+    if !options.fixed_pitch_monospace || *slot != FontFamily::Monospace {
+        return true;
+    }
+    let fixed = defs
+        .font_data
+        .get(key)
+        .is_some_and(|data| coverage::is_fixed_pitch(data));
+    if !fixed {
+        log::debug!("{:?} is not fixed-pitch; not added to Monospace", key);
+    }
+    fixed
+}
+
+/// Prefix of every `font_data` key inserted by this crate.
+pub const KEY_PREFIX: &str = "egui-system-fonts/";
+
+/// Returns `true` if `key` is a `font_data` key inserted by this crate.
+///
+/// # Examples
+///
+/// ```
+/// # use egui_system_fonts::is_system_font_key;
+/// assert!(is_system_font_key("egui-system-fonts/system:Noto Sans KR:0"));
+/// assert!(!is_system_font_key("korean"));
+/// assert!(!is_system_font_key("Ubuntu-Light"));
+/// ```
+pub fn is_system_font_key(key: &str) -> bool {
+    key.starts_with(KEY_PREFIX)
+}
+
+/// Removes a font family installed by this crate from `defs` and reapplies the definitions to `ctx`.
+///
+/// Every key this crate inserted for `family_name` (as reported in [`FontReport::installed`]) is removed
+/// from `font_data` and from all font family lists; see [`remove_font`] to remove a single key. Fonts not
+/// inserted by this crate are never touched. Returns `true` if anything was removed; otherwise `ctx` is
+/// left alone.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use egui_system_fonts::{remove_family, snapshot};
+/// # fn demo(ctx: &egui::Context) {
+/// let mut defs = snapshot(ctx).definitions().clone();
+/// if remove_family(ctx, &mut defs, "Noto Sans KR") {
+///     println!("Noto Sans KR removed");
+/// }
+/// # }
+/// ```
+pub fn remove_family(ctx: &egui::Context, defs: &mut FontDefinitions, family_name: &str) -> bool {
+    let keys: Vec<String> = defs
+        .font_data
+        .keys()
+        .filter(|key| is_system_font_key(key))
+        .filter(|key| names::family_from_key(key) == Some(family_name))
+        .cloned()
+        .collect();
+    if keys.is_empty() {
+        return false;
+    }
+
+    for key in &keys {
+        defs.font_data.remove(key);
+    }
+    for list in defs.families.values_mut() {
+        list.retain(|k| !keys.contains(k));
     }
+
+    snapshot::install(ctx, defs.clone());
+    if global_defaults().logs() {
+        log::info!("Removed font family {:?}", family_name);
+    }
+    true
+}
+
+/// Removes the single font installed by this crate under `key` (as reported in
+/// [`FontReport::installed_keys`]) from `defs` and reapplies the definitions to `ctx`.
+///
+/// Keys this crate did not create are never touched. Returns `true` if the font was removed; otherwise
+/// `ctx` is left alone.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use egui_system_fonts::{installed_fonts, remove_font, snapshot};
+/// # fn demo(ctx: &egui::Context) {
+/// let mut defs = snapshot(ctx).definitions().clone();
+/// for font in installed_fonts(ctx).unwrap_or_default() {
+///     if font.family.ends_with(" Emoji") {
+///         remove_font(ctx, &mut defs, &font.key);
+///     }
+/// }
+/// # }
+/// ```
+pub fn remove_font(ctx: &egui::Context, defs: &mut FontDefinitions, key: &FontKey) -> bool {
+    if !key.is_system() || defs.font_data.remove(key.as_str()).is_none() {
+        return false;
+    }
+    for list in defs.families.values_mut() {
+        list.retain(|k| k != key);
+    }
+
+    snapshot::install(ctx, defs.clone());
+    if global_defaults().logs() {
+        log::info!("Removed font {}", key);
+    }
+    true
+}
+
+/// Removes every font this crate installed, keeping fonts added by anyone else, and reapplies the result.
+///
+/// If a `set_*` call replaced the definitions, `defs` becomes the definitions in effect before the first
+/// such call, so fonts and family order from before it come back. Otherwise the crate's fonts are removed
+/// from `defs`, leaving the remaining fonts in their order. Either way no font of this crate remains (see
+/// [`is_system_font_key`]). Returns `true` if `defs` changed; otherwise `ctx` is left alone.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use egui_system_fonts::{reset_system_fonts, snapshot};
+/// # fn demo(ctx: &egui::Context) {
+/// let mut defs = snapshot(ctx).definitions().clone();
+/// reset_system_fonts(ctx, &mut defs);
+/// # }
+/// ```
+pub fn reset_system_fonts(ctx: &egui::Context, defs: &mut FontDefinitions) -> bool {
+    let mut reset = snapshot::take_original(ctx).unwrap_or_else(|| defs.clone());
+    reset.font_data.retain(|key, _| !is_system_font_key(key));
+    for list in reset.families.values_mut() {
+        list.retain(|key| !is_system_font_key(key));
+    }
+    if reset == *defs {
+        return false;
+    }
+
+    *defs = reset;
+    snapshot::install(ctx, defs.clone());
+    if global_defaults().logs() {
+        log::info!("Removed all fonts installed by egui-system-fonts");
+    }
+    true
+}
+
+fn namespaced_key(key: &str) -> FontKey {
+    FontKey::from(format!("{KEY_PREFIX}{key}"))
+}
+
+/// Reads candidates into `defs.font_data` and returns their keys in priority order.
+///
+/// Installed and skipped candidates are recorded in `report`.
+fn load_found_fonts(
+    defs: &mut FontDefinitions,
+    fonts: Vec<FoundFont>,
+    options: &FontOptions,
+    report: &mut FontReport,
+    progress: &mut dyn FnMut(&str, usize, usize),
+) -> Vec<String> {
+    let mut installed = read::Installed::from_defs(defs);
+    let loaded = read::read_found_fonts(fonts, options, report, &mut installed, progress);
+
+    let mut keys_in_priority: Vec<String> = Vec::new();
+    for font in loaded {
+        let key = font.key.into_string();
+        defs.font_data.insert(key.clone(), font.data);
+        keys_in_priority.push(key);
+    }
+
+    keys_in_priority
 }
 
+/// Puts `key` first in `family`, displacing any entry of the same font family (see [`same_family`]).
 fn insert_front(families: &mut BTreeMap<FontFamily, Vec<String>>, family: FontFamily, key: String) {
     let list = families.entry(family).or_default();
-    if list.iter().any(|k| k == &key) {
-        return;
-    }
+    list.retain(|k| !same_family(k, &key));
     list.insert(0, key);
 }
 
+/// Appends `key` to `family` unless an entry of the same font family is already there.
 fn insert_back(families: &mut BTreeMap<FontFamily, Vec<String>>, family: FontFamily, key: String) {
     let list = families.entry(family).or_default();
-    if list.iter().any(|k| k == &key) {
+    if list.iter().any(|k| same_family(k, &key)) {
         return;
     }
     list.push(key);
 }
+
+/// Whether two `font_data` keys name the same font family.
+///
+/// Keys inserted by this crate compare by their normalized family name (see
+/// [`normalize_family_name`]), so `system:Noto Sans:0` and `system:Noto Sans:3` are one family; other
+/// keys only match themselves.
+fn same_family(a: &str, b: &str) -> bool {
+    if a == b {
+        return true;
+    }
+    let family = |key: &str| {
+        is_system_font_key(key)
+            .then(|| names::family_from_key(key))
+            .flatten()
+            .map(normalize_family_name)
+    };
+    match (family(a), family(b)) {
+        (Some(a), Some(b)) => a == b,
+        _ => false,
+    }
+}
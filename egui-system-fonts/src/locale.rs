@@ -0,0 +1,71 @@
+//! Mapping locales to regions, with app-supplied exceptions.
+
+use crate::FontRegion;
+use std::collections::HashMap;
+use std::sync::{PoisonError, RwLock};
+
+/// Overrides by normalized locale (see [`normalize`]).
+static OVERRIDES: RwLock<Option<HashMap<String, FontRegion>>> = RwLock::new(None);
+
+/// Sets locale-to-region mappings that take precedence over the built-in ones, process-wide.
+///
+/// Keys are locales or locale prefixes such as `"zh-MO"`, `"zh-Hant"` or `"pt"`, matched without regard to
+/// case, `-` versus `_`, and encoding suffixes (`.UTF-8`). The most specific key wins: `zh-MO` is
+/// looked up before `zh`; locales no key matches use the built-in mapping. Applies wherever a region is
+/// derived from a locale: [`FontSelection::Auto`](crate::FontSelection::Auto), the `*_auto` functions,
+/// [`EnvOverrides`](crate::EnvOverrides) locales and [`region_for_locale`]. Replaces the previous table;
+/// pass an empty map to remove all overrides.
+///
+/// # Examples
+///
+/// ```
+/// # use egui_system_fonts::{region_for_locale, set_locale_region_overrides, FontRegion};
+/// # use std::collections::HashMap;
+/// set_locale_region_overrides(HashMap::from([
+///     ("zh-MO".to_owned(), FontRegion::TraditionalChinese),
+///     ("zh".to_owned(), FontRegion::SimplifiedChinese),
+/// ]));
+/// assert_eq!(region_for_locale("zh_MO.UTF-8"), FontRegion::TraditionalChinese);
+/// assert_eq!(region_for_locale("zh-CN"), FontRegion::SimplifiedChinese);
+/// assert_eq!(region_for_locale("ja-JP"), FontRegion::Japanese);
+///
+/// set_locale_region_overrides(HashMap::new());
+/// ```
+pub fn set_locale_region_overrides(overrides: HashMap<String, FontRegion>) {
+    let overrides = overrides
+        .into_iter()
+        .map(|(locale, region)| (normalize(&locale), region))
+        .collect();
+    *OVERRIDES.write().unwrap_or_else(PoisonError::into_inner) = Some(overrides);
+}
+
+/// Maps a locale string (e.g. `"ko-KR"`, `"ja_JP.UTF-8"`) to the region used for font selection.
+///
+/// Like [`region_from_locale`](crate::discovery::region_from_locale), but honors the overrides set with
+/// [`set_locale_region_overrides`].
+pub fn region_for_locale(locale: &str) -> FontRegion {
+    if let Some(region) = overridden(locale) {
+        return region;
+    }
+    system_fonts::region_from_locale(locale)
+}
+
+/// The override for the most specific prefix of `locale` that has one.
+fn overridden(locale: &str) -> Option<FontRegion> {
+    let overrides = OVERRIDES.read().unwrap_or_else(PoisonError::into_inner);
+    let overrides = overrides.as_ref().filter(|o| !o.is_empty())?;
+    let mut key = normalize(locale);
+    loop {
+        if let Some(&region) = overrides.get(&key) {
+            return Some(region);
+        }
+        let (prefix, _) = key.rsplit_once('-')?;
+        key = prefix.to_owned();
+    }
+}
+
+/// Lowercases `locale`, drops `.encoding` and `@modifier` suffixes and separates subtags with `-`.
+fn normalize(locale: &str) -> String {
+    let end = locale.find(['.', '@']).unwrap_or(locale.len());
+    locale[..end].trim().replace('_', "-").to_ascii_lowercase()
+}
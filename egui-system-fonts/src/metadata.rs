@@ -0,0 +1,63 @@
+//! Font facts read from a candidate's own tables, for pickers and diagnostics.
+
+use crate::chain::{family_name, first_name};
+use crate::coverage::face_is_fixed_pitch;
+use crate::read::read_font_bytes;
+use crate::{FontError, FontOptions, FoundFontSource, SkipReason};
+
+/// What a font says about itself; see [`read_metadata`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FontMeta {
+    /// Family name (typographic family if present, e.g. `Noto Sans` rather than `Noto Sans Light`).
+    pub family: Option<String>,
+    /// Subfamily name (typographic subfamily if present), e.g. `Bold Italic`.
+    pub subfamily: Option<String>,
+    /// Weight class from the `OS/2` table (400 is regular, 700 bold).
+    pub weight: u16,
+    /// Whether the face is italic or oblique.
+    pub is_italic: bool,
+    /// Whether the face is fixed-pitch (flagged in `post`, or equal-width ASCII).
+    pub is_monospace: bool,
+    /// Number of faces in the file: more than one for a font collection (`.ttc`).
+    pub num_faces: u32,
+}
+
+/// Reads the family, style and weight of the font in `source`.
+///
+/// Describes the first face, which is the one this crate installs; [`FontMeta::num_faces`] tells whether
+/// the file holds more. Unreadable files fail with [`FontError::ReadFailed`] and data that does not parse
+/// as a font with [`FontError::InvalidFont`]; malformed data never panics.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use egui_system_fonts::{read_metadata, FoundFontSource};
+/// let source = FoundFontSource::Path("/usr/share/fonts/noto/NotoSans-Regular.ttf".into());
+/// let meta = read_metadata(&source).unwrap();
+/// println!("{:?} {:?}, weight {}", meta.family, meta.subfamily, meta.weight);
+/// ```
+pub fn read_metadata(source: &FoundFontSource) -> Result<FontMeta, FontError> {
+    let bytes =
+        read_font_bytes(source, &FontOptions::default()).map_err(|reason| match reason {
+            SkipReason::ReadFailed(e) => FontError::ReadFailed(e),
+            other => FontError::ReadFailed(format!("{other:?}")),
+        })?;
+    let num_faces = ttf_parser::fonts_in_collection(&bytes).unwrap_or(1);
+    let face =
+        ttf_parser::Face::parse(&bytes, 0).map_err(|e| FontError::InvalidFont(e.to_string()))?;
+
+    Ok(FontMeta {
+        family: family_name(&face),
+        subfamily: first_name(
+            &face,
+            &[
+                ttf_parser::name_id::TYPOGRAPHIC_SUBFAMILY,
+                ttf_parser::name_id::SUBFAMILY,
+            ],
+        ),
+        weight: face.weight().to_number(),
+        is_italic: face.is_italic() || face.is_oblique(),
+        is_monospace: face_is_fixed_pitch(&face),
+        num_faces,
+    })
+}
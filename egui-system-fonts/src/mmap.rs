@@ -0,0 +1,53 @@
+//! Memory-mapped font files (`mmap` feature).
+
+use memmap2::Mmap;
+use std::collections::HashMap;
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock, PoisonError};
+
+/// Mapped files by path, with the size and modification time they were mapped at.
+type Mappings = Mutex<HashMap<PathBuf, (Option<(u64, u128)>, &'static [u8])>>;
+
+fn mappings() -> &'static Mappings {
+    static MAPPINGS: OnceLock<Mappings> = OnceLock::new();
+    MAPPINGS.get_or_init(Default::default)
+}
+
+/// Maps the font file at `path` for the rest of the process and returns its bytes, or `None` if it cannot
+/// be mapped (the caller then reads it).
+///
+/// `egui` keeps font bytes for as long as the fonts are in use and only accepts owned or `'static` data, so
+/// mappings are never unmapped. A path is mapped once and reused by later calls; a file whose size or
+/// modification time changed is mapped again, and the old mapping stays for fonts still using it.
+pub(crate) fn map(path: &Path) -> Option<&'static [u8]> {
+    let stamp = crate::cache::stamp(path).ok();
+    let mut mappings = mappings().lock().unwrap_or_else(PoisonError::into_inner);
+    if let Some((mapped_stamp, bytes)) = mappings.get(path) {
+        if *mapped_stamp == stamp {
+            return Some(bytes);
+        }
+    }
+
+    let file = match File::open(path) {
+        Ok(file) => file,
+        Err(e) => {
+            log::debug!("Failed to open {:?} for mapping: {}", path, e);
+            return None;
+        }
+    };
+    // SAFETY: the mapping is only sound while no one truncates or rewrites the file. Installed fonts are
+    // not modified in place in practice (updates replace the file), and the `mmap` feature documents the
+    // risk for fonts that are.
+    let map = match unsafe { Mmap::map(&file) } {
+        Ok(map) => map,
+        Err(e) => {
+            log::debug!("Failed to map {:?}: {}", path, e);
+            return None;
+        }
+    };
+    let bytes: &'static [u8] = Box::leak(Box::new(map));
+    log::debug!("Mapped {:?} ({} bytes)", path, bytes.len());
+    mappings.insert(path.to_owned(), (stamp, bytes));
+    Some(bytes)
+}
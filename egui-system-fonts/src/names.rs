@@ -0,0 +1,311 @@
+//! Names, labels and variant lists for the re-exported enums, and font name parsing.
+
+use crate::{FontPreset, FontRegion, FontStyle, KEY_PREFIX};
+use std::fmt;
+
+/// Every [`FontRegion`], in the order the demo's region picker shows them.
+///
+/// `FontRegion` is defined in the `system-fonts` crate, so the list lives here; it is updated whenever a
+/// region is added there. Includes [`FontRegion::Unknown`], which UIs usually leave out.
+///
+/// # Examples
+///
+/// ```
+/// # use egui_system_fonts::{region_label, region_name, FontRegion, REGIONS};
+/// assert!(REGIONS.contains(&FontRegion::Cyrillic));
+///
+/// let mut names: Vec<_> = REGIONS.iter().map(|&r| region_name(r)).collect();
+/// let mut labels: Vec<_> = REGIONS.iter().map(|&r| region_label(r)).collect();
+/// names.sort_unstable();
+/// names.dedup();
+/// labels.sort_unstable();
+/// labels.dedup();
+/// // Every variant has its own name and label (none falls through to a catch-all arm).
+/// assert_eq!(names.len(), REGIONS.len());
+/// assert_eq!(labels.len(), REGIONS.len());
+/// ```
+pub const REGIONS: &[FontRegion] = &[
+    FontRegion::Korean,
+    FontRegion::Japanese,
+    FontRegion::SimplifiedChinese,
+    FontRegion::TraditionalChinese,
+    FontRegion::Cyrillic,
+    FontRegion::Latin,
+    FontRegion::Unknown,
+];
+
+/// Every [`FontStyle`].
+pub const STYLES: &[FontStyle] = &[FontStyle::Sans, FontStyle::Serif];
+
+/// Every built-in [`FontPreset`] (all but [`FontPreset::Custom`]).
+///
+/// # Examples
+///
+/// ```
+/// # use egui_system_fonts::{preset_label, PRESETS};
+/// let labels: Vec<_> = PRESETS.iter().map(preset_label).collect();
+/// assert_eq!(labels[0], "Latin");
+/// assert!(!labels.contains(&"Custom"));
+/// ```
+pub const PRESETS: &[FontPreset] = &[
+    FontPreset::Latin,
+    FontPreset::Korean,
+    FontPreset::Japanese,
+    FontPreset::SimplifiedChinese,
+    FontPreset::TraditionalChinese,
+    FontPreset::Cyrillic,
+];
+
+/// Returns a human-readable English name for `region`, e.g. `"Chinese (Simplified)"`, for UIs.
+///
+/// Unlike [`region_name`], labels may change between releases; don't store them.
+pub fn region_label(region: FontRegion) -> &'static str {
+    match region {
+        FontRegion::Korean => "Korean",
+        FontRegion::Japanese => "Japanese",
+        FontRegion::SimplifiedChinese => "Chinese (Simplified)",
+        FontRegion::TraditionalChinese => "Chinese (Traditional)",
+        FontRegion::Cyrillic => "Cyrillic",
+        FontRegion::Latin => "Latin",
+        _ => "Unknown",
+    }
+}
+
+/// Returns a human-readable English name for `style`: `"Sans-serif"` or `"Serif"`.
+pub fn style_label(style: FontStyle) -> &'static str {
+    match style {
+        FontStyle::Sans => "Sans-serif",
+        FontStyle::Serif => "Serif",
+    }
+}
+
+/// Returns a human-readable English name for `preset`; custom presets are labelled `"Custom"`.
+pub fn preset_label(preset: &FontPreset) -> &'static str {
+    match preset {
+        FontPreset::Latin => "Latin",
+        FontPreset::Korean => "Korean",
+        FontPreset::Japanese => "Japanese",
+        FontPreset::SimplifiedChinese => "Chinese (Simplified)",
+        FontPreset::TraditionalChinese => "Chinese (Traditional)",
+        FontPreset::Cyrillic => "Cyrillic",
+        _ => "Custom",
+    }
+}
+
+/// Returns the stable identifier of `region`, e.g. `"korean"` or `"simplified-chinese"`.
+///
+/// `FontRegion` comes from the `system-fonts` crate, so it has no `Display` impl of its own; use this
+/// (and [`parse_region`]) to store a region in a settings file. The identifiers are lowercase, hyphenated
+/// variant names and will not change in future releases.
+///
+/// # Examples
+///
+/// ```
+/// # use egui_system_fonts::{parse_region, region_name, FontRegion, REGIONS};
+/// assert_eq!(region_name(FontRegion::SimplifiedChinese), "simplified-chinese");
+///
+/// for &region in REGIONS {
+///     assert_eq!(parse_region(region_name(region)), Ok(region));
+/// }
+/// ```
+pub fn region_name(region: FontRegion) -> &'static str {
+    match region {
+        FontRegion::Korean => "korean",
+        FontRegion::Japanese => "japanese",
+        FontRegion::SimplifiedChinese => "simplified-chinese",
+        FontRegion::TraditionalChinese => "traditional-chinese",
+        FontRegion::Cyrillic => "cyrillic",
+        FontRegion::Latin => "latin",
+        _ => "unknown",
+    }
+}
+
+/// Parses a region from its identifier (see [`region_name`]) or a common alias.
+///
+/// Matching ignores case, `-`, `_` and spaces, so `Korean`, `simplified-chinese` and
+/// `TRADITIONAL_CHINESE` all parse. Language and locale tags are accepted as aliases: `ko`/`kr`,
+/// `ja`/`jp`, `zh-Hans`/`zh_CN`/`zh-SG`, `zh-Hant`/`zh_TW`/`zh-HK`, `ru` and `en`.
+///
+/// # Examples
+///
+/// ```
+/// # use egui_system_fonts::{parse_region, FontRegion};
+/// assert_eq!(parse_region("ko"), Ok(FontRegion::Korean));
+/// assert_eq!(parse_region("KR"), Ok(FontRegion::Korean));
+/// assert_eq!(parse_region("zh-hans"), Ok(FontRegion::SimplifiedChinese));
+/// assert_eq!(parse_region("zh_CN"), Ok(FontRegion::SimplifiedChinese));
+/// assert_eq!(parse_region("zh-Hant"), Ok(FontRegion::TraditionalChinese));
+/// assert_eq!(parse_region("Japanese"), Ok(FontRegion::Japanese));
+///
+/// let err = parse_region("klingon").unwrap_err();
+/// assert_eq!(err.to_string(), r#"unknown font region "klingon""#);
+/// ```
+pub fn parse_region(s: &str) -> Result<FontRegion, ParseNameError> {
+    let region = match normalize(s).as_str() {
+        "korean" | "ko" | "kr" | "kokr" => FontRegion::Korean,
+        "japanese" | "ja" | "jp" | "jajp" => FontRegion::Japanese,
+        "simplifiedchinese" | "zhhans" | "zhcn" | "zhsg" | "zhhanscn" => {
+            FontRegion::SimplifiedChinese
+        }
+        "traditionalchinese" | "zhhant" | "zhtw" | "zhhk" | "zhmo" | "zhhanttw" => {
+            FontRegion::TraditionalChinese
+        }
+        "cyrillic" | "ru" | "ruru" => FontRegion::Cyrillic,
+        "latin" | "en" => FontRegion::Latin,
+        "unknown" => FontRegion::Unknown,
+        _ => return Err(ParseNameError::new("region", s)),
+    };
+    Ok(region)
+}
+
+/// Returns the stable identifier of `style`: `"sans"` or `"serif"`.
+///
+/// # Examples
+///
+/// ```
+/// # use egui_system_fonts::{parse_style, style_name, FontStyle, STYLES};
+/// assert_eq!(style_name(FontStyle::Serif), "serif");
+/// for &style in STYLES {
+///     assert_eq!(parse_style(style_name(style)), Ok(style));
+/// }
+/// ```
+pub fn style_name(style: FontStyle) -> &'static str {
+    match style {
+        FontStyle::Sans => "sans",
+        FontStyle::Serif => "serif",
+    }
+}
+
+/// Parses a style from its identifier (see [`style_name`]); `sans-serif` is accepted as well.
+///
+/// Matching ignores case, `-`, `_` and spaces.
+///
+/// # Examples
+///
+/// ```
+/// # use egui_system_fonts::{parse_style, FontStyle};
+/// assert_eq!(parse_style("Sans-Serif"), Ok(FontStyle::Sans));
+/// assert_eq!(parse_style("SERIF"), Ok(FontStyle::Serif));
+/// assert!(parse_style("mono").is_err());
+/// ```
+pub fn parse_style(s: &str) -> Result<FontStyle, ParseNameError> {
+    match normalize(s).as_str() {
+        "sans" | "sansserif" => Ok(FontStyle::Sans),
+        "serif" => Ok(FontStyle::Serif),
+        _ => Err(ParseNameError::new("style", s)),
+    }
+}
+
+/// Error returned by [`parse_region`] and [`parse_style`] for an unrecognized name.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseNameError {
+    kind: &'static str,
+    name: String,
+}
+
+impl ParseNameError {
+    fn new(kind: &'static str, name: &str) -> Self {
+        Self {
+            kind,
+            name: name.to_owned(),
+        }
+    }
+
+    /// The string that failed to parse.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl fmt::Display for ParseNameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown font {} {:?}", self.kind, self.name)
+    }
+}
+
+impl std::error::Error for ParseNameError {}
+
+fn normalize(s: &str) -> String {
+    s.trim()
+        .chars()
+        .filter(|c| !matches!(c, '-' | '_' | ' '))
+        .map(|c| c.to_ascii_lowercase())
+        .collect()
+}
+
+/// Trailing words that name a weight or slant rather than a family.
+const STYLE_SUFFIXES: &[&str] = &[
+    "thin",
+    "hairline",
+    "extralight",
+    "ultralight",
+    "light",
+    "demilight",
+    "regular",
+    "normal",
+    "book",
+    "medium",
+    "semibold",
+    "demibold",
+    "bold",
+    "extrabold",
+    "ultrabold",
+    "black",
+    "heavy",
+    "italic",
+    "oblique",
+];
+
+/// Trailing language tags of pan-CJK families, which pick the glyph forms of a language.
+const PAN_CJK_TAGS: &[&str] = &["sc", "tc", "hc", "hk", "jp", "kr", "j", "k"];
+
+/// Canonicalizes a font family name for duplicate detection.
+///
+/// Lowercases the name, collapses whitespace and strips trailing weight/slant words. Language tags are
+/// kept: `Noto Sans CJK SC` and `Noto Sans CJK JP` draw Han characters in different forms, and
+/// `Noto Sans SC` is not `Noto Sans`.
+///
+/// # Examples
+///
+/// ```
+/// # use egui_system_fonts::normalize_family_name;
+/// assert_eq!(normalize_family_name("Noto Sans CJK SC Bold"), "noto sans cjk sc");
+/// assert_eq!(normalize_family_name("Malgun Gothic Bold"), "malgun gothic");
+/// assert_eq!(normalize_family_name("Noto Sans SC"), "noto sans sc");
+/// ```
+pub fn normalize_family_name(name: &str) -> String {
+    let lower = name.to_lowercase();
+    let mut words: Vec<&str> = lower.split_whitespace().collect();
+    while words.len() > 1 && STYLE_SUFFIXES.contains(&words[words.len() - 1]) {
+        words.pop();
+    }
+    words.join(" ")
+}
+
+/// Whether two normalized family names (see [`normalize_family_name`]) name the same font.
+///
+/// Besides equal names, a pan-CJK family (a name containing `CJK` or `Source Han`) listed without a
+/// language tag matches each of its tagged names (`noto sans cjk` and `noto sans cjk sc`); two different
+/// tags do not match each other. Actual copies of one font are caught by file and contents instead.
+pub(crate) fn same_family_name(a: &str, b: &str) -> bool {
+    a == b || pan_cjk_base(a) == Some(b) || pan_cjk_base(b) == Some(a)
+}
+
+/// `name` without its trailing language tag, if it is a tagged pan-CJK family name.
+fn pan_cjk_base(name: &str) -> Option<&str> {
+    if !(name.contains("cjk") || name.contains("source han")) {
+        return None;
+    }
+    let (base, tag) = name.rsplit_once(' ')?;
+    PAN_CJK_TAGS.contains(&tag).then_some(base)
+}
+
+/// Extracts the family name from a key inserted by this crate (or by 0.1, without the prefix).
+///
+/// Keys look like `egui-system-fonts/system:<family>:<n>`.
+pub(crate) fn family_from_key(key: &str) -> Option<&str> {
+    let raw = key.strip_prefix(KEY_PREFIX).unwrap_or(key);
+    let rest = raw.strip_prefix("system:")?;
+    let (family, _) = rest.rsplit_once(':')?;
+    Some(family)
+}
@@ -0,0 +1,900 @@
+//! Options for the `*_with_options` functions.
+
+use crate::{
+    EnvOverrides, FontPreset, FontProvider, FontRegion, FontStyle, FoundFont, LogEvent, LogSink,
+    Progress, ProgressCallback, SystemProvider, TweakOverride, Verbosity,
+};
+use egui::{FontFamily, FontTweak};
+use std::fmt;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, PoisonError, RwLock};
+use std::time::Duration;
+
+/// Caller-supplied predicate deciding whether a candidate may be installed.
+pub type CandidateFilter = dyn Fn(&FoundFont) -> bool + Send + Sync;
+
+/// Caller-supplied style classification of a candidate; `None` keeps the built-in one.
+pub type StyleClassifier = dyn Fn(&FoundFont) -> Option<FontStyle> + Send + Sync;
+
+/// A shared callback stored in [`FontOptions`].
+pub(crate) struct Hook<F: ?Sized>(pub(crate) Arc<F>);
+
+impl<F: ?Sized> Clone for Hook<F> {
+    fn clone(&self) -> Self {
+        Self(Arc::clone(&self.0))
+    }
+}
+
+impl<F: ?Sized> fmt::Debug for Hook<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Hook(..)")
+    }
+}
+
+/// Which fonts to resolve.
+///
+/// Installed fonts strictly follow preset order: every font found for a preset comes before the fonts of
+/// later presets, in both `set_*` (front of the family lists) and `extend_*` (back of the lists) calls. A
+/// family already found for an earlier preset is not repeated.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use egui_system_fonts::{set_with_options, FontOptions, FontPreset, FontStyle};
+/// # fn demo(ctx: &egui::Context) {
+/// // Japanese first, then Chinese, then Latin.
+/// let presets = vec![FontPreset::Japanese, FontPreset::SimplifiedChinese, FontPreset::Latin];
+/// set_with_options(ctx, presets, FontStyle::Sans, &FontOptions::new()).unwrap();
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub enum FontSelection {
+    /// Detect the region from the system locale (honoring [`EnvOverrides`](crate::EnvOverrides)).
+    Auto,
+    /// Use the default presets for a region.
+    Region(FontRegion),
+    /// Use explicit presets, in priority order.
+    Presets(Vec<FontPreset>),
+}
+
+impl From<FontRegion> for FontSelection {
+    fn from(region: FontRegion) -> Self {
+        Self::Region(region)
+    }
+}
+
+impl From<Vec<FontPreset>> for FontSelection {
+    fn from(presets: Vec<FontPreset>) -> Self {
+        Self::Presets(presets)
+    }
+}
+
+/// Which script's font leads a CJK region's stack; see [`FontOptions::primary_script`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum PrimaryScript {
+    /// The region's CJK fonts come first (the region's preset order), so Latin text uses the CJK fonts'
+    /// own Latin glyphs and mixed lines look uniform.
+    #[default]
+    Cjk,
+    /// A dedicated Latin font comes first, with the CJK fonts behind it for the characters it lacks.
+    Latin,
+}
+
+/// Lets another thread abort a call that takes [`FontOptions::cancel_token`].
+///
+/// Clones share one flag, so keep a clone and call [`cancel`](Self::cancel) on it, e.g. when the user
+/// leaves the screen the fonts were being set up for.
+#[derive(Clone, Debug, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    /// Creates a token that is not cancelled.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Asks every call using this token (or a clone) to stop. Cannot be undone.
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    /// Whether [`cancel`](Self::cancel) was called.
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// Where `extend_*` calls put the new fonts in each font family; see [`FontOptions::order`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum OrderPolicy {
+    /// Append the fonts after the existing ones, so `egui`'s fonts keep drawing every character they
+    /// cover.
+    #[default]
+    PreferDefaults,
+    /// Insert the fonts before the existing ones (like `set_*`), keeping the existing fonts as fallbacks.
+    PreferSystem,
+}
+
+impl OrderPolicy {
+    /// Where the policy inserts fonts.
+    pub(crate) fn anchor(self) -> Anchor<'static> {
+        match self {
+            Self::PreferDefaults => Anchor::Back,
+            Self::PreferSystem => Anchor::Front,
+        }
+    }
+}
+
+/// Where [`extend_with_options_at`](crate::extend_with_options_at) inserts fonts in each font family.
+///
+/// Keys are plain `&str` so `egui`'s and the app's own keys work; a [`FontKey`](crate::FontKey) from a
+/// report converts with `&key`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Anchor<'a> {
+    /// Before all existing fonts.
+    Front,
+    /// After all existing fonts.
+    Back,
+    /// Right before the given `font_data` key.
+    Before(&'a str),
+    /// Right after the given `font_data` key.
+    After(&'a str),
+}
+
+/// Tuning knobs for font resolution and installation.
+///
+/// The defaults match the behavior of the plain `set_*`/`extend_*` functions, unless
+/// [`set_global_defaults`] changed those.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use egui_system_fonts::{set_with_options, FontOptions, FontSelection, FontStyle};
+/// # use std::time::Duration;
+/// # fn demo(ctx: &egui::Context) {
+/// let options = FontOptions::new().candidate_timeout(Duration::from_millis(500));
+/// let report = set_with_options(ctx, FontSelection::Auto, FontStyle::Sans, &options).unwrap();
+/// for skipped in &report.skipped {
+///     println!("skipped {}: {:?}", skipped.family, skipped.reason);
+/// }
+/// # }
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct FontOptions {
+    pub(crate) candidate_timeout: Option<Duration>,
+    pub(crate) max_total_bytes: Option<u64>,
+    pub(crate) max_file_bytes: Option<u64>,
+    pub(crate) max_families: Option<usize>,
+    pub(crate) max_fonts_per_preset: Option<usize>,
+    pub(crate) filter: Option<Hook<CandidateFilter>>,
+    pub(crate) strict: bool,
+    pub(crate) provider: Option<Hook<dyn FontProvider>>,
+    pub(crate) env: Option<EnvOverrides>,
+    pub(crate) font_dirs: Vec<PathBuf>,
+    pub(crate) tweak: Option<Hook<TweakOverride>>,
+    pub(crate) no_default_tweaks: bool,
+    pub(crate) global_scale: Option<f32>,
+    pub(crate) normalize_metrics: bool,
+    pub(crate) style_tweaks: Vec<(FontStyle, FontTweak)>,
+    pub(crate) style_classifier: Option<Hook<StyleClassifier>>,
+    pub(crate) monospace: Option<bool>,
+    pub(crate) fixed_pitch_monospace: bool,
+    pub(crate) order: OrderPolicy,
+    pub(crate) primary_script: PrimaryScript,
+    pub(crate) force: bool,
+    pub(crate) preferred_families: Vec<String>,
+    pub(crate) display_faces: bool,
+    pub(crate) pin_han: bool,
+    pub(crate) primary_monospace: bool,
+    pub(crate) weight: Option<u16>,
+    pub(crate) cache_file: Option<PathBuf>,
+    pub(crate) verbosity: Verbosity,
+    pub(crate) log_sink: Option<Hook<LogSink>>,
+    pub(crate) progress: Option<Hook<ProgressCallback>>,
+    pub(crate) cancel: Option<CancelToken>,
+    #[cfg(feature = "subset")]
+    pub(crate) subset: Option<Arc<std::collections::HashSet<char>>>,
+}
+
+impl FontOptions {
+    /// Creates options with default values.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Gives up on a font file that takes longer than `timeout` to read.
+    ///
+    /// Each read runs on a worker thread. A read that exceeds the timeout is skipped with
+    /// [`SkipReason::TimedOut`](crate::SkipReason::TimedOut) and its thread is left to finish in the
+    /// background. Useful when fonts live on a slow network mount. With the `mmap` feature, files that can
+    /// be mapped are not read up front, so the timeout does not apply to them.
+    pub fn candidate_timeout(mut self, timeout: Duration) -> Self {
+        self.candidate_timeout = Some(timeout);
+        self
+    }
+
+    /// Caps the combined size of the fonts installed by one call.
+    ///
+    /// Candidates are sized (via file metadata) before they are read and picked in priority order while
+    /// they fit; the rest are skipped with [`SkipReason::OverBudget`](crate::SkipReason::OverBudget).
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use egui_system_fonts::{set_with_options, FontOptions, FontRegion, FontStyle};
+    /// # fn demo(ctx: &egui::Context) {
+    /// let options = FontOptions::new().max_total_bytes(32 * 1024 * 1024);
+    /// let report = set_with_options(ctx, FontRegion::Korean, FontStyle::Sans, &options).unwrap();
+    /// println!("installed: {:?}", report.installed);
+    /// # }
+    /// ```
+    pub fn max_total_bytes(mut self, bytes: u64) -> Self {
+        self.max_total_bytes = Some(bytes);
+        self
+    }
+
+    /// Skips any single font file larger than `bytes`
+    /// ([`SkipReason::FileTooLarge`](crate::SkipReason::FileTooLarge)).
+    pub fn max_file_bytes(mut self, bytes: u64) -> Self {
+        self.max_file_bytes = Some(bytes);
+        self
+    }
+
+    /// Caps the number of system font families in the definitions, counting those added by earlier calls.
+    ///
+    /// Each family grows `egui`'s font atlas, so extending with many regions can slow startup. Candidates
+    /// are added in priority order until the definitions hold `count` families from this crate; the rest
+    /// are skipped with [`SkipReason::TooManyFamilies`](crate::SkipReason::TooManyFamilies).
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use egui_system_fonts::{set_with_options, FontOptions, FontRegion, FontStyle};
+    /// # fn demo(ctx: &egui::Context) {
+    /// let options = FontOptions::new().max_families(4);
+    /// let report = set_with_options(ctx, FontRegion::Korean, FontStyle::Sans, &options).unwrap();
+    /// println!("installed: {:?}", report.installed);
+    /// # }
+    /// ```
+    pub fn max_families(mut self, count: usize) -> Self {
+        self.max_families = Some(count);
+        self
+    }
+
+    /// Keeps only the first `count` candidates of each preset, in priority order.
+    ///
+    /// The others are left out while resolving, before any file is read, so with `1` each preset costs at
+    /// most one font's memory. [`FontReport::trimmed`](crate::FontReport::trimmed) counts the candidates
+    /// left out of each preset.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use egui_system_fonts::{set_with_options, FontOptions, FontRegion, FontStyle};
+    /// # fn demo(ctx: &egui::Context) {
+    /// let options = FontOptions::new().max_fonts_per_preset(1);
+    /// let report = set_with_options(ctx, FontRegion::Korean, FontStyle::Sans, &options).unwrap();
+    /// println!("installed: {:?}", report.installed);
+    /// # }
+    /// ```
+    pub fn max_fonts_per_preset(mut self, count: usize) -> Self {
+        self.max_fonts_per_preset = Some(count);
+        self
+    }
+
+    /// Vetoes candidates before their bytes are read.
+    ///
+    /// Candidates for which `filter` returns `false` are skipped with
+    /// [`SkipReason::Filtered`](crate::SkipReason::Filtered).
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use egui_system_fonts::{FontOptions, FoundFontSource};
+    /// // Never load user-installed fonts.
+    /// let options = FontOptions::new().filter(|f| match &f.source {
+    ///     FoundFontSource::Path(path) => !path.to_string_lossy().contains("/.local/share/fonts"),
+    ///     FoundFontSource::Bytes(_) => true,
+    /// });
+    /// ```
+    pub fn filter(mut self, filter: impl Fn(&FoundFont) -> bool + Send + Sync + 'static) -> Self {
+        self.filter = Some(Hook(Arc::new(filter)));
+        self
+    }
+
+    /// Fails instead of leaving text to render as tofu (empty boxes).
+    ///
+    /// With a region, a call fails with
+    /// [`FontError::RegionUnsupportedOnSystem`](crate::FontError::RegionUnsupportedOnSystem) when the
+    /// region's own preset has no installed font; fallback presets (such as Latin for a Japanese region) do
+    /// not count. After reading the candidates, a call fails with
+    /// [`FontError::NoFontsInstalled`](crate::FontError::NoFontsInstalled) when none of them could be
+    /// installed, and with [`FontError::RegionNotCovered`](crate::FontError::RegionNotCovered) when the
+    /// resulting Proportional fonts still cannot draw common characters of the region's script. A failed
+    /// call leaves the context and the definitions untouched. Selections without a region (explicit presets)
+    /// only fail when nothing was installed. Off by default.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use egui_system_fonts::{set_with_options, FontError, FontOptions, FontSelection, FontStyle};
+    /// # fn demo(ctx: &egui::Context) {
+    /// let options = FontOptions::new().strict(true);
+    /// match set_with_options(ctx, FontSelection::Auto, FontStyle::Sans, &options) {
+    ///     Ok(report) => println!("installed: {:?}", report.installed),
+    ///     Err(FontError::RegionUnsupportedOnSystem(region)) => {
+    ///         eprintln!("please install a font pack for {region:?}");
+    ///     }
+    ///     Err(e) => eprintln!("{e}"),
+    /// }
+    /// # }
+    /// ```
+    pub fn strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /// Discovers fonts (and the locale) through `provider` instead of the operating system.
+    ///
+    /// See [`StaticProvider`](crate::StaticProvider) for an in-memory provider.
+    pub fn provider(mut self, provider: impl FontProvider + 'static) -> Self {
+        self.provider = Some(Hook(Arc::new(provider)));
+        self
+    }
+
+    /// Uses `env` instead of the process's `EGUI_SYSTEM_FONTS_*` variables for [`FontSelection::Auto`].
+    ///
+    /// By default the variables are read on every call. Pass `EnvOverrides::default()` to ignore them.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use egui_system_fonts::{set_with_options, EnvOverrides, FontOptions, FontRegion, FontSelection, FontStyle, StaticProvider};
+    /// let env = EnvOverrides::from_vars([("EGUI_SYSTEM_FONTS_REGION", "Korean")]);
+    /// let options = FontOptions::new().provider(StaticProvider::new()).env_overrides(env);
+    ///
+    /// let ctx = egui::Context::default();
+    /// let report = set_with_options(&ctx, FontSelection::Auto, FontStyle::Sans, &options).unwrap();
+    /// assert_eq!(report.region, Some(FontRegion::Korean));
+    /// ```
+    pub fn env_overrides(mut self, env: EnvOverrides) -> Self {
+        self.env = Some(env);
+        self
+    }
+
+    /// Also searches `paths` for fonts, before the system font locations.
+    ///
+    /// Each path may be a font file (`ttf`, `otf`, `ttc`, `otc`) or a directory, which is scanned
+    /// recursively up to 8 levels deep. Files are matched by family name against the same candidate lists
+    /// as system fonts (or the names of a [`FontPreset::Custom`]), and a family found here replaces the
+    /// system copy, so bundled fonts win. If several files hold equally good faces of a family, the first by
+    /// path wins, whatever order the filesystem lists them in. Symlinks are followed and each target is
+    /// scanned once; missing or unreadable paths are skipped with a debug log. The paths are scanned on every
+    /// call. Ignored on `wasm32`.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use egui_system_fonts::{set_with_options, FontOptions, FontRegion, FontStyle};
+    /// # fn demo(ctx: &egui::Context) {
+    /// let options = FontOptions::new().add_font_dirs(["assets/fonts"]);
+    /// let report = set_with_options(ctx, FontRegion::Korean, FontStyle::Sans, &options).unwrap();
+    /// println!("installed: {:?}", report.installed);
+    /// # }
+    /// ```
+    pub fn add_font_dirs(mut self, paths: impl IntoIterator<Item = impl Into<PathBuf>>) -> Self {
+        self.font_dirs.extend(paths.into_iter().map(Into::into));
+        self
+    }
+
+    /// Overrides the `FontTweak` of individual fonts.
+    ///
+    /// `tweak` runs for every installed font; returning `None` keeps the default
+    /// ([`default_tweak`](crate::default_tweak) for the font's preset). The applied tweaks are listed in
+    /// [`FontReport::tweaks`](crate::FontReport::tweaks).
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use egui_system_fonts::FontOptions;
+    /// let options = FontOptions::new().tweak(|f| {
+    ///     (f.family == "Malgun Gothic").then_some(egui::FontTweak { scale: 0.9, ..Default::default() })
+    /// });
+    /// ```
+    pub fn tweak(
+        mut self,
+        tweak: impl Fn(&FoundFont) -> Option<FontTweak> + Send + Sync + 'static,
+    ) -> Self {
+        self.tweak = Some(Hook(Arc::new(tweak)));
+        self
+    }
+
+    /// Corrects the sans/serif classification of individual fonts.
+    ///
+    /// By default a family's style is the candidate list it appears in. Within each preset, fonts whose
+    /// style differs from the requested one are moved behind the matching ones (they stay as fallbacks).
+    /// Returning `None` keeps the built-in classification; families in no built-in list count as matching.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use egui_system_fonts::{FontOptions, FontStyle};
+    /// let options = FontOptions::new().style_classifier(|f| f.family.contains("Mincho").then_some(FontStyle::Serif));
+    /// ```
+    pub fn style_classifier(
+        mut self,
+        classifier: impl Fn(&FoundFont) -> Option<FontStyle> + Send + Sync + 'static,
+    ) -> Self {
+        self.style_classifier = Some(Hook(Arc::new(classifier)));
+        self
+    }
+
+    /// Turns the built-in per-preset tweaks on or off (on by default).
+    ///
+    /// When off, fonts get `FontTweak::default()` unless [`tweak`](Self::tweak) overrides them.
+    pub fn default_tweaks(mut self, enabled: bool) -> Self {
+        self.no_default_tweaks = !enabled;
+        self
+    }
+
+    /// Adjusts every font of `style` by `tweak`, e.g. a slightly larger scale for serif body text.
+    ///
+    /// A font's style is its [`style_classifier`](Self::style_classifier) result or the built-in candidate
+    /// list it appears in, so sans fallbacks of a serif setup keep their own adjustment, and fonts of unknown
+    /// style get none. The adjustment applies on top of the built-in per-preset tweak: scales multiply and
+    /// offsets add. Fonts whose tweak is set by [`tweak`](Self::tweak) keep that one. Calling this again for
+    /// the same style replaces its tweak.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use egui_system_fonts::{FontOptions, FontStyle};
+    /// let serif = egui::FontTweak { scale: 1.1, ..Default::default() };
+    /// let options = FontOptions::new().style_tweak(FontStyle::Serif, serif);
+    /// ```
+    pub fn style_tweak(mut self, style: FontStyle, tweak: FontTweak) -> Self {
+        self.style_tweaks.retain(|(s, _)| *s != style);
+        self.style_tweaks.push((style, tweak));
+        self
+    }
+
+    /// Scales every installed font by `scale` (1.0, the default, leaves sizes unchanged).
+    ///
+    /// Multiplies the `scale` of each font's tweak, whether it comes from
+    /// [`default_tweak`](crate::default_tweak) or [`tweak`](Self::tweak). Useful when the system fonts look
+    /// larger or smaller than `egui`'s own at the same size.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use egui_system_fonts::{set_with_options, FontOptions, FontRegion, FontStyle};
+    /// # fn demo(ctx: &egui::Context) {
+    /// let options = FontOptions::new().global_scale(1.1);
+    /// let report = set_with_options(ctx, FontRegion::Korean, FontStyle::Sans, &options).unwrap();
+    /// println!("installed: {:?}", report.installed);
+    /// # }
+    /// ```
+    pub fn global_scale(mut self, scale: f32) -> Self {
+        self.global_scale = Some(scale);
+        self
+    }
+
+    /// Fits each font's tweak to its metrics so rows are as tall as with `egui`'s default font (off by
+    /// default).
+    ///
+    /// Fonts with a large line gap, common among CJK fonts, otherwise get taller rows than `egui`'s own
+    /// font at the same size, and their baseline may sit higher or lower. When on, each font is scaled to
+    /// the row height of `egui`'s default proportional font and moved to its baseline, in place of
+    /// [`default_tweak`](crate::default_tweak). [`style_tweak`](Self::style_tweak) and
+    /// [`global_scale`](Self::global_scale) apply on top; fonts whose tweak is set by
+    /// [`tweak`](Self::tweak) keep that one.
+    ///
+    /// This is best-effort: it only evens out the line box from the font's declared ascent, descent and
+    /// line gap, not how large its glyphs look, and `egui` still rounds metrics to pixels. The fitted tweaks
+    /// are listed in [`FontReport::tweaks`](crate::FontReport::tweaks).
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use egui_system_fonts::{set_with_options, FontOptions, FontRegion, FontStyle};
+    /// # fn demo(ctx: &egui::Context) {
+    /// let options = FontOptions::new().normalize_metrics(true);
+    /// let report = set_with_options(ctx, FontRegion::Korean, FontStyle::Sans, &options).unwrap();
+    /// println!("installed: {:?}", report.installed);
+    /// # }
+    /// ```
+    pub fn normalize_metrics(mut self, enabled: bool) -> Self {
+        self.normalize_metrics = enabled;
+        self
+    }
+
+    /// Trims each installed font to the glyphs needed for `chars` (requires the `subset` feature).
+    ///
+    /// Characters a font does not cover are ignored. A font that cannot be subset is installed in full.
+    /// Size limits apply to the original files.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use egui_system_fonts::{region_chars, set_with_options, FontOptions, FontRegion, FontStyle};
+    /// # fn demo(ctx: &egui::Context) {
+    /// let options = FontOptions::new().subset(region_chars(FontRegion::Cyrillic));
+    /// set_with_options(ctx, FontRegion::Cyrillic, FontStyle::Sans, &options).unwrap();
+    /// # }
+    /// ```
+    #[cfg(feature = "subset")]
+    pub fn subset(mut self, chars: impl IntoIterator<Item = char>) -> Self {
+        self.subset = Some(Arc::new(chars.into_iter().collect()));
+        self
+    }
+
+    /// Whether installed fonts are also added to `FontFamily::Monospace`.
+    ///
+    /// When off, only `FontFamily::Proportional` gets the new fonts and monospace text keeps `egui`'s
+    /// default fonts. By default this depends on the style: sans fonts are added, serif fonts are not, as a
+    /// serif body font makes for odd-looking code. Set it to `true` to add serif fonts as well.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use egui_system_fonts::{set_with_options, FontOptions, FontRegion, FontStyle};
+    /// # fn demo(ctx: &egui::Context) {
+    /// let options = FontOptions::new().monospace(false);
+    /// let report = set_with_options(ctx, FontRegion::Korean, FontStyle::Sans, &options).unwrap();
+    /// println!("installed: {:?}", report.installed);
+    /// # }
+    /// ```
+    pub fn monospace(mut self, enabled: bool) -> Self {
+        self.monospace = Some(enabled);
+        self
+    }
+
+    /// Sets where `extend_*` calls insert the fonts ([`OrderPolicy::PreferDefaults`] by default).
+    ///
+    /// With [`OrderPolicy::PreferSystem`] the new fonts come first, so they also draw characters `egui`'s
+    /// fonts cover (e.g. fullwidth punctuation next to Japanese text). Unlike `set_*`, nothing is removed.
+    /// `set_*` calls always put the fonts first.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use egui_system_fonts::{set_with_options, FontOptions, FontRegion, FontStyle, OrderPolicy};
+    /// # fn demo(ctx: &egui::Context) {
+    /// let options = FontOptions::new().order(OrderPolicy::PreferSystem);
+    /// let report = set_with_options(ctx, FontRegion::Korean, FontStyle::Sans, &options).unwrap();
+    /// println!("installed: {:?}", report.installed);
+    /// # }
+    /// ```
+    pub fn order(mut self, policy: OrderPolicy) -> Self {
+        self.order = policy;
+        self
+    }
+
+    /// Chooses whether the Latin or the CJK fonts come first for CJK regions (default:
+    /// [`PrimaryScript::Cjk`], the region's own preset order).
+    ///
+    /// With [`PrimaryScript::Latin`], the Latin preset is moved in front of the other presets, so Latin
+    /// text is drawn by a dedicated Latin font. Fonts from [`prefer_families`](Self::prefer_families) still
+    /// come first.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use egui_system_fonts::{set_with_options, FontOptions, FontRegion, FontStyle, PrimaryScript};
+    /// # fn demo(ctx: &egui::Context) {
+    /// let options = FontOptions::new().primary_script(PrimaryScript::Latin);
+    /// let report = set_with_options(ctx, FontRegion::Korean, FontStyle::Sans, &options).unwrap();
+    /// println!("installed: {:?}", report.installed);
+    /// # }
+    /// ```
+    pub fn primary_script(mut self, script: PrimaryScript) -> Self {
+        self.primary_script = script;
+        self
+    }
+
+    /// Adds only fixed-pitch fonts to `FontFamily::Monospace` (off by default).
+    ///
+    /// Proportional fonts (e.g. a Korean UI font that becomes the fallback for Hangul comments) would break
+    /// column alignment in code views, so they only go to `FontFamily::Proportional`. A font counts as
+    /// fixed-pitch if its `post` table says so or a sample of ASCII glyphs share one advance width. For CJK
+    /// regions, monospaced CJK families are also looked up, preferring programming fonts drawn on a
+    /// two-cells-per-CJK-character grid (`D2Coding`, `Sarasa Mono`) over the general ones
+    /// (`Noto Sans Mono CJK`, `MS Gothic`, ...); without any, those characters fall back to tofu in
+    /// monospace text. Columns only line up if the installed font is actually designed that way: `egui`
+    /// draws each glyph at the font's own advance width.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use egui_system_fonts::{set_with_options, FontOptions, FontRegion, FontStyle};
+    /// # fn demo(ctx: &egui::Context) {
+    /// let options = FontOptions::new().monospace_fixed_pitch_only(true);
+    /// let report = set_with_options(ctx, FontRegion::Korean, FontStyle::Sans, &options).unwrap();
+    /// println!("installed: {:?}", report.installed);
+    /// # }
+    /// ```
+    pub fn monospace_fixed_pitch_only(mut self, enabled: bool) -> Self {
+        self.fixed_pitch_monospace = enabled;
+        self
+    }
+
+    /// Tries `families` (in order) before the selection's own candidates.
+    ///
+    /// Useful to pin a specific installed family while keeping the selected presets as fallbacks.
+    pub fn prefer_families(
+        mut self,
+        families: impl IntoIterator<Item = impl Into<String>>,
+    ) -> Self {
+        self.preferred_families
+            .extend(families.into_iter().map(Into::into));
+        self
+    }
+
+    /// Installs every candidate at the given weight (100 to 900; 400 is regular, 700 bold).
+    ///
+    /// Each family in the stack, Latin and CJK alike, is looked up at the same weight so emphasis stays
+    /// consistent. A family without a face of that weight keeps its regular face, and a warning names it
+    /// and its preset. See [`FontProvider::find_weight`](crate::FontProvider::find_weight).
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use egui_system_fonts::{set_with_options, FontOptions, FontRegion, FontStyle};
+    /// # fn demo(ctx: &egui::Context) {
+    /// let options = FontOptions::new().weight(700);
+    /// let report = set_with_options(ctx, FontRegion::Korean, FontStyle::Sans, &options).unwrap();
+    /// println!("installed: {:?}", report.installed);
+    /// # }
+    /// ```
+    pub fn weight(mut self, weight: u16) -> Self {
+        self.weight = Some(weight);
+        self
+    }
+
+    /// Keeps the region's own CJK fonts ahead of other CJK fonts already installed (off by default).
+    ///
+    /// Applies when the selection has a CJK region, e.g. [`FontSelection::Auto`](crate::FontSelection::Auto)
+    /// on a Japanese system: after the fonts are added, see [`pin_han_region`](crate::pin_han_region).
+    /// Mostly useful with the `extend_*` functions, which add to fonts installed earlier.
+    pub fn pin_han(mut self, enabled: bool) -> Self {
+        self.pin_han = enabled;
+        self
+    }
+
+    /// Lets the app's own font lead `FontFamily::Monospace` too, in
+    /// [`setup_with_primary_with_options`](crate::setup_with_primary_with_options) (off by default, leaving
+    /// monospace text to `egui`'s monospace font). Use it when the primary font is itself a monospace font.
+    pub fn primary_monospace(mut self, enabled: bool) -> Self {
+        self.primary_monospace = enabled;
+        self
+    }
+
+    /// Prefers optical "Display" faces, which are drawn for large sizes (off by default).
+    ///
+    /// Each candidate is replaced by its display variant when one is installed (`SF Pro Text` becomes
+    /// `SF Pro Display`, `Noto Serif` becomes `Noto Serif Display`); candidates without one are kept. Best
+    /// combined with a named family for headings, see [`apply_text_style_fonts`](crate::apply_text_style_fonts).
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use egui_system_fonts::{set_with_options, FontOptions, FontRegion, FontStyle};
+    /// # fn demo(ctx: &egui::Context) {
+    /// let options = FontOptions::new().display_faces(true);
+    /// let report = set_with_options(ctx, FontRegion::Latin, FontStyle::Sans, &options).unwrap();
+    /// println!("installed: {:?}", report.installed);
+    /// # }
+    /// ```
+    pub fn display_faces(mut self, enabled: bool) -> Self {
+        self.display_faces = enabled;
+        self
+    }
+
+    /// Caches the resolved font files in `path` and reuses them on later runs.
+    ///
+    /// After a resolution the chosen files are recorded with their sizes and modification times. A later
+    /// call with the same locale, style and presets loads those files directly, skipping font discovery,
+    /// as long as every file is unchanged; otherwise it resolves again and rewrites the cache. In-memory
+    /// fonts are never cached. Use one cache file per font configuration (provider, directories).
+    /// [`default_cache_path`](crate::default_cache_path) suggests a location.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use egui_system_fonts::{set_with_options, FontOptions, FontRegion, FontStyle};
+    /// # fn demo(ctx: &egui::Context) {
+    /// let options = FontOptions::new().cache_file("fonts.cache");
+    /// let report = set_with_options(ctx, FontRegion::Korean, FontStyle::Sans, &options).unwrap();
+    /// println!("installed: {:?}", report.installed);
+    /// # }
+    /// ```
+    pub fn cache_file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.cache_file = Some(path.into());
+        self
+    }
+
+    /// Applies the fonts even if the resulting definitions are already in effect (off by default).
+    ///
+    /// Normally a call whose result equals the current definitions (same keys, order and font data) skips
+    /// `set_fonts`, since `egui` would rebuild its font atlas for nothing, and reports
+    /// [`FontReport::unchanged`](crate::FontReport::unchanged).
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use egui_system_fonts::{set_with_options, FontOptions, FontRegion, FontStyle};
+    /// # fn demo(ctx: &egui::Context) {
+    /// let options = FontOptions::new().force(true);
+    /// let report = set_with_options(ctx, FontRegion::Korean, FontStyle::Sans, &options).unwrap();
+    /// println!("installed: {:?}", report.installed);
+    /// # }
+    /// ```
+    pub fn force(mut self, enabled: bool) -> Self {
+        self.force = enabled;
+        self
+    }
+
+    /// Sets how much the call writes to the global logger ([`Verbosity::Normal`] by default).
+    ///
+    /// [`Verbosity::Silent`] keeps routine font setup out of the app's logs; [`Verbosity::Verbose`] adds a
+    /// line per candidate with its size and read time. Environment overrides
+    /// ([`ENV_REGION`](crate::ENV_REGION), ...) are reported at `Normal` and above as well.
+    pub fn verbosity(mut self, verbosity: Verbosity) -> Self {
+        self.verbosity = verbosity;
+        self
+    }
+
+    /// Sends structured [`LogEvent`]s to `sink`, e.g. to show them in a diagnostics panel.
+    ///
+    /// `sink` is called on the calling thread for every candidate tried, skipped or installed, whatever
+    /// the [`verbosity`](Self::verbosity).
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use egui_system_fonts::{FontOptions, Verbosity};
+    /// let options = FontOptions::new()
+    ///     .verbosity(Verbosity::Silent)
+    ///     .log_sink(|event| eprintln!("fonts: {event:?}"));
+    /// ```
+    pub fn log_sink(mut self, sink: impl Fn(&LogEvent) + Send + Sync + 'static) -> Self {
+        self.log_sink = Some(Hook(Arc::new(sink)));
+        self
+    }
+
+    /// Calls `callback` as the call moves through its phases: looking up fonts, scanning
+    /// the [`add_font_dirs`](Self::add_font_dirs) directories, reading each candidate, done.
+    ///
+    /// `callback` runs on the calling thread, never while the crate holds a lock, so it may call back into
+    /// the crate. Without one (the default) no [`Progress`] is built.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use egui_system_fonts::{FontOptions, Progress};
+    /// let options = FontOptions::new().progress(|progress| {
+    ///     if let Progress::ReadingFont { family, index, total, .. } = progress {
+    ///         println!("reading {family} ({}/{total})", index + 1);
+    ///     }
+    /// });
+    /// ```
+    pub fn progress(mut self, callback: impl Fn(&Progress) + Send + Sync + 'static) -> Self {
+        self.progress = Some(Hook(Arc::new(callback)));
+        self
+    }
+
+    /// Stops the call early once `token` is cancelled.
+    ///
+    /// The token is checked between font lookups and between font reads. A cancelled call fails with
+    /// [`FontError::Cancelled`](crate::FontError::Cancelled) and leaves the context and the definitions
+    /// passed in as they were; fonts read before the cancellation are dropped, not applied. Functions
+    /// returning a plain report return an empty one.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use egui_system_fonts::{set_async_with_options, CancelToken, FontOptions, FontRegion, FontStyle};
+    /// # fn demo(ctx: &egui::Context) {
+    /// let token = CancelToken::new();
+    /// let options = FontOptions::new().cancel_token(token.clone());
+    /// let _task = set_async_with_options(ctx, FontRegion::Korean, FontStyle::Sans, &options);
+    /// // The window is closing.
+    /// token.cancel();
+    /// # }
+    /// ```
+    pub fn cancel_token(mut self, token: CancelToken) -> Self {
+        self.cancel = Some(token);
+        self
+    }
+
+    /// Whether the [`cancel_token`](Self::cancel_token) was cancelled.
+    pub(crate) fn cancelled(&self) -> bool {
+        self.cancel.as_ref().is_some_and(CancelToken::is_cancelled)
+    }
+
+    /// The font families installed fonts of `style` are added to.
+    pub(crate) fn slots(&self, style: FontStyle) -> &'static [FontFamily] {
+        const BOTH: &[FontFamily] = &[FontFamily::Proportional, FontFamily::Monospace];
+        if self.monospace.unwrap_or(style != FontStyle::Serif) {
+            BOTH
+        } else {
+            &BOTH[..1]
+        }
+    }
+
+    /// Whether the call writes to the global logger at all.
+    pub(crate) fn logs(&self) -> bool {
+        self.verbosity > Verbosity::Silent
+    }
+
+    /// Whether the call logs every candidate.
+    pub(crate) fn verbose(&self) -> bool {
+        self.verbosity >= Verbosity::Verbose
+    }
+
+    /// Sends `event` to the [`log_sink`](Self::log_sink), if any.
+    pub(crate) fn emit(&self, event: impl FnOnce() -> LogEvent) {
+        if let Some(Hook(sink)) = &self.log_sink {
+            sink(&event());
+        }
+    }
+
+    /// Sends `progress` to the [`progress`](Self::progress) callback, if any.
+    pub(crate) fn report_progress(&self, progress: impl FnOnce() -> Progress) {
+        if let Some(Hook(callback)) = &self.progress {
+            callback(&progress());
+        }
+    }
+
+    /// Logs `message` as a warning (unless silent) and sends it to the sink.
+    pub(crate) fn warn(&self, message: String) {
+        if self.logs() {
+            log::warn!("{}", message);
+        }
+        self.emit(|| LogEvent::Warning(message));
+    }
+
+    /// The configured provider, or [`SystemProvider`].
+    pub(crate) fn font_provider(&self) -> &dyn FontProvider {
+        match &self.provider {
+            Some(Hook(provider)) => provider.as_ref(),
+            None => &SystemProvider,
+        }
+    }
+}
+
+static GLOBAL_DEFAULTS: RwLock<Option<FontOptions>> = RwLock::new(None);
+
+/// Sets the options used by every call that takes no options, process-wide.
+///
+/// The plain functions (`set_auto`, `extend_auto`, `set_with_region`, ...) use these instead of
+/// `FontOptions::default()`, so an app can configure fonts once even when other crates set them up on the
+/// same context. Precedence is: options passed to a `*_with_options` call (used as they are, not merged),
+/// then these global defaults, then the built-in defaults. Functions that add a setting of their own (such as
+/// [`set_with_subset`](crate::set_with_subset)) apply it on top of the global defaults.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use egui_system_fonts::{set_global_defaults, set_with_region, FontOptions, FontRegion, FontStyle};
+/// # fn demo(ctx: &egui::Context) {
+/// set_global_defaults(FontOptions::new().monospace(false));
+/// set_with_region(ctx, FontRegion::Korean, FontStyle::Sans);
+/// # }
+/// ```
+pub fn set_global_defaults(options: FontOptions) {
+    *GLOBAL_DEFAULTS
+        .write()
+        .unwrap_or_else(PoisonError::into_inner) = Some(options);
+}
+
+/// The options used by calls that take none: those set with [`set_global_defaults`], or
+/// `FontOptions::default()`.
+pub fn global_defaults() -> FontOptions {
+    GLOBAL_DEFAULTS
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clone()
+        .unwrap_or_default()
+}
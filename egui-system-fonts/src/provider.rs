@@ -0,0 +1,230 @@
+//! Pluggable font discovery.
+
+use crate::{FontPreset, FontStyle, FoundFont, FoundFontSource};
+use std::collections::HashSet;
+use std::sync::Arc;
+
+/// Source of candidate fonts and of the locale used by the `*_auto` functions.
+///
+/// [`SystemProvider`] (the default) queries the operating system. Set a different provider with
+/// [`FontOptions::provider`](crate::FontOptions::provider) to test font setup deterministically or to
+/// run without a filesystem.
+///
+/// # Examples
+///
+/// ```
+/// # use egui_system_fonts::{extend_with_options, FontOptions, FontPreset, FontProvider, FontSelection, FontStyle, FoundFont};
+/// # use egui::FontFamily;
+/// /// A provider that finds nothing, e.g. for a headless test run.
+/// struct NoFonts;
+///
+/// impl FontProvider for NoFonts {
+///     fn find(&self, _presets: &[FontPreset], _style: FontStyle) -> Vec<FoundFont> {
+///         Vec::new()
+///     }
+///
+///     fn locale(&self) -> Option<String> {
+///         Some("ja-JP".to_owned())
+///     }
+/// }
+///
+/// let ctx = egui::Context::default();
+/// let mut defs = egui::FontDefinitions::default();
+/// let before = defs.families[&FontFamily::Proportional].clone();
+/// let options = FontOptions::new().provider(NoFonts);
+/// let report = extend_with_options(&ctx, &mut defs, FontSelection::Auto, FontStyle::Sans, &options).unwrap();
+///
+/// assert!(report.installed.is_empty());
+/// assert_eq!(defs.families[&FontFamily::Proportional], before);
+/// ```
+pub trait FontProvider: Send + Sync {
+    /// Returns installed fonts matching `presets`, in priority order.
+    fn find(&self, presets: &[FontPreset], style: FontStyle) -> Vec<FoundFont>;
+
+    /// Returns the current locale string (e.g. `"ko-KR"`), if known.
+    fn locale(&self) -> Option<String>;
+
+    /// Returns the face of `font`'s family with the given weight (100 to 900, 400 being regular), if
+    /// installed.
+    ///
+    /// Used for [`FontOptions::weight`](crate::FontOptions::weight). The default finds none, so the
+    /// regular faces are kept.
+    fn find_weight(&self, font: &FoundFont, weight: u16) -> Option<FoundFont> {
+        let _ = (font, weight);
+        None
+    }
+
+    /// Returns the family names of the font the platform uses for its own UI, best match first.
+    ///
+    /// Used by [`set_system_ui`](crate::set_system_ui). The default knows none, so only the region's
+    /// fonts are used.
+    fn ui_families(&self) -> Vec<String> {
+        Vec::new()
+    }
+
+    /// Returns installed fonts with glyphs for any of `chars`, whatever their preset: those covering the
+    /// most characters first, then those of `style`.
+    ///
+    /// Used by [`extend_for_chars`](crate::extend_for_chars). The default finds none.
+    fn find_for_chars(&self, chars: &[char], style: FontStyle) -> Vec<FoundFont> {
+        let _ = (chars, style);
+        Vec::new()
+    }
+}
+
+/// Discovers fonts installed on the system via [`discovery`](crate::discovery).
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SystemProvider;
+
+impl FontProvider for SystemProvider {
+    fn find(&self, presets: &[FontPreset], style: FontStyle) -> Vec<FoundFont> {
+        system_fonts::find_from_presets(presets.iter().cloned(), style)
+    }
+
+    fn locale(&self) -> Option<String> {
+        system_fonts::system_locale()
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn find_weight(&self, font: &FoundFont, weight: u16) -> Option<FoundFont> {
+        crate::dirs::find_system_weight(font, weight)
+    }
+
+    fn ui_families(&self) -> Vec<String> {
+        crate::ui_font::system_ui_families()
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn find_for_chars(&self, chars: &[char], style: FontStyle) -> Vec<FoundFont> {
+        crate::dirs::find_system_for_chars(chars, style)
+    }
+}
+
+/// Serves caller-supplied in-memory fonts.
+///
+/// Each font is registered for one preset; [`find`](FontProvider::find) returns the fonts of the requested
+/// presets in preset order, then registration order, skipping repeated family names. The style is ignored.
+/// [`find_for_chars`](FontProvider::find_for_chars) ranks all regular faces, whatever their preset. Faces
+/// registered with [`weighted_font`](Self::weighted_font) are only returned by
+/// [`find_weight`](FontProvider::find_weight).
+///
+/// # Examples
+///
+/// ```no_run
+/// # use egui_system_fonts::{set_with_options, FontOptions, FontPreset, FontRegion, FontStyle, StaticProvider};
+/// # fn demo(ctx: &egui::Context, noto_sans_kr: &[u8]) {
+/// // let noto_sans_kr = include_bytes!("../assets/NotoSansKR-Regular.otf");
+/// let provider = StaticProvider::new()
+///     .with_locale("ko-KR")
+///     .font(FontPreset::Korean, "Noto Sans KR", noto_sans_kr.to_vec());
+/// let options = FontOptions::new().provider(provider);
+/// set_with_options(ctx, FontRegion::Korean, FontStyle::Sans, &options).unwrap();
+/// # }
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct StaticProvider {
+    locale: Option<String>,
+    ui_families: Vec<String>,
+    fonts: Vec<(FontPreset, FoundFont, u16)>,
+}
+
+impl StaticProvider {
+    /// Creates an empty provider with no locale.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the locale reported to the `*_auto` functions.
+    pub fn with_locale(mut self, locale: impl Into<String>) -> Self {
+        self.locale = Some(locale.into());
+        self
+    }
+
+    /// Adds `family` to the UI font families reported to [`set_system_ui`](crate::set_system_ui).
+    ///
+    /// Register the font itself with [`font`](Self::font) under `FontPreset::Custom(vec![family])`.
+    pub fn with_ui_family(mut self, family: impl Into<String>) -> Self {
+        self.ui_families.push(family.into());
+        self
+    }
+
+    /// Registers an in-memory font for `preset`.
+    pub fn font(
+        self,
+        preset: FontPreset,
+        family: impl Into<String>,
+        bytes: impl Into<Arc<[u8]>>,
+    ) -> Self {
+        self.weighted_font(preset, family, 400, bytes)
+    }
+
+    /// Registers an in-memory face of `family` with the given weight (400 being regular).
+    pub fn weighted_font(
+        mut self,
+        preset: FontPreset,
+        family: impl Into<String>,
+        weight: u16,
+        bytes: impl Into<Arc<[u8]>>,
+    ) -> Self {
+        let family = family.into();
+        let found = FoundFont {
+            key: format!("system:{}:{}", family, self.fonts.len()),
+            family,
+            source: FoundFontSource::Bytes(bytes.into()),
+        };
+        self.fonts.push((preset, found, weight));
+        self
+    }
+}
+
+impl FontProvider for StaticProvider {
+    fn find(&self, presets: &[FontPreset], _style: FontStyle) -> Vec<FoundFont> {
+        let mut seen_family = HashSet::<&str>::new();
+        let mut out = Vec::new();
+        for preset in presets {
+            for (p, f, weight) in &self.fonts {
+                if *weight == 400 && same_preset(p, preset) && seen_family.insert(&f.family) {
+                    out.push(f.clone());
+                }
+            }
+        }
+        out
+    }
+
+    fn locale(&self) -> Option<String> {
+        self.locale.clone()
+    }
+
+    fn ui_families(&self) -> Vec<String> {
+        self.ui_families.clone()
+    }
+
+    fn find_for_chars(&self, chars: &[char], style: FontStyle) -> Vec<FoundFont> {
+        let mut seen_family = HashSet::<&str>::new();
+        let regular = self
+            .fonts
+            .iter()
+            .filter(|(_, f, weight)| *weight == 400 && seen_family.insert(&f.family))
+            .map(|(_, f, _)| f.clone())
+            .collect();
+        crate::coverage::rank_by_coverage(regular, chars, style)
+    }
+
+    fn find_weight(&self, font: &FoundFont, weight: u16) -> Option<FoundFont> {
+        self.fonts
+            .iter()
+            .find(|(_, f, w)| *w == weight && f.family == font.family)
+            .map(|(_, f, _)| FoundFont {
+                key: font.key.clone(),
+                ..f.clone()
+            })
+    }
+}
+
+/// Compares presets (`FontPreset` has no `PartialEq`).
+pub(crate) fn same_preset(a: &FontPreset, b: &FontPreset) -> bool {
+    match (a, b) {
+        (FontPreset::Custom(a), FontPreset::Custom(b)) => a == b,
+        _ => std::mem::discriminant(a) == std::mem::discriminant(b),
+    }
+}
@@ -0,0 +1,602 @@
+//! Reading candidate font bytes.
+
+use crate::embedded;
+use crate::font_cache;
+use crate::names::{family_from_key, normalize_family_name, same_family_name};
+use crate::tweak::{fit_metrics, tweak_for};
+use crate::{
+    is_system_font_key, namespaced_key, FontKey, FontOptions, FontReport, FoundFont, LogEvent,
+    Progress, SkipReason, SkippedFont,
+};
+use egui::{FontData, FontDefinitions, FontTweak};
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::{Duration, Instant};
+use system_fonts::FoundFontSource;
+
+/// A candidate whose bytes have been read.
+pub(crate) struct LoadedFont {
+    /// Namespaced `font_data` key.
+    pub(crate) key: FontKey,
+    pub(crate) family: String,
+    /// Possibly shared with fonts installed by earlier calls.
+    pub(crate) data: Arc<FontData>,
+}
+
+/// Keys, normalized family names and fonts already present in the target definitions.
+#[derive(Default)]
+pub(crate) struct Installed {
+    keys: HashSet<String>,
+    families: HashSet<String>,
+    /// Canonical paths of the font files installed by this call, with their keys.
+    files: HashMap<PathBuf, FontKey>,
+    /// Data of the fonts installed by this crate, with their keys.
+    data: Vec<(FontKey, Arc<FontData>)>,
+}
+
+impl Installed {
+    pub(crate) fn from_defs(defs: &FontDefinitions) -> Self {
+        let keys: HashSet<String> = defs.font_data.keys().cloned().collect();
+        let families = keys
+            .iter()
+            .filter_map(|key| family_from_key(key))
+            .map(normalize_family_name)
+            .collect();
+        let data = defs
+            .font_data
+            .iter()
+            .filter(|(key, _)| is_system_font_key(key))
+            .map(|(key, data)| (FontKey::from(key.as_str()), data.clone()))
+            .collect();
+        Self {
+            keys,
+            families,
+            files: HashMap::new(),
+            data,
+        }
+    }
+
+    /// The installed family `normalized` (a normalized family name) duplicates, if any.
+    fn same_family(&self, normalized: &str) -> Option<&String> {
+        self.families
+            .iter()
+            .find(|family| same_family_name(family, normalized))
+    }
+
+    /// The key of an installed font with the same bytes as `data`.
+    fn same_data(&self, data: &Arc<FontData>) -> Option<&FontKey> {
+        self.data
+            .iter()
+            .find(|(_, installed)| {
+                Arc::ptr_eq(installed, data)
+                    || (installed.font.len() == data.font.len()
+                        && installed.index == data.index
+                        && installed.font == data.font)
+            })
+            .map(|(key, _)| key)
+    }
+}
+
+/// Reads candidates in priority order, applying the filter and size limits in `options`.
+///
+/// Candidates whose key or normalized family name is already in `installed` are skipped; loaded fonts are
+/// added to it. Installed and skipped candidates are recorded in `report`. `progress` is called with the
+/// family, its position among the candidates and their count after each font is read.
+pub(crate) fn read_found_fonts(
+    fonts: Vec<FoundFont>,
+    options: &FontOptions,
+    report: &mut FontReport,
+    installed: &mut Installed,
+    progress: &mut dyn FnMut(&str, usize, usize),
+) -> Vec<LoadedFont> {
+    let mut loaded = Vec::new();
+    let total = fonts.len();
+    let accepted: Vec<bool> = match &options.filter {
+        Some(filter) => fonts.iter().map(|f| (filter.0)(f)).collect(),
+        None => vec![true; total],
+    };
+    let mut prefetched = prefetch(&fonts, &accepted, options, installed);
+
+    for (index, f) in fonts.into_iter().enumerate() {
+        if options.cancelled() {
+            return loaded;
+        }
+        options.report_progress(|| Progress::ReadingFont {
+            family: f.family.clone(),
+            path: match &f.source {
+                FoundFontSource::Path(path) => Some(path.clone()),
+                FoundFontSource::Bytes(_) => None,
+            },
+            index,
+            total,
+        });
+        let key = namespaced_key(&f.key);
+        options.emit(|| LogEvent::Candidate {
+            family: f.family.clone(),
+            key: key.clone(),
+        });
+        // Definitions built by 0.1 stored the raw key; treat those as already installed.
+        if installed.keys.contains(key.as_str()) || installed.keys.contains(&f.key) {
+            skip(
+                options,
+                report,
+                SkippedFont {
+                    family: f.family,
+                    key,
+                    reason: SkipReason::AlreadyInstalled,
+                },
+            );
+            continue;
+        }
+
+        let normalized = normalize_family_name(&f.family);
+        if let Some(first) = installed.same_family(&normalized) {
+            log::debug!("Candidate {:?} duplicates {:?}", f.family, first);
+            let reason = SkipReason::DuplicateFamily(first.clone());
+            skip(
+                options,
+                report,
+                SkippedFont {
+                    family: f.family,
+                    key,
+                    reason,
+                },
+            );
+            continue;
+        }
+
+        let file = canonical_file(&f.source);
+        if let Some(first) = file.as_ref().and_then(|file| installed.files.get(file)) {
+            log::debug!("Candidate {:?} is the file of {}", f.family, first);
+            let reason = SkipReason::DuplicateFile(first.clone());
+            skip(
+                options,
+                report,
+                SkippedFont {
+                    family: f.family,
+                    key,
+                    reason,
+                },
+            );
+            continue;
+        }
+
+        if !accepted[index] {
+            log::debug!("Candidate {:?} rejected by filter", f.family);
+            skip(
+                options,
+                report,
+                SkippedFont {
+                    family: f.family,
+                    key,
+                    reason: SkipReason::Filtered,
+                },
+            );
+            continue;
+        }
+
+        if let Some(max) = options.max_families {
+            if installed.families.len() >= max {
+                skip(
+                    options,
+                    report,
+                    SkippedFont {
+                        family: f.family,
+                        key,
+                        reason: SkipReason::TooManyFamilies(max),
+                    },
+                );
+                continue;
+            }
+        }
+
+        let started = Instant::now();
+        let prefetched = prefetched[index].take();
+        // A font read ahead counts the time it took then, not just the moment to pick it up.
+        let read_ahead = prefetched
+            .as_ref()
+            .map_or(Duration::ZERO, |&(_, took)| took);
+        let (tweak, fit) = tweak_for(&f, options);
+        let tweak = |font: &[u8]| if fit { fit_metrics(tweak, font) } else { tweak };
+        let budget = check_budget(&f.source, options, report.installed_bytes);
+        let data = budget.and_then(|()| match font_cache::get(&f.source, tweak) {
+            Some(data) if !subsets(options) => Ok(data),
+            _ => read_font_data(&f, tweak, options, prefetched.map(|(bytes, _)| bytes)),
+        });
+        let data = match data {
+            // In-memory fonts are the caller's explicit choice; only files are compared by contents.
+            Ok(data) => match installed.same_data(&data).filter(|_| file.is_some()) {
+                Some(first) => Err(SkipReason::DuplicateFile(first.clone())),
+                None => Ok(data),
+            },
+            Err(reason) => Err(reason),
+        };
+        let data = match data {
+            Ok(data) => data,
+            Err(reason) => {
+                skip(
+                    options,
+                    report,
+                    SkippedFont {
+                        family: f.family,
+                        key,
+                        reason,
+                    },
+                );
+                continue;
+            }
+        };
+
+        let len = data.font.len();
+        let elapsed = started.elapsed() + read_ahead;
+        if options.verbose() {
+            log::info!(
+                "Installed {:?} from {} ({} bytes, read in {:?})",
+                f.family,
+                describe_source(&f.source),
+                len,
+                elapsed
+            );
+        }
+        options.emit(|| LogEvent::Installed {
+            family: f.family.clone(),
+            key: key.clone(),
+            bytes: len as u64,
+            elapsed,
+        });
+        crate::chain::record_origin(&key, &f.family, &f.source);
+        installed.keys.insert(key.to_string());
+        installed.families.insert(normalized);
+        installed.files.extend(file.map(|file| (file, key.clone())));
+        installed.data.push((key.clone(), data.clone()));
+        report.installed_bytes += len as u64;
+        report.installed.push(f.family.clone());
+        report.installed_keys.push(key.clone());
+        report.tweaks.push((f.family.clone(), data.tweak));
+        #[cfg(feature = "subset")]
+        if subsets(options) {
+            let original = source_len(&f.source).unwrap_or(len as u64);
+            report.subset.push((f.family.clone(), original, len as u64));
+        }
+        progress(&f.family, index, total);
+        loaded.push(LoadedFont {
+            key,
+            family: f.family,
+            data,
+        });
+    }
+
+    options.report_progress(|| Progress::Done {
+        installed: loaded.len(),
+    });
+    loaded
+}
+
+/// Reads (unless `prefetched`), checks and prepares the data of `font`, sharing it with later calls (see
+/// [`font_cache`]).
+///
+/// `tweak` picks the tweak for the face that is kept.
+fn read_font_data(
+    font: &FoundFont,
+    tweak: impl Fn(&[u8]) -> FontTweak,
+    options: &FontOptions,
+    prefetched: Option<Result<Cow<'static, [u8]>, SkipReason>>,
+) -> Result<Arc<FontData>, SkipReason> {
+    let bytes = match prefetched {
+        Some(bytes) => bytes?,
+        None => read_font_bytes(&font.source, options)?,
+    };
+    validate_sfnt(&bytes)?;
+    #[cfg(feature = "ttc-extract")]
+    let bytes = crate::collection::first_face(bytes, &font.family);
+    let tweak = tweak(&bytes);
+    #[cfg(feature = "subset")]
+    let bytes = match &options.subset {
+        Some(chars) => Cow::Owned(crate::subset::subset_or_full(
+            bytes.into_owned(),
+            chars,
+            &font.family,
+            options,
+        )),
+        None => bytes,
+    };
+
+    let data = Arc::new(FontData {
+        font: bytes,
+        index: 0,
+        tweak,
+    });
+    if !subsets(options) {
+        font_cache::insert(&font.source, &data);
+    }
+    Ok(data)
+}
+
+/// How many font files [`prefetch`] reads at once, so a spinning disk is not read in too many places.
+const PARALLEL_READS: usize = 4;
+
+/// A candidate's bytes, read ahead of its turn, with the time the read took.
+type Prefetched = Option<(Result<Cow<'static, [u8]>, SkipReason>, Duration)>;
+
+/// Reads the files of the candidates [`read_found_fonts`] is going to read, several at a time.
+///
+/// Returns one entry per candidate, `None` for those left to the in-order pass: fonts already installed,
+/// in memory or shared from an earlier call, repeats of a family, and everything when a size or family
+/// limit decides in priority order which fonts are read at all. Files reached through several paths are
+/// read once. A failed read only affects its own entry.
+fn prefetch(
+    fonts: &[FoundFont],
+    accepted: &[bool],
+    options: &FontOptions,
+    installed: &Installed,
+) -> Vec<Prefetched> {
+    let mut out: Vec<Prefetched> = fonts.iter().map(|_| None).collect();
+    if cfg!(target_arch = "wasm32")
+        || options.max_total_bytes.is_some()
+        || options.max_file_bytes.is_some()
+        || options.max_families.is_some()
+    {
+        return out;
+    }
+
+    let mut families = HashSet::new();
+    let mut files = HashSet::new();
+    let wanted: Vec<usize> = (0..fonts.len())
+        .filter(|&i| {
+            let f = &fonts[i];
+            let on_disk = matches!(&f.source, FoundFontSource::Path(path) if embedded::lookup(path).is_none());
+            let key = namespaced_key(&f.key);
+            let family = normalize_family_name(&f.family);
+            accepted[i]
+                && on_disk
+                && !installed.keys.contains(key.as_str())
+                && !installed.keys.contains(&f.key)
+                && installed.same_family(&family).is_none()
+                && !families.iter().any(|f: &String| same_family_name(f, &family))
+                && families.insert(family)
+                && canonical_file(&f.source).is_none_or(|file| files.insert(file))
+                && (subsets(options) || !font_cache::contains(&f.source))
+        })
+        .collect();
+    if wanted.len() < 2 {
+        return out;
+    }
+
+    let next = AtomicUsize::new(0);
+    let results = Mutex::new(&mut out);
+    std::thread::scope(|scope| {
+        for _ in 0..PARALLEL_READS.min(wanted.len()) {
+            let worker = || {
+                while let Some(&i) = wanted.get(next.fetch_add(1, Ordering::Relaxed)) {
+                    if options.cancelled() {
+                        break;
+                    }
+                    let started = Instant::now();
+                    let bytes = read_font_bytes(&fonts[i].source, options);
+                    results.lock().unwrap_or_else(PoisonError::into_inner)[i] =
+                        Some((bytes, started.elapsed()));
+                }
+            };
+            let spawned = std::thread::Builder::new()
+                .name("egui-system-fonts-read".to_owned())
+                .spawn_scoped(scope, worker);
+            if let Err(e) = spawned {
+                // Whatever is not read here is read in order afterwards.
+                log::debug!("Failed to spawn font reader thread: {}", e);
+                break;
+            }
+        }
+    });
+    out
+}
+
+/// Whether fonts are trimmed for this call, so their data is not shared with other calls.
+fn subsets(options: &FontOptions) -> bool {
+    #[cfg(feature = "subset")]
+    if options.subset.is_some() {
+        return true;
+    }
+    let _ = options;
+    false
+}
+
+/// Records a skipped candidate in `report`, logging it as `options` asks.
+fn skip(options: &FontOptions, report: &mut FontReport, skipped: SkippedFont) {
+    match &skipped.reason {
+        SkipReason::TimedOut(timeout) if options.logs() => {
+            log::warn!("Timed out reading {:?} after {:?}", skipped.family, timeout);
+        }
+        SkipReason::InvalidFont(e) if options.logs() => {
+            log::warn!(
+                "Ignoring {:?}, which is not a valid font: {}",
+                skipped.family,
+                e
+            );
+        }
+        reason if options.verbose() => {
+            log::info!("Skipped {:?}: {:?}", skipped.family, reason);
+        }
+        _ => {}
+    }
+    options.emit(|| LogEvent::Skipped(skipped.clone()));
+    report.skipped.push(skipped);
+}
+
+/// The canonical path of a candidate's file, so symlinks and other aliases of one file compare equal.
+///
+/// Falls back to the path as given when it cannot be resolved (e.g. for embedded fonts).
+fn canonical_file(source: &FoundFontSource) -> Option<PathBuf> {
+    match source {
+        FoundFontSource::Path(path) => {
+            Some(std::fs::canonicalize(path).unwrap_or_else(|_| path.clone()))
+        }
+        FoundFontSource::Bytes(_) => None,
+    }
+}
+
+fn describe_source(source: &FoundFontSource) -> String {
+    match source {
+        FoundFontSource::Path(path) => format!("{:?}", path),
+        FoundFontSource::Bytes(_) => "memory".to_owned(),
+    }
+}
+
+/// Reads the bytes of a candidate.
+///
+/// Embedded fonts (and, with the `mmap` feature, font files) are borrowed, not copied. In-memory fonts are
+/// copied once: `egui` keeps font bytes as owned or `'static` data, which a shared `Arc<[u8]>` is neither.
+pub(crate) fn read_font_bytes(
+    source: &FoundFontSource,
+    options: &FontOptions,
+) -> Result<Cow<'static, [u8]>, SkipReason> {
+    match source {
+        FoundFontSource::Path(path) => {
+            if let Some(bytes) = embedded::lookup(path) {
+                return Ok(Cow::Borrowed(bytes));
+            }
+            #[cfg(all(feature = "mmap", not(target_arch = "wasm32")))]
+            if let Some(bytes) = crate::mmap::map(path) {
+                return Ok(Cow::Borrowed(bytes));
+            }
+            let bytes = match options.candidate_timeout {
+                Some(timeout) => read_with_timeout(path, timeout),
+                None => read_file(path),
+            };
+            bytes.map(Cow::Owned)
+        }
+        FoundFontSource::Bytes(b) => Ok(Cow::Owned(b.to_vec())),
+    }
+}
+
+/// Checks that `bytes` look like a complete TrueType/OpenType font (or collection) before it is handed to
+/// `egui`.
+///
+/// Only the header and table directory of the first face (the one that is installed) are checked: the
+/// magic number, and that every table lies within the data. This catches truncated and non-font files
+/// without parsing the tables themselves.
+pub(crate) fn validate_sfnt(bytes: &[u8]) -> Result<(), SkipReason> {
+    let invalid = |e: &str| Err(SkipReason::InvalidFont(e.to_owned()));
+    let face_offset = match read_u32(bytes, 0) {
+        Some(0x0001_0000) | Some(0x4F54_544F) | Some(0x7472_7565) => 0, // 1.0, `OTTO`, `true`
+        Some(0x7474_6366) => {
+            // `ttcf`: the first face's offset follows the version and face count.
+            match (read_u32(bytes, 8), read_u32(bytes, 12)) {
+                (Some(faces), Some(offset)) if faces > 0 => offset as usize,
+                _ => return invalid("truncated font collection header"),
+            }
+        }
+        Some(_) => return invalid("unknown font format"),
+        None => return invalid("file too short"),
+    };
+
+    let Some(num_tables) = read_u16(bytes, face_offset.saturating_add(4)) else {
+        return invalid("truncated table directory");
+    };
+    if num_tables == 0 {
+        return invalid("no tables");
+    }
+    for i in 0..usize::from(num_tables) {
+        let record = face_offset.saturating_add(12 + 16 * i);
+        let (Some(offset), Some(len)) = (
+            read_u32(bytes, record.saturating_add(8)),
+            read_u32(bytes, record.saturating_add(12)),
+        ) else {
+            return invalid("truncated table directory");
+        };
+        let end = u64::from(offset) + u64::from(len);
+        if end > bytes.len() as u64 {
+            let tag = String::from_utf8_lossy(&bytes[record..record + 4]);
+            return Err(SkipReason::InvalidFont(format!(
+                "table `{}` ends at byte {}, past the end of the {}-byte file",
+                tag,
+                end,
+                bytes.len()
+            )));
+        }
+    }
+    Ok(())
+}
+
+pub(crate) fn read_u16(bytes: &[u8], at: usize) -> Option<u16> {
+    let b = bytes.get(at..at.checked_add(2)?)?;
+    Some(u16::from_be_bytes([b[0], b[1]]))
+}
+
+pub(crate) fn read_u32(bytes: &[u8], at: usize) -> Option<u32> {
+    let b = bytes.get(at..at.checked_add(4)?)?;
+    Some(u32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+}
+
+/// Checks the size limits in `options` before a candidate is read.
+///
+/// `used` is the number of bytes already installed by the current call.
+pub(crate) fn check_budget(
+    source: &FoundFontSource,
+    options: &FontOptions,
+    used: u64,
+) -> Result<(), SkipReason> {
+    if options.max_file_bytes.is_none() && options.max_total_bytes.is_none() {
+        return Ok(());
+    }
+
+    let size = source_len(source)?;
+    if let Some(max) = options.max_file_bytes {
+        if size > max {
+            return Err(SkipReason::FileTooLarge(size));
+        }
+    }
+    if let Some(max) = options.max_total_bytes {
+        if used.saturating_add(size) > max {
+            return Err(SkipReason::OverBudget(size));
+        }
+    }
+    Ok(())
+}
+
+fn source_len(source: &FoundFontSource) -> Result<u64, SkipReason> {
+    match source {
+        FoundFontSource::Path(path) => match embedded::lookup(path) {
+            Some(bytes) => Ok(bytes.len() as u64),
+            None => std::fs::metadata(path)
+                .map(|m| m.len())
+                .map_err(|e| SkipReason::ReadFailed(e.to_string())),
+        },
+        FoundFontSource::Bytes(b) => Ok(b.len() as u64),
+    }
+}
+
+fn read_file(path: &Path) -> Result<Vec<u8>, SkipReason> {
+    std::fs::read(path).map_err(|e| {
+        log::debug!("Failed to read font file {:?}: {}", path, e);
+        SkipReason::ReadFailed(e.to_string())
+    })
+}
+
+fn read_with_timeout(path: &Path, timeout: Duration) -> Result<Vec<u8>, SkipReason> {
+    let (tx, rx) = mpsc::channel();
+    let owned = path.to_path_buf();
+    let spawned = std::thread::Builder::new()
+        .name("egui-system-fonts-read".to_owned())
+        .spawn(move || {
+            let _ = tx.send(read_file(&owned));
+        });
+    if let Err(e) = spawned {
+        log::debug!("Failed to spawn font reader thread: {}", e);
+        return read_file(path);
+    }
+
+    match rx.recv_timeout(timeout) {
+        Ok(result) => result,
+        Err(mpsc::RecvTimeoutError::Timeout) => {
+            log::debug!("Timed out reading font file {:?} after {:?}", path, timeout);
+            Err(SkipReason::TimedOut(timeout))
+        }
+        Err(mpsc::RecvTimeoutError::Disconnected) => Err(SkipReason::ReadFailed(
+            "font reader thread exited".to_owned(),
+        )),
+    }
+}
@@ -0,0 +1,125 @@
+//! Results returned by the `*_with_options` functions.
+
+use crate::{FontKey, FontPreset, FontRegion};
+use egui::FontTweak;
+use std::time::Duration;
+
+/// Outcome of a set/extend call.
+///
+/// # Examples
+///
+/// ```
+/// # use egui_system_fonts::{set_with_options, FontOptions, FontRegion, FontSelection, FontStyle, StaticProvider};
+/// let options = FontOptions::new().provider(StaticProvider::new().with_locale("ko-KR"));
+/// let ctx = egui::Context::default();
+/// let report = set_with_options(&ctx, FontSelection::Auto, FontStyle::Sans, &options).unwrap();
+/// assert_eq!(report.locale.as_deref(), Some("ko-KR"));
+/// assert_eq!(report.region, Some(FontRegion::Korean));
+/// assert!(report.installed.is_empty());
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct FontReport {
+    /// Locale the region was detected from, if any (only for [`FontSelection::Auto`](crate::FontSelection::Auto)
+    /// without a region override).
+    pub locale: Option<String>,
+    /// Region the fonts were resolved for, if any.
+    pub region: Option<FontRegion>,
+    /// Installed font family names, in priority order.
+    pub installed: Vec<String>,
+    /// `font_data` keys of the installed fonts, in the same order as [`installed`](Self::installed).
+    pub installed_keys: Vec<FontKey>,
+    /// Candidates that were resolved but not installed.
+    pub skipped: Vec<SkippedFont>,
+    /// Presets that had more candidates than [`FontOptions::max_fonts_per_preset`](crate::FontOptions::max_fonts_per_preset),
+    /// with the number of candidates left out (not read and not listed in [`skipped`](Self::skipped)).
+    pub trimmed: Vec<(FontPreset, usize)>,
+    /// Total size of the installed font data, in bytes.
+    pub installed_bytes: u64,
+    /// The `FontTweak` applied to each installed family, in priority order.
+    pub tweaks: Vec<(String, FontTweak)>,
+    /// How many times the definitions were applied to the context, each rebuilding `egui`'s font atlas.
+    pub applications: usize,
+    /// `true` if the resulting definitions were already in effect, so they were not applied again (see
+    /// [`FontOptions::force`](crate::FontOptions::force)).
+    pub unchanged: bool,
+    /// With [`FontOptions::subset`](crate::FontOptions::subset): each installed family with the size of its
+    /// file and of the subset installed instead, in bytes (equal when the font could not be subset).
+    #[cfg(feature = "subset")]
+    pub subset: Vec<(String, u64, u64)>,
+    /// Problems that did not stop the call, e.g. an [`Anchor`](crate::Anchor) missing from a family.
+    pub warnings: Vec<String>,
+}
+
+impl FontReport {
+    /// Adds the outcome of a later step to this report.
+    pub(crate) fn merge(&mut self, other: FontReport) {
+        self.locale = self.locale.take().or(other.locale);
+        self.region = self.region.or(other.region);
+        self.installed.extend(other.installed);
+        self.installed_keys.extend(other.installed_keys);
+        self.skipped.extend(other.skipped);
+        self.trimmed.extend(other.trimmed);
+        self.installed_bytes += other.installed_bytes;
+        self.tweaks.extend(other.tweaks);
+        #[cfg(feature = "subset")]
+        self.subset.extend(other.subset);
+        self.applications += other.applications;
+        self.warnings.extend(other.warnings);
+    }
+}
+
+/// A candidate font that was not installed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SkippedFont {
+    /// Font family name of the candidate.
+    pub family: String,
+    /// The `font_data` key the candidate would have used.
+    pub key: FontKey,
+    /// Why the candidate was skipped.
+    pub reason: SkipReason,
+}
+
+/// Why a candidate font was not installed.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use egui_system_fonts::{set_with_options, FontOptions, FontRegion, FontStyle, SkipReason};
+/// # fn demo(ctx: &egui::Context) {
+/// let report = set_with_options(ctx, FontRegion::Korean, FontStyle::Sans, &FontOptions::new()).unwrap();
+/// for skipped in &report.skipped {
+///     if let SkipReason::InvalidFont(e) = &skipped.reason {
+///         eprintln!("{} is damaged: {e}", skipped.family);
+///     }
+/// }
+/// # }
+/// ```
+#[non_exhaustive]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SkipReason {
+    /// The key is already present in the definitions.
+    AlreadyInstalled,
+    /// A font of the same normalized family (see
+    /// [`normalize_family_name`](crate::normalize_family_name)) is already installed; holds that family.
+    /// A pan-CJK family without a language tag (`Noto Sans CJK`) counts as the same as its tagged names
+    /// (`Noto Sans CJK SC`), but differently tagged names are kept apart.
+    DuplicateFamily(String),
+    /// The candidate is the same font file as the one installed under the given key: the same file reached
+    /// through another path (e.g. a symlink), or another file with identical contents.
+    DuplicateFile(FontKey),
+    /// The caller's [`FontOptions::filter`](crate::FontOptions::filter) rejected the candidate.
+    Filtered,
+    /// Reading the font file failed.
+    ReadFailed(String),
+    /// The file was read but is not a usable font: not TrueType/OpenType, or truncated or damaged.
+    InvalidFont(String),
+    /// Reading the font file took longer than the configured timeout.
+    TimedOut(Duration),
+    /// The font (of the given size in bytes) exceeds [`FontOptions::max_file_bytes`](crate::FontOptions::max_file_bytes).
+    FileTooLarge(u64),
+    /// The font (of the given size in bytes) would exceed [`FontOptions::max_total_bytes`](crate::FontOptions::max_total_bytes).
+    OverBudget(u64),
+    /// The definitions already hold [`FontOptions::max_families`](crate::FontOptions::max_families) (the
+    /// given number of) system font families.
+    TooManyFamilies(usize),
+}
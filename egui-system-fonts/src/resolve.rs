@@ -0,0 +1,365 @@
+//! Turning a [`FontSelection`] into candidate fonts.
+
+use crate::candidates::{builtin_preset_of, builtin_style_of, monospace_families};
+use crate::{
+    region_for_locale, EnvOverrides, FontError, FontOptions, FontPreset, FontProvider, FontRegion,
+    FontSelection, FontStyle, FoundFont, LogEvent, PrimaryScript, Progress, ENV_DISABLE,
+    ENV_LOCALE, ENV_REGION, ENV_STYLE,
+};
+use std::collections::HashSet;
+
+/// What a selection expands to, before any font lookup.
+pub(crate) struct Plan {
+    /// Locale the region was derived from, if any.
+    pub(crate) locale: Option<String>,
+    /// Region the presets were derived from, if any.
+    pub(crate) region: Option<FontRegion>,
+    /// Effective style (after environment overrides).
+    pub(crate) style: FontStyle,
+    /// Presets in priority order.
+    pub(crate) presets: Vec<FontPreset>,
+    /// Candidates left out of each preset by [`FontOptions::max_fonts_per_preset`], filled in by
+    /// [`resolve`].
+    pub(crate) trimmed: Vec<(FontPreset, usize)>,
+}
+
+/// Candidates resolved for one call.
+pub(crate) struct Resolution {
+    pub(crate) plan: Plan,
+    /// Candidates in priority order.
+    pub(crate) fonts: Vec<FoundFont>,
+}
+
+/// Resolves candidate fonts for a selection.
+///
+/// Returns `None` when environment overrides disable the crate.
+pub(crate) fn resolve(
+    selection: FontSelection,
+    style: FontStyle,
+    options: &FontOptions,
+) -> Option<Resolution> {
+    let mut plan = plan(
+        selection,
+        style,
+        options.env.as_ref(),
+        options.font_provider(),
+        options.logs(),
+    )?;
+    #[cfg(feature = "testing")]
+    if let Some(fonts) = crate::testing::injected() {
+        report_resolved(&plan, &fonts, options);
+        return Some(Resolution { plan, fonts });
+    }
+    let mut presets = plan.presets.clone();
+    if options.primary_script == PrimaryScript::Latin {
+        // Stable: the other presets keep their order behind Latin.
+        presets.sort_by_key(|preset| !matches!(preset, FontPreset::Latin));
+    }
+    if !options.preferred_families.is_empty() {
+        let preferred = FontPreset::Custom(options.preferred_families.clone());
+        presets.insert(0, preferred);
+    }
+    if options.fixed_pitch_monospace {
+        let monospace = plan.presets.iter().flat_map(monospace_families);
+        presets.extend(monospace.map(|family| FontPreset::Custom(vec![family])));
+    }
+    let cache_key = format!(
+        "{:?} {:?} {:?} {:?} {:?}",
+        plan.locale, plan.style, options.weight, options.max_fonts_per_preset, presets
+    );
+    if let Some(path) = &options.cache_file {
+        if let Some(fonts) = crate::cache::load(path, &cache_key) {
+            report_resolved(&plan, &fonts, options);
+            return Some(Resolution { plan, fonts });
+        }
+    }
+
+    options.report_progress(|| Progress::Resolving);
+    #[cfg(not(target_arch = "wasm32"))]
+    if !options.font_dirs.is_empty() {
+        options.report_progress(|| Progress::ScanningDirectories {
+            count: options.font_dirs.len(),
+        });
+    }
+    let find_in = |presets: &[FontPreset]| match &options.style_classifier {
+        Some(_) => find_classified(presets, plan.style, options),
+        None => find(presets, plan.style, options),
+    };
+    let mut fonts = match options.max_fonts_per_preset {
+        Some(max) => {
+            let mut seen_family = HashSet::<String>::new();
+            let mut fonts = Vec::new();
+            for preset in &presets {
+                let found: Vec<FoundFont> = find_in(std::slice::from_ref(preset))
+                    .into_iter()
+                    .filter(|f| seen_family.insert(f.family.clone()))
+                    .collect();
+                if found.len() > max {
+                    log::debug!("Leaving out {} {:?} candidates", found.len() - max, preset);
+                    plan.trimmed.push((preset.clone(), found.len() - max));
+                }
+                fonts.extend(found.into_iter().take(max));
+            }
+            fonts
+        }
+        None => find_in(&presets),
+    };
+    if options.display_faces {
+        fonts = fonts
+            .into_iter()
+            .map(|f| display_face(f, plan.style, options))
+            .collect();
+    }
+    if let Some(weight) = options.weight.filter(|&w| w != 400) {
+        fonts = fonts
+            .into_iter()
+            .map(|f| weighted_face(f, weight, options))
+            .collect();
+    }
+    report_resolved(&plan, &fonts, options);
+    if let Some(path) = options.cache_file.as_ref().filter(|_| !options.cancelled()) {
+        if let Err(e) = crate::cache::store(path, &cache_key, &fonts) {
+            options.warn(format!("Failed to update font cache {:?}: {}", path, e));
+        }
+    }
+    Some(Resolution { plan, fonts })
+}
+
+/// Logs the outcome of planning and finding candidates.
+fn report_resolved(plan: &Plan, fonts: &[FoundFont], options: &FontOptions) {
+    if options.logs() {
+        log::info!(
+            "Detected locale: {:?}, region: {:?}, style: {:?}, candidates: {}",
+            plan.locale,
+            plan.region,
+            plan.style,
+            fonts.len()
+        );
+    }
+    options.emit(|| LogEvent::Resolved {
+        locale: plan.locale.clone(),
+        region: plan.region,
+        style: plan.style,
+        candidates: fonts.len(),
+    });
+}
+
+/// Swaps `font` for its optical display face (`SF Pro Text` -> `SF Pro Display`,
+/// `Noto Serif` -> `Noto Serif Display`) if one is installed.
+fn display_face(font: FoundFont, style: FontStyle, options: &FontOptions) -> FoundFont {
+    let base = font.family.strip_suffix(" Text").unwrap_or(&font.family);
+    if base.ends_with(" Display") {
+        return font;
+    }
+    let display = FontPreset::Custom(vec![format!("{base} Display")]);
+    match find(&[display], style, options).into_iter().next() {
+        Some(found) => {
+            log::debug!("Using {:?} instead of {:?}", found.family, font.family);
+            found
+        }
+        None => font,
+    }
+}
+
+/// Swaps `font` for its face of `weight`, or keeps it (with a warning) if there is none.
+fn weighted_face(font: FoundFont, weight: u16, options: &FontOptions) -> FoundFont {
+    match options.font_provider().find_weight(&font, weight) {
+        Some(found) => found,
+        None => {
+            let preset = builtin_preset_of(&font.family);
+            options.warn(format!(
+                "No weight {} face of {:?} (preset {:?}); using its regular face",
+                weight, font.family, preset
+            ));
+            font
+        }
+    }
+}
+
+/// Finds candidates for `presets`, strictly in preset order.
+///
+/// Within each preset, fonts in the option's font directories come first, then the provider's. A family
+/// found earlier (for an earlier preset, or in a font directory) is not repeated.
+pub(crate) fn find(
+    presets: &[FontPreset],
+    style: FontStyle,
+    options: &FontOptions,
+) -> Vec<FoundFont> {
+    #[cfg(feature = "testing")]
+    if let Some(fonts) = crate::testing::injected_for(presets, style) {
+        return fonts;
+    }
+
+    let mut seen_family = HashSet::<String>::new();
+    let mut fonts = Vec::new();
+    for preset in presets {
+        if options.cancelled() {
+            break;
+        }
+        let preset = std::slice::from_ref(preset);
+        #[cfg(not(target_arch = "wasm32"))]
+        if !options.font_dirs.is_empty() {
+            let bundled = crate::dirs::find_in_dirs(&options.font_dirs, preset, style);
+            fonts.extend(
+                bundled
+                    .into_iter()
+                    .filter(|f| seen_family.insert(f.family.clone())),
+            );
+        }
+        let provided = options.font_provider().find(preset, style);
+        fonts.extend(
+            provided
+                .into_iter()
+                .filter(|f| seen_family.insert(f.family.clone())),
+        );
+    }
+    fonts
+}
+
+/// Like [`find`], but within each preset moves fonts classified as another style behind those that
+/// match `style`.
+///
+/// Classification uses [`FontOptions::style_classifier`], falling back to the candidate list a family
+/// appears in. Unclassified fonts count as matching.
+fn find_classified(
+    presets: &[FontPreset],
+    style: FontStyle,
+    options: &FontOptions,
+) -> Vec<FoundFont> {
+    let mut seen_family = HashSet::<String>::new();
+    let mut out = Vec::new();
+    for preset in presets {
+        let (matching, other): (Vec<_>, Vec<_>) =
+            find(std::slice::from_ref(preset), style, options)
+                .into_iter()
+                .filter(|f| seen_family.insert(f.family.clone()))
+                .partition(|f| classify(f, options).is_none_or(|s| s == style));
+        for f in &other {
+            log::debug!("Candidate {:?} is not {:?}; demoted", f.family, style);
+        }
+        out.extend(matching);
+        out.extend(other);
+    }
+    out
+}
+
+/// The style of `font`: the caller's classification, else the built-in candidate list it appears in.
+pub(crate) fn classify(font: &FoundFont, options: &FontOptions) -> Option<FontStyle> {
+    options
+        .style_classifier
+        .as_ref()
+        .and_then(|hook| (hook.0)(font))
+        .or_else(|| builtin_style_of(&font.family))
+}
+
+/// Expands a selection into presets, applying environment overrides for [`FontSelection::Auto`].
+///
+/// `env` defaults to the process environment. Returns `None` when environment overrides disable the crate.
+/// Active overrides are logged if `logs`.
+pub(crate) fn plan(
+    selection: FontSelection,
+    style: FontStyle,
+    env: Option<&EnvOverrides>,
+    provider: &dyn FontProvider,
+    logs: bool,
+) -> Option<Plan> {
+    match selection {
+        FontSelection::Auto => match env {
+            Some(env) => plan_auto(style, env, provider, logs),
+            None => plan_auto(style, &EnvOverrides::from_env(), provider, logs),
+        },
+        FontSelection::Region(region) => Some(Plan {
+            locale: None,
+            region: Some(region),
+            style,
+            presets: system_fonts::presets_for_region(region),
+            trimmed: Vec::new(),
+        }),
+        FontSelection::Presets(presets) => Some(Plan {
+            locale: None,
+            region: None,
+            style,
+            presets,
+            trimmed: Vec::new(),
+        }),
+    }
+}
+
+fn plan_auto(
+    style: FontStyle,
+    env: &EnvOverrides,
+    provider: &dyn FontProvider,
+    logs: bool,
+) -> Option<Plan> {
+    if env.disabled {
+        if logs {
+            log::info!("{} is set; skipping system font setup.", ENV_DISABLE);
+        }
+        return None;
+    }
+
+    let style = match env.style {
+        Some(forced) => {
+            if logs {
+                log::info!(
+                    "{} override active: style {:?} -> {:?}",
+                    ENV_STYLE,
+                    style,
+                    forced
+                );
+            }
+            forced
+        }
+        None => style,
+    };
+
+    let (locale, region) = if let Some(region) = env.region {
+        if logs {
+            log::info!("{} override active: region {:?}", ENV_REGION, region);
+        }
+        (None, region)
+    } else if let Some(locale) = env.locale.clone() {
+        if logs {
+            log::info!("{} override active: locale {:?}", ENV_LOCALE, locale);
+        }
+        let region = region_for_locale(&locale);
+        (Some(locale), region)
+    } else {
+        let locale = provider.locale();
+        let region = match locale.as_deref() {
+            Some(loc) if !loc.trim().is_empty() => region_for_locale(loc),
+            _ => region_for_locale("en-US"),
+        };
+        (locale, region)
+    };
+
+    Some(Plan {
+        locale,
+        region: Some(region),
+        style,
+        presets: system_fonts::presets_for_region(region),
+        trimmed: Vec::new(),
+    })
+}
+
+/// Fails if the region's own preset has no installed candidate.
+///
+/// Fallback presets (e.g. Latin for a Japanese region) do not count. Selections without a known region
+/// always pass.
+pub(crate) fn check_strict(plan: &Plan, options: &FontOptions) -> Result<(), FontError> {
+    let Some(region) = plan.region else {
+        return Ok(());
+    };
+    if region == FontRegion::Unknown {
+        return Ok(());
+    }
+
+    let Some(primary) = plan.presets.first() else {
+        return Ok(());
+    };
+    if find(std::slice::from_ref(primary), plan.style, options).is_empty() {
+        options.warn(format!("No {:?} candidates found on this system.", region));
+        return Err(FontError::RegionUnsupportedOnSystem(region));
+    }
+    Ok(())
+}
@@ -0,0 +1,110 @@
+//! Picking presets from the scripts used in a piece of text.
+
+use crate::provider::same_preset;
+use crate::resolve;
+use crate::{FontPreset, FontRegion, FontSelection, FontStyle, SystemProvider};
+
+/// A writing system that needs fonts beyond `egui`'s defaults.
+///
+/// Latin, digits and common punctuation are covered by `egui` and have no variant here.
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Script {
+    Hangul,
+    Hiragana,
+    Katakana,
+    Han,
+    Cyrillic,
+}
+
+impl Script {
+    /// Classifies a single character.
+    pub fn of(c: char) -> Option<Self> {
+        let script = match c as u32 {
+            0x1100..=0x11FF | 0x3130..=0x318F | 0xA960..=0xA97F | 0xAC00..=0xD7FF => Self::Hangul,
+            0x3040..=0x309F | 0x1B000..=0x1B16F => Self::Hiragana,
+            0x30A0..=0x30FF | 0x31F0..=0x31FF | 0xFF66..=0xFF9F => Self::Katakana,
+            0x2E80..=0x2FDF
+            | 0x3400..=0x4DBF
+            | 0x4E00..=0x9FFF
+            | 0xF900..=0xFAFF
+            | 0x20000..=0x3134F => Self::Han,
+            0x0400..=0x052F | 0x1C80..=0x1C8F | 0x2DE0..=0x2DFF | 0xA640..=0xA69F => Self::Cyrillic,
+            _ => return None,
+        };
+        Some(script)
+    }
+}
+
+/// Returns the scripts used in `text`, in order of first appearance.
+///
+/// # Examples
+///
+/// ```
+/// # use egui_system_fonts::{detect_scripts, Script};
+/// assert_eq!(detect_scripts("Hello, 世界! こんにちは"), [Script::Han, Script::Hiragana]);
+/// assert!(detect_scripts("plain ASCII 123 !?").is_empty());
+/// ```
+pub fn detect_scripts(text: &str) -> Vec<Script> {
+    let mut scripts = Vec::new();
+    for script in text.chars().filter_map(Script::of) {
+        if !scripts.contains(&script) {
+            scripts.push(script);
+        }
+    }
+    scripts
+}
+
+/// Result of [`extend_for_text`](crate::extend_for_text).
+#[derive(Clone, Debug, Default)]
+pub struct TextReport {
+    /// Scripts detected in the text, in order of first appearance.
+    pub scripts: Vec<Script>,
+    /// Newly added font family names, in priority order.
+    pub installed: Vec<String>,
+}
+
+/// Maps detected scripts to the presets needed to render them.
+///
+/// Han is ambiguous: kana in the same text selects Japanese, Hangul selects Korean, and otherwise the
+/// system locale decides (Simplified Chinese when it is not CJK).
+pub(crate) fn presets_for_scripts(scripts: &[Script], style: FontStyle) -> Vec<FontPreset> {
+    let has = |s: Script| scripts.contains(&s);
+
+    let mut presets: Vec<FontPreset> = Vec::new();
+    let mut push = |p: FontPreset| {
+        if !presets.iter().any(|q| same_preset(q, &p)) {
+            presets.push(p);
+        }
+    };
+
+    for script in scripts {
+        match script {
+            Script::Hangul => push(FontPreset::Korean),
+            Script::Hiragana | Script::Katakana => push(FontPreset::Japanese),
+            Script::Cyrillic => push(FontPreset::Cyrillic),
+            Script::Han => {
+                if has(Script::Hiragana) || has(Script::Katakana) {
+                    push(FontPreset::Japanese);
+                } else if has(Script::Hangul) {
+                    push(FontPreset::Korean);
+                } else {
+                    push(han_preset_for_locale(style));
+                }
+            }
+        }
+    }
+
+    presets
+}
+
+fn han_preset_for_locale(style: FontStyle) -> FontPreset {
+    let region = resolve::plan(FontSelection::Auto, style, None, &SystemProvider, true)
+        .and_then(|plan| plan.region);
+    match region {
+        Some(FontRegion::Japanese) => FontPreset::Japanese,
+        Some(FontRegion::Korean) => FontPreset::Korean,
+        Some(FontRegion::TraditionalChinese) => FontPreset::TraditionalChinese,
+        _ => FontPreset::SimplifiedChinese,
+    }
+}
@@ -0,0 +1,142 @@
+//! Batching several set/extend steps into a single font application.
+
+use crate::{
+    install_fonts, plan_report, prepare_fonts, resolve, FontError, FontOptions, FontReport,
+    FontSelection, FontStyle, Handoff, Placement,
+};
+use egui::FontDefinitions;
+
+/// Font definitions being built up by several set/extend steps, applied to the context once.
+///
+/// Every `extend_*` function clones the definitions and applies them to the context, which makes `egui`
+/// rebuild its font atlas. A session runs the same steps on its own definitions and applies the result
+/// with a single [`commit`](Self::commit).
+///
+/// # Examples
+///
+/// ```no_run
+/// # use egui_system_fonts::{snapshot, FontError, FontOptions, FontPreset, FontRegion, FontSession, FontStyle};
+/// # fn demo(ctx: &egui::Context) -> Result<(), FontError> {
+/// let options = FontOptions::new();
+/// let mut session = FontSession::begin(snapshot(ctx).definitions().clone());
+/// session
+///     .extend(FontRegion::Japanese, FontStyle::Sans, &options)?
+///     .extend(vec![FontPreset::Custom(vec!["Noto Color Emoji".to_owned()])], FontStyle::Sans, &options)?;
+/// println!("installed: {:?}", session.commit(ctx).installed);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct FontSession {
+    defs: FontDefinitions,
+    report: FontReport,
+    changed: bool,
+    replaced: bool,
+    logs: bool,
+    force: bool,
+}
+
+impl FontSession {
+    /// Starts a session from `defs`, e.g. `FontDefinitions::default()` or a [`snapshot`](crate::snapshot()).
+    pub fn begin(defs: FontDefinitions) -> Self {
+        Self {
+            defs,
+            report: FontReport::default(),
+            changed: false,
+            replaced: false,
+            logs: false,
+            force: false,
+        }
+    }
+
+    /// Replaces the session's definitions with `egui`'s defaults and the resolved fonts, like
+    /// [`set_with_options`](crate::set_with_options).
+    ///
+    /// If no fonts are found the definitions are left unchanged.
+    pub fn set(
+        &mut self,
+        selection: impl Into<FontSelection>,
+        style: FontStyle,
+        options: &FontOptions,
+    ) -> Result<&mut Self, FontError> {
+        if self.step(true, selection.into(), style, options)? {
+            self.replaced = true;
+        }
+        Ok(self)
+    }
+
+    /// Appends the resolved fonts as fallbacks, like [`extend_with_options`](crate::extend_with_options).
+    pub fn extend(
+        &mut self,
+        selection: impl Into<FontSelection>,
+        style: FontStyle,
+        options: &FontOptions,
+    ) -> Result<&mut Self, FontError> {
+        self.step(false, selection.into(), style, options)?;
+        Ok(self)
+    }
+
+    /// Resolves `selection` and sets or extends the session's definitions with the fonts found. Returns
+    /// whether they changed; on failure they are left as they were.
+    fn step(
+        &mut self,
+        replace: bool,
+        selection: FontSelection,
+        style: FontStyle,
+        options: &FontOptions,
+    ) -> Result<bool, FontError> {
+        self.logs |= options.logs();
+        self.force |= options.force;
+        let Some(resolution) = resolve::resolve(selection, style, options) else {
+            return Ok(false);
+        };
+        if options.strict {
+            resolve::check_strict(&resolution.plan, options)?;
+        }
+
+        let plan = Some(&resolution.plan);
+        let mut report = plan_report(plan);
+        let placement = Placement::at(options.order.anchor());
+        let changed = prepare_fonts(
+            &mut self.defs,
+            replace,
+            resolution.fonts,
+            options,
+            plan,
+            placement,
+            &mut report,
+        )?;
+        self.changed |= changed;
+        self.report.merge(report);
+        Ok(changed)
+    }
+
+    /// The definitions built so far.
+    pub fn definitions(&self) -> &FontDefinitions {
+        &self.defs
+    }
+
+    /// Applies the definitions to `ctx` (once, and only if a step changed them) and returns the combined
+    /// report of all steps.
+    ///
+    /// Like a single call, definitions already in effect are not applied again unless a step's options set
+    /// [`force`](FontOptions::force). The result is logged unless every step's options were
+    /// [`Verbosity::Silent`](crate::Verbosity::Silent).
+    pub fn commit(mut self, ctx: &egui::Context) -> FontReport {
+        let mut report = self.report;
+        if self.changed {
+            let applied = install_fonts(
+                ctx,
+                &mut self.defs,
+                Handoff::Move,
+                self.replaced,
+                self.force,
+                &mut report,
+            );
+            if applied && self.logs {
+                log::info!("Set fonts (family names): {:?}", report.installed);
+            }
+        }
+        report
+    }
+}
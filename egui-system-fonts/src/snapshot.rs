@@ -0,0 +1,169 @@
+//! Capturing and reapplying font definitions.
+
+use crate::names::family_from_key;
+use crate::{is_system_font_key, FontKey};
+use egui::{FontDefinitions, FontFamily};
+use std::sync::Arc;
+
+/// A captured set of [`FontDefinitions`], for undoing font changes.
+///
+/// Cloning is cheap: the definitions are shared.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FontSnapshot(Arc<FontDefinitions>);
+
+impl FontSnapshot {
+    /// The captured definitions.
+    pub fn definitions(&self) -> &FontDefinitions {
+        &self.0
+    }
+}
+
+fn applied_id() -> egui::Id {
+    egui::Id::new("egui-system-fonts/applied")
+}
+
+fn original_id() -> egui::Id {
+    egui::Id::new("egui-system-fonts/original")
+}
+
+fn installed_id() -> egui::Id {
+    egui::Id::new("egui-system-fonts/installed")
+}
+
+/// A font installed by this crate, as listed by [`installed_fonts`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct InstalledFont {
+    /// The `font_data` key.
+    pub key: FontKey,
+    /// Font family name.
+    pub family: String,
+    /// `egui`'s input time (`InputState::time`, in seconds) when the font was installed.
+    pub installed_at: f64,
+}
+
+/// Applies `defs` to `ctx` and remembers them for [`snapshot`] and [`installed_fonts`].
+pub(crate) fn install(ctx: &egui::Context, defs: FontDefinitions) {
+    record_installed(ctx, &defs);
+    ctx.data_mut(|d| d.insert_temp(applied_id(), Arc::new(defs.clone())));
+    ctx.set_fonts(defs);
+}
+
+/// Whether `defs` equal the definitions currently in effect (see [`snapshot`]): same keys, order, data
+/// and tweaks. Applying them again would only make `egui` rebuild its font atlas.
+pub(crate) fn is_applied(ctx: &egui::Context, defs: &FontDefinitions) -> bool {
+    snapshot(ctx).definitions() == defs
+}
+
+/// Updates the list of installed fonts to the crate's fonts in `defs`.
+///
+/// Fonts that stay installed keep their installation time.
+fn record_installed(ctx: &egui::Context, defs: &FontDefinitions) {
+    let now = ctx.input(|i| i.time);
+    let proportional = defs.families.get(&FontFamily::Proportional);
+    let mut keys: Vec<&String> = Vec::new();
+    for key in proportional
+        .into_iter()
+        .flatten()
+        .chain(defs.families.values().flatten())
+        .chain(defs.font_data.keys())
+    {
+        if is_system_font_key(key) && defs.font_data.contains_key(key) && !keys.contains(&key) {
+            keys.push(key);
+        }
+    }
+
+    ctx.data_mut(|d| {
+        let previous = d
+            .get_temp::<Arc<Vec<InstalledFont>>>(installed_id())
+            .unwrap_or_default();
+        let fonts: Vec<InstalledFont> = keys
+            .into_iter()
+            .map(|key| match previous.iter().find(|f| &f.key == key) {
+                Some(font) => font.clone(),
+                None => InstalledFont {
+                    key: FontKey::from(key.as_str()),
+                    family: family_from_key(key).unwrap_or(key).to_owned(),
+                    installed_at: now,
+                },
+            })
+            .collect();
+        d.insert_temp(installed_id(), Arc::new(fonts));
+    });
+}
+
+/// Lists the fonts this crate has installed on `ctx`, in Proportional priority order.
+///
+/// Returns `None` if the crate never applied fonts to `ctx`. The list follows every change made through
+/// this crate (`set_*`, `extend_*`, [`remove_family`](crate::remove_family), [`restore`], ...), so any part
+/// of an app can check what was added without threading state around.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use egui_system_fonts::{installed_fonts};
+/// # fn demo(ctx: &egui::Context) {
+/// for font in installed_fonts(ctx).unwrap_or_default() {
+///     println!("{}: {}", font.family, font.key);
+/// }
+/// # }
+/// ```
+pub fn installed_fonts(ctx: &egui::Context) -> Option<Vec<InstalledFont>> {
+    ctx.data(|d| d.get_temp::<Arc<Vec<InstalledFont>>>(installed_id()))
+        .map(Arc::unwrap_or_clone)
+}
+
+/// Like [`install`], for definitions that replace the current ones rather than extend them.
+///
+/// The definitions in effect before the first replacement are kept for [`take_original`].
+pub(crate) fn install_replacement(ctx: &egui::Context, defs: FontDefinitions) {
+    let current = snapshot(ctx).0;
+    ctx.data_mut(|d| {
+        if d.get_temp::<Arc<FontDefinitions>>(original_id()).is_none() {
+            d.insert_temp(original_id(), current);
+        }
+    });
+    install(ctx, defs);
+}
+
+/// Removes and returns the definitions kept by [`install_replacement`], if any.
+pub(crate) fn take_original(ctx: &egui::Context) -> Option<FontDefinitions> {
+    let original = ctx.data_mut(|d| d.remove_temp::<Arc<FontDefinitions>>(original_id()))?;
+    Some(Arc::unwrap_or_clone(original))
+}
+
+/// Captures the font definitions currently in effect for `ctx`.
+///
+/// These are the definitions most recently applied by this crate (including ones that take effect on the
+/// next pass), or else the context's active fonts. Before the first pass and without any fonts applied by
+/// this crate, `egui`'s defaults are returned. Fonts passed directly to `egui::Context::set_fonts` are not
+/// tracked, so they are only captured if this crate never applied fonts to `ctx`.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use egui_system_fonts::{restore, set_with_region, snapshot, FontRegion, FontStyle};
+/// # fn demo(ctx: &egui::Context) {
+/// // Preview Japanese fonts, then undo.
+/// let before = snapshot(ctx);
+/// set_with_region(ctx, FontRegion::Japanese, FontStyle::Serif);
+/// restore(ctx, &before);
+/// # }
+/// ```
+pub fn snapshot(ctx: &egui::Context) -> FontSnapshot {
+    if let Some(applied) = ctx.data(|d| d.get_temp::<Arc<FontDefinitions>>(applied_id())) {
+        return FontSnapshot(applied);
+    }
+    if ctx.cumulative_pass_nr() > 0 {
+        return FontSnapshot(Arc::new(ctx.fonts(|f| f.definitions().clone())));
+    }
+    FontSnapshot(Arc::new(FontDefinitions::default()))
+}
+
+/// Reapplies the definitions captured by [`snapshot`].
+///
+/// Takes effect on the next pass, like `egui::Context::set_fonts`.
+pub fn restore(ctx: &egui::Context, snapshot: &FontSnapshot) {
+    record_installed(ctx, &snapshot.0);
+    ctx.data_mut(|d| d.insert_temp(applied_id(), Arc::clone(&snapshot.0)));
+    ctx.set_fonts(FontDefinitions::clone(&snapshot.0));
+}
@@ -0,0 +1,112 @@
+//! Trimming fonts to the characters an app displays (`subset` feature).
+
+use crate::{FontOptions, FontRegion};
+use allsorts::binary::read::ReadScope;
+use allsorts::font_data::FontData;
+use allsorts::subset::{subset, CmapTarget, SubsetProfile};
+use std::collections::HashSet;
+
+/// Characters every subset keeps: printable ASCII, Latin-1 and common punctuation.
+const BASE_RANGES: &[(char, char)] = &[
+    (' ', '~'),
+    ('\u{a0}', '\u{ff}'),
+    ('\u{2010}', '\u{2027}'),
+    ('\u{2030}', '\u{205e}'),
+];
+
+/// Returns the characters commonly needed to display text of `region`, for
+/// [`FontOptions::subset`](crate::FontOptions::subset) (requires the `subset` feature).
+///
+/// Covers whole Unicode blocks rather than a frequency list: all of Hangul for Korean, kana and the
+/// unified ideographs for Japanese and Chinese, the Cyrillic block for Cyrillic, and Latin Extended-A
+/// otherwise, each with printable ASCII, Latin-1 and common punctuation. CJK subsets therefore still hold
+/// some twenty thousand ideographs but drop the rarely used extension blocks; for the smallest fonts,
+/// pass the characters your app actually displays instead.
+///
+/// # Examples
+///
+/// ```
+/// # use egui_system_fonts::{region_chars, FontRegion};
+/// let chars = region_chars(FontRegion::Cyrillic);
+/// assert!(chars.contains(&'Ж'));
+/// ```
+pub fn region_chars(region: FontRegion) -> Vec<char> {
+    let script: &[(char, char)] = match region {
+        FontRegion::Korean => &[
+            ('\u{3000}', '\u{303f}'),
+            ('\u{3131}', '\u{318e}'),
+            ('\u{ac00}', '\u{d7a3}'),
+        ],
+        FontRegion::Japanese => &[
+            ('\u{3000}', '\u{30ff}'),
+            ('\u{4e00}', '\u{9fff}'),
+            ('\u{ff00}', '\u{ffef}'),
+        ],
+        FontRegion::SimplifiedChinese | FontRegion::TraditionalChinese => &[
+            ('\u{3000}', '\u{303f}'),
+            ('\u{4e00}', '\u{9fff}'),
+            ('\u{ff00}', '\u{ffef}'),
+        ],
+        FontRegion::Cyrillic => &[('\u{400}', '\u{4ff}')],
+        _ => &[('\u{100}', '\u{17f}')],
+    };
+    BASE_RANGES
+        .iter()
+        .chain(script)
+        .flat_map(|&(start, end)| start..=end)
+        .collect()
+}
+
+/// Returns `bytes` reduced to the glyphs needed for `chars`, or the original bytes if subsetting fails.
+///
+/// A failure is reported as a warning through `options`.
+pub(crate) fn subset_or_full(
+    bytes: Vec<u8>,
+    chars: &HashSet<char>,
+    family: &str,
+    options: &FontOptions,
+) -> Vec<u8> {
+    match subset_font(&bytes, chars) {
+        Ok(trimmed) => {
+            log::debug!(
+                "Subset {:?}: {} -> {} bytes",
+                family,
+                bytes.len(),
+                trimmed.len()
+            );
+            trimmed
+        }
+        Err(e) => {
+            options.warn(format!(
+                "Failed to subset {:?}, using the full font: {}",
+                family, e
+            ));
+            bytes
+        }
+    }
+}
+
+/// Builds a font containing `.notdef` plus the glyphs `chars` map to in the first face of `bytes`.
+fn subset_font(bytes: &[u8], chars: &HashSet<char>) -> Result<Vec<u8>, String> {
+    let face = ttf_parser::Face::parse(bytes, 0).map_err(|e| e.to_string())?;
+    let mut glyph_ids: Vec<u16> = chars
+        .iter()
+        .filter_map(|&c| face.glyph_index(c))
+        .map(|g| g.0)
+        .chain([0])
+        .collect();
+    glyph_ids.sort_unstable();
+    glyph_ids.dedup();
+
+    let data = ReadScope::new(bytes)
+        .read::<FontData<'_>>()
+        .map_err(|e| e.to_string())?;
+    let provider = data.table_provider(0).map_err(|e| e.to_string())?;
+    subset(
+        &provider,
+        &glyph_ids,
+        &SubsetProfile::Minimal,
+        CmapTarget::Unicode,
+    )
+    .map_err(|e| e.to_string())
+}
@@ -0,0 +1,134 @@
+//! Setting up fonts on a background thread.
+
+use crate::{
+    global_defaults, set_with_options, FontError, FontOptions, FontReport, FontSelection, FontStyle,
+};
+use std::thread::JoinHandle;
+
+/// Like [`set_auto`](crate::set_auto), but finds and reads the fonts on a background thread.
+///
+/// The first frames render with `egui`'s own fonts; the system fonts are applied through the context as
+/// soon as they are ready, followed by a repaint. Meant for startup code such as the `eframe` app creator,
+/// where [`set_auto`](crate::set_auto) would delay the first frame. The options set with
+/// [`set_global_defaults`](crate::set_global_defaults) apply.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use egui_system_fonts::{set_auto_async, FontStyle};
+/// # fn demo(ctx: &egui::Context) {
+/// // Runs in the background; drop the task to not wait for it.
+/// let _ = set_auto_async(ctx, FontStyle::Sans);
+/// # }
+/// ```
+pub fn set_auto_async(ctx: &egui::Context, style: FontStyle) -> FontTask {
+    set_async_with_options(ctx, FontSelection::Auto, style, &global_defaults())
+}
+
+/// Like [`set_with_options`], but runs on a background thread; see [`set_auto_async`].
+///
+/// To abort the setup, pass a [`CancelToken`](crate::CancelToken) with
+/// [`FontOptions::cancel_token`] and cancel it; the task then finishes with
+/// [`FontError::Cancelled`] and leaves the fonts of `ctx` alone.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use egui_system_fonts::{set_async_with_options, FontOptions, FontRegion, FontStyle};
+/// # fn demo(ctx: &egui::Context) {
+/// let mut task = set_async_with_options(ctx, FontRegion::Korean, FontStyle::Sans, &FontOptions::new());
+/// // Later, once per frame:
+/// if let Some(result) = task.poll() {
+///     println!("fonts ready: {:?}", result.map(|report| report.installed));
+/// }
+/// # }
+/// ```
+pub fn set_async_with_options(
+    ctx: &egui::Context,
+    selection: impl Into<FontSelection>,
+    style: FontStyle,
+    options: &FontOptions,
+) -> FontTask {
+    let selection = selection.into();
+    let spawned = {
+        let (ctx, selection, options) = (ctx.clone(), selection.clone(), options.clone());
+        #[cfg(feature = "testing")]
+        let injected = crate::testing::injected();
+        std::thread::Builder::new()
+            .name("egui-system-fonts-setup".to_owned())
+            .spawn(move || {
+                #[cfg(feature = "testing")]
+                crate::testing::inherit(injected);
+                setup(&ctx, selection, style, &options)
+            })
+    };
+    match spawned {
+        Ok(handle) => FontTask {
+            thread: Some(handle),
+            result: None,
+        },
+        Err(e) => {
+            log::warn!(
+                "Failed to spawn font setup thread, setting up fonts now: {}",
+                e
+            );
+            FontTask {
+                thread: None,
+                result: Some(setup(ctx, selection, style, options)),
+            }
+        }
+    }
+}
+
+fn setup(
+    ctx: &egui::Context,
+    selection: FontSelection,
+    style: FontStyle,
+    options: &FontOptions,
+) -> Result<FontReport, FontError> {
+    let result = set_with_options(ctx, selection, style, options);
+    ctx.request_repaint();
+    result
+}
+
+/// A font setup running on a background thread; see [`set_auto_async`].
+///
+/// Dropping the task lets the setup run to completion in the background; cancel it with a
+/// [`CancelToken`](crate::CancelToken) instead.
+#[derive(Debug)]
+pub struct FontTask {
+    thread: Option<JoinHandle<Result<FontReport, FontError>>>,
+    result: Option<Result<FontReport, FontError>>,
+}
+
+impl FontTask {
+    /// Whether the fonts have been applied (or the setup failed).
+    pub fn is_finished(&self) -> bool {
+        self.thread.as_ref().is_none_or(JoinHandle::is_finished)
+    }
+
+    /// Returns the outcome once the setup has finished, without blocking.
+    pub fn poll(&mut self) -> Option<Result<FontReport, FontError>> {
+        if !self.is_finished() {
+            return None;
+        }
+        self.join();
+        self.result.clone()
+    }
+
+    /// Blocks until the setup has finished and returns its outcome.
+    pub fn wait(mut self) -> Result<FontReport, FontError> {
+        self.join();
+        self.result.take().expect("joined task has a result")
+    }
+
+    /// Takes the outcome from the thread, if it is still held.
+    fn join(&mut self) {
+        if let Some(thread) = self.thread.take() {
+            let result = thread
+                .join()
+                .unwrap_or_else(|panic| std::panic::resume_unwind(panic));
+            self.result = Some(result);
+        }
+    }
+}
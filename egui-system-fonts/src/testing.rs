@@ -0,0 +1,81 @@
+//! Injecting a fixed font set in place of the installed fonts, for tests.
+
+use crate::candidates::target_families;
+use crate::names::normalize_family_name;
+use crate::{FontPreset, FontStyle, FoundFont};
+use std::cell::RefCell;
+
+thread_local! {
+    static TEST_FONTS: RefCell<Option<Vec<FoundFont>>> = const { RefCell::new(None) };
+}
+
+/// Makes every lookup on the current thread return `fonts` instead of the installed fonts.
+///
+/// Applies to the `set_*`/`extend_*` functions (including [`set_auto`](crate::set_auto) and
+/// [`set_with_region`](crate::set_with_region)) whatever the region, presets or style, so code that sets up
+/// fonts can be tested in CI without depending on the machine's fonts. The override is per thread, so
+/// tests running in parallel don't see each other's fonts; the background setups of
+/// [`set_auto_async`](crate::set_auto_async) and [`set_async_with_options`](crate::set_async_with_options)
+/// started on this thread use its fonts too. Undo it with [`clear_test_fonts`].
+///
+/// The injected set is used as the resolved font list as is, so options that choose between installed
+/// faces ([`FontOptions::display_faces`](crate::FontOptions::display_faces),
+/// [`FontOptions::weight`](crate::FontOptions::weight),
+/// [`FontOptions::max_fonts_per_preset`](crate::FontOptions::max_fonts_per_preset)) leave it alone. Lookups
+/// of particular families, such as [`set_pan_cjk`](crate::set_pan_cjk) probing for a pan-CJK font or the
+/// [`strict`](crate::FontOptions::strict) check for the region's own fonts, only see the injected fonts of
+/// those families.
+///
+/// Only available with the `testing` feature; enable it in `[dev-dependencies]`.
+///
+/// # Examples
+///
+/// ```
+/// # use egui_system_fonts::{set_auto, set_test_fonts, FontStyle, FoundFont, FoundFontSource};
+/// let hack = egui::FontDefinitions::default().font_data["Hack"].font.to_vec();
+/// set_test_fonts(vec![FoundFont {
+///     family: "Test Sans".to_owned(),
+///     key: "system:Test Sans:0".to_owned(),
+///     source: FoundFontSource::Bytes(hack.into()),
+/// }]);
+///
+/// let ctx = egui::Context::default();
+/// assert_eq!(set_auto(&ctx, FontStyle::Sans), ["Test Sans"]);
+/// ```
+pub fn set_test_fonts(fonts: Vec<FoundFont>) {
+    TEST_FONTS.with(|cell| *cell.borrow_mut() = Some(fonts));
+}
+
+/// Removes the fonts injected with [`set_test_fonts`] on the current thread.
+pub fn clear_test_fonts() {
+    TEST_FONTS.with(|cell| *cell.borrow_mut() = None);
+}
+
+/// Injects `fonts` (as returned by [`injected`] on another thread) on the current thread.
+pub(crate) fn inherit(fonts: Option<Vec<FoundFont>>) {
+    TEST_FONTS.with(|cell| *cell.borrow_mut() = fonts);
+}
+
+/// The fonts injected on the current thread, if any.
+pub(crate) fn injected() -> Option<Vec<FoundFont>> {
+    TEST_FONTS.with(|cell| cell.borrow().clone())
+}
+
+/// The injected fonts that are candidates of `presets`, in preset order, if fonts are injected.
+///
+/// Used for lookups of particular families (display faces, pan-CJK fonts, the strict check), which must
+/// not see every injected font.
+pub(crate) fn injected_for(presets: &[FontPreset], style: FontStyle) -> Option<Vec<FoundFont>> {
+    let fonts = injected()?;
+    let found = target_families(presets, style)
+        .iter()
+        .map(|family| normalize_family_name(family))
+        .flat_map(|family| {
+            fonts
+                .iter()
+                .filter(move |f| normalize_family_name(&f.family) == family)
+        })
+        .cloned()
+        .collect();
+    Some(found)
+}
@@ -0,0 +1,1772 @@
+//! Behavior tests, run against fixture fonts instead of the machine's fonts.
+
+use super::*;
+use egui::FontTweak;
+use std::path::PathBuf;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+/// Bytes of a font bundled with `egui` (`Hack` or `Ubuntu-Light`), standing in for a system font.
+fn bundled(name: &str) -> Vec<u8> {
+    FontDefinitions::default().font_data[name].font.to_vec()
+}
+
+/// A provider serving a copy of Hack under each `(preset, family)`, keyed `system:<family>:<index>`.
+fn fonts(fonts: &[(FontPreset, &str)]) -> StaticProvider {
+    let hack = bundled("Hack");
+    fonts
+        .iter()
+        .fold(StaticProvider::new(), |provider, (preset, family)| {
+            provider.font(preset.clone(), *family, hack.clone())
+        })
+}
+
+/// A found font holding Hack's bytes.
+fn found(family: &str, n: usize) -> FoundFont {
+    FoundFont {
+        family: family.to_owned(),
+        key: format!("system:{family}:{n}"),
+        source: FoundFontSource::Bytes(bundled("Hack").into()),
+    }
+}
+
+/// The `font_data` key [`found`] (or [`fonts`]) fonts are installed under.
+fn key(family: &str, n: usize) -> String {
+    format!("egui-system-fonts/system:{family}:{n}")
+}
+
+/// The system font keys in `family` of the definitions in effect on `ctx`.
+fn system_keys(ctx: &egui::Context, family: FontFamily) -> Vec<String> {
+    snapshot(ctx).definitions().families[&family]
+        .iter()
+        .filter(|key| is_system_font_key(key))
+        .cloned()
+        .collect()
+}
+
+/// An empty directory for `test`'s files.
+fn temp_dir(test: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("egui-system-fonts-{test}-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// Serializes the tests that change [`set_global_defaults`] or call functions that read it.
+fn global_defaults_lock() -> MutexGuard<'static, ()> {
+    static LOCK: Mutex<()> = Mutex::new(());
+    LOCK.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+#[test]
+fn byte_provider_fetches_fonts_by_family() {
+    use std::future::Future;
+    use std::pin::Pin;
+
+    struct Assets;
+
+    impl FontByteProvider for Assets {
+        fn fetch(&self, key: &str) -> Pin<Box<dyn Future<Output = Option<Vec<u8>>>>> {
+            let found = (key == "Noto Sans").then(|| bundled("Hack"));
+            Box::pin(async move { found })
+        }
+    }
+
+    let ctx = egui::Context::default();
+    let options = FontOptions::new();
+    let setup = set_from_byte_provider(&ctx, FontRegion::Latin, FontStyle::Sans, &Assets, &options);
+    let mut setup = std::pin::pin!(setup);
+    let mut cx = std::task::Context::from_waker(std::task::Waker::noop());
+    let std::task::Poll::Ready(report) = setup.as_mut().poll(&mut cx) else {
+        panic!("the fetch is ready at once");
+    };
+    assert_eq!(report.unwrap().installed, ["Noto Sans"]);
+}
+
+#[test]
+fn preset_candidates_are_listed_once_for_every_preset() {
+    let japanese = preset_candidates(&FontPreset::Japanese, FontStyle::Serif);
+    assert_eq!(japanese[0], "Noto Serif JP");
+    assert!(japanese.contains(&"Noto Sans JP"));
+
+    for preset in PRESETS {
+        for &style in STYLES {
+            let families = preset_candidates(preset, style);
+            assert!(!families.is_empty(), "{preset:?} {style:?}");
+            let mut unique = families.clone();
+            unique.sort_unstable();
+            unique.dedup();
+            assert_eq!(unique.len(), families.len(), "{preset:?} {style:?}");
+        }
+    }
+}
+
+#[test]
+fn fallback_chain_names_typographic_families() {
+    let defs = FontDefinitions::default();
+    let chain = fallback_chain(&defs);
+
+    let monospace = &chain.families[&FontFamily::Monospace];
+    assert_eq!(monospace[0].key, "Hack");
+    assert_eq!(monospace[0].name.as_deref(), Some("Hack"));
+    assert_eq!(monospace[0].bytes, defs.font_data["Hack"].font.len());
+    // "Ubuntu Light" is a weight of the typographic family "Ubuntu".
+    let proportional = &chain.families[&FontFamily::Proportional];
+    assert_eq!(proportional[0].name.as_deref(), Some("Ubuntu"));
+    assert!(chain.to_string().contains("  1. Hack: Hack ("));
+}
+
+#[test]
+fn describe_current_lists_installed_fonts_with_sizes() {
+    let size = bundled("Hack").len();
+    let options = FontOptions::new().provider(fonts(&[(FontPreset::Latin, "Hack")]));
+    let ctx = egui::Context::default();
+    set_with_options(&ctx, FontRegion::Latin, FontStyle::Sans, &options).unwrap();
+
+    let text = describe_current(&ctx);
+    assert!(text.starts_with("Proportional:\n  1. Hack <memory>"));
+    assert!(text.contains(&format!("({size} bytes)")));
+}
+
+#[test]
+fn config_families_and_monospace_are_applied() {
+    let provider = fonts(&[(FontPreset::Custom(vec!["Hack".to_owned()]), "Hack")]);
+    let config = FontConfig {
+        region: Some(FontRegion::Latin),
+        mode: ConfigMode::Extend,
+        families: vec!["Hack".to_owned()],
+        monospace: Some(false),
+        ..Default::default()
+    };
+
+    let ctx = egui::Context::default();
+    let report = apply_config_with_options(&ctx, &config, &FontOptions::new().provider(provider));
+    assert_eq!(report.installed, ["Hack"]);
+    assert_eq!(
+        system_keys(&ctx, FontFamily::Proportional),
+        [key("Hack", 0)]
+    );
+    assert!(system_keys(&ctx, FontFamily::Monospace).is_empty());
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn config_fields_are_optional_and_round_trip() {
+    let config: FontConfig =
+        serde_json::from_str(r#"{ "region": "korean", "mode": "extend" }"#).unwrap();
+    assert_eq!(config.region, Some(FontRegion::Korean));
+    assert_eq!(config.style, FontStyle::Sans);
+    assert_eq!(config.mode, ConfigMode::Extend);
+    assert_eq!(config.monospace, None);
+
+    let json = serde_json::to_string(&config).unwrap();
+    assert_eq!(serde_json::from_str::<FontConfig>(&json).unwrap(), config);
+}
+
+#[test]
+fn missing_glyphs_rereads_a_changed_file() {
+    let dir = temp_dir("glyphs");
+    let path = dir.join("font.ttf");
+    std::fs::write(&path, bundled("Hack")).unwrap();
+    let font = FoundFont {
+        family: "Hack".to_owned(),
+        key: "system:Hack:0".to_owned(),
+        source: FoundFontSource::Path(path.clone()),
+    };
+    assert_eq!(missing_glyphs(&font, "fn main() {} // ─ 한"), ['한']);
+
+    std::fs::write(&path, bundled("Ubuntu-Light")).unwrap();
+    assert_eq!(missing_glyphs(&font, "fn main() {} // ─ 한"), ['─', '한']);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn diff_shows_fonts_appended_behind_egui_fonts() {
+    let before = FontDefinitions::default();
+    let options = FontOptions::new().provider(fonts(&[(FontPreset::Latin, "Hack")]));
+    let mut after = before.clone();
+    let ctx = egui::Context::default();
+    extend_with_options(
+        &ctx,
+        &mut after,
+        FontRegion::Latin,
+        FontStyle::Sans,
+        &options,
+    )
+    .unwrap();
+
+    let diff = diff_definitions(&before, &after);
+    assert_eq!(diff.added, [key("Hack", 0)]);
+    assert!(diff.removed.is_empty() && diff.replaced.is_empty());
+    let proportional = &diff.families[&FontFamily::Proportional];
+    assert_eq!(proportional.after.last(), Some(&key("Hack", 0)));
+    assert_eq!(
+        proportional.after[..proportional.before.len()],
+        proportional.before[..]
+    );
+    assert!(diff_definitions(&after, &after).is_empty());
+}
+
+#[test]
+fn found_for_system_locale_uses_the_global_provider() {
+    let _lock = global_defaults_lock();
+    let provider = fonts(&[(FontPreset::Japanese, "Noto Sans JP")]).with_locale("ja-JP");
+    set_global_defaults(FontOptions::new().provider(provider));
+
+    let (locale, region, found) = discovery::found_for_system_locale(FontStyle::Sans);
+    set_global_defaults(FontOptions::new());
+    assert_eq!(locale.as_deref(), Some("ja-JP"));
+    assert_eq!(region, Some(FontRegion::Japanese));
+    assert_eq!(found[0].family, "Noto Sans JP");
+}
+
+#[test]
+fn embedded_fonts_are_borrowed() {
+    let hack: &'static [u8] = Box::leak(bundled("Hack").into_boxed_slice());
+    register_embedded("embedded://tests/Hack-Regular.ttf", hack);
+    let fonts = vec![FoundFont {
+        source: FoundFontSource::Path("embedded://tests/Hack-Regular.ttf".into()),
+        ..found("Hack", 0)
+    }];
+
+    let mut defs = FontDefinitions::default();
+    let ctx = egui::Context::default();
+    let report = apply_found_fonts(
+        &ctx,
+        fonts,
+        ApplyMode::Extend(&mut defs),
+        &FontOptions::new(),
+    );
+    assert_eq!(report.installed, ["Hack"]);
+    assert!(std::ptr::eq(&*defs.font_data[&key("Hack", 0)].font, hack));
+}
+
+#[test]
+fn font_data_is_shared_until_the_cache_is_cleared() {
+    let options = FontOptions::new().provider(fonts(&[(FontPreset::Latin, "Hack")]));
+    let installed =
+        |ctx: &egui::Context| snapshot(ctx).definitions().font_data[&key("Hack", 0)].clone();
+
+    let (first, second) = (egui::Context::default(), egui::Context::default());
+    set_with_options(&first, FontRegion::Latin, FontStyle::Sans, &options).unwrap();
+    set_with_options(&second, FontRegion::Latin, FontStyle::Sans, &options).unwrap();
+    assert!(Arc::ptr_eq(&installed(&first), &installed(&second)));
+
+    clear_font_cache();
+    let third = egui::Context::default();
+    set_with_options(&third, FontRegion::Latin, FontStyle::Sans, &options).unwrap();
+    assert!(!Arc::ptr_eq(&installed(&first), &installed(&third)));
+}
+
+#[cfg(all(feature = "watch", not(target_arch = "wasm32")))]
+#[test]
+fn font_dir_watcher_hears_about_new_files() {
+    use std::sync::mpsc;
+    use std::time::Duration;
+
+    let dir = temp_dir("watch");
+    let (tx, rx) = mpsc::channel();
+    let watcher = watch_font_dirs_with([dir.clone()], Duration::from_millis(50), move || {
+        let _ = tx.send(());
+    })
+    .unwrap();
+
+    std::fs::write(dir.join("new.ttf"), b"font data").unwrap();
+    assert!(rx.recv_timeout(Duration::from_secs(10)).is_ok());
+    drop(watcher);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn pin_han_region_moves_the_region_font_forward() {
+    let provider = StaticProvider::new()
+        .font(
+            FontPreset::SimplifiedChinese,
+            "Noto Sans CJK SC",
+            bundled("Hack"),
+        )
+        .font(
+            FontPreset::Japanese,
+            "Noto Sans JP",
+            bundled("Ubuntu-Light"),
+        );
+    let options = FontOptions::new().provider(provider);
+    let ctx = egui::Context::default();
+    let mut defs = FontDefinitions::default();
+    let presets = vec![FontPreset::SimplifiedChinese, FontPreset::Japanese];
+    extend_with_options(&ctx, &mut defs, presets, FontStyle::Sans, &options).unwrap();
+
+    assert!(pin_han_region(&ctx, &mut defs, FontRegion::Japanese));
+    let proportional = &defs.families[&FontFamily::Proportional];
+    let position = |key: &str| proportional.iter().position(|k| k == key).unwrap();
+    assert!(position(&key("Noto Sans JP", 1)) < position(&key("Noto Sans CJK SC", 0)));
+    // Already pinned.
+    assert!(!pin_han_region(&ctx, &mut defs, FontRegion::Japanese));
+}
+
+#[test]
+fn initial_definitions_lead_with_system_fonts() {
+    let _lock = global_defaults_lock();
+    let provider = fonts(&[(FontPreset::Latin, "Noto Sans")]).with_locale("en-US");
+    set_global_defaults(FontOptions::new().provider(provider));
+
+    let initial = initial_font_definitions(FontStyle::Sans);
+    set_global_defaults(FontOptions::new());
+    let (defs, report) = initial.unwrap();
+    assert_eq!(report.installed, ["Noto Sans"]);
+    assert_eq!(
+        defs.families[&FontFamily::Proportional][0],
+        key("Noto Sans", 0)
+    );
+}
+
+#[test]
+fn system_ui_font_leads_the_region_fonts() {
+    let provider = fonts(&[(FontPreset::Korean, "Noto Sans KR")])
+        .with_locale("ko-KR")
+        .with_ui_family("Ubuntu")
+        .font(
+            FontPreset::Custom(vec!["Ubuntu".to_owned()]),
+            "Ubuntu",
+            bundled("Ubuntu-Light"),
+        );
+    let ctx = egui::Context::default();
+    let report = set_system_ui_with_options(&ctx, &FontOptions::new().provider(provider)).unwrap();
+    assert_eq!(report.installed, ["Ubuntu", "Noto Sans KR"]);
+}
+
+#[test]
+fn pan_cjk_prefers_the_region_name() {
+    let regional = fonts(&[
+        (FontPreset::Korean, "Noto Sans KR"),
+        (FontPreset::Japanese, "Noto Sans JP"),
+    ])
+    .with_locale("ko-KR")
+    .font(FontPreset::Latin, "Noto Sans", bundled("Ubuntu-Light"));
+    let pan = |family: &str| FontPreset::Custom(vec![family.to_owned()]);
+    let with_pan = regional
+        .clone()
+        .font(pan("Noto Sans CJK JP"), "Noto Sans CJK JP", bundled("Hack"))
+        .font(pan("Noto Sans CJK KR"), "Noto Sans CJK KR", bundled("Hack"));
+
+    let ctx = egui::Context::default();
+    let options = FontOptions::new().provider(with_pan);
+    let report = set_pan_cjk_with_options(&ctx, FontStyle::Sans, &options).unwrap();
+    assert_eq!(report.installed, ["Noto Sans CJK KR", "Noto Sans"]);
+
+    // No pan-CJK font: the regional fonts are stacked.
+    let options = FontOptions::new().provider(regional);
+    let report = set_pan_cjk_with_options(&ctx, FontStyle::Sans, &options).unwrap();
+    assert_eq!(
+        report.installed,
+        ["Noto Sans KR", "Noto Sans JP", "Noto Sans"]
+    );
+}
+
+#[test]
+fn differently_tagged_pan_cjk_families_are_both_installed() {
+    let provider = fonts(&[
+        (FontPreset::SimplifiedChinese, "Noto Sans CJK SC"),
+        (FontPreset::Japanese, "Noto Sans CJK JP"),
+        (FontPreset::Japanese, "Noto Sans CJK"),
+    ]);
+    let options = FontOptions::new().provider(provider);
+    let ctx = egui::Context::default();
+    set_with_options(
+        &ctx,
+        vec![FontPreset::SimplifiedChinese],
+        FontStyle::Sans,
+        &options,
+    )
+    .unwrap();
+
+    let mut defs = snapshot(&ctx).definitions().clone();
+    let presets = vec![FontPreset::Japanese];
+    let report = extend_with_options(&ctx, &mut defs, presets, FontStyle::Sans, &options).unwrap();
+    assert_eq!(report.installed, ["Noto Sans CJK JP"]);
+    // The untagged name is the same family as the tagged ones.
+    assert_eq!(report.skipped[0].family, "Noto Sans CJK");
+    assert!(matches!(
+        report.skipped[0].reason,
+        SkipReason::DuplicateFamily(_)
+    ));
+}
+
+#[test]
+fn primary_font_leads_and_only_fixed_pitch_fonts_join_monospace() {
+    let defaults = FontDefinitions::default();
+    let provider = StaticProvider::new()
+        .with_locale("ko-KR")
+        .font(FontPreset::Korean, "Noto Sans KR", bundled("Ubuntu-Light"))
+        .font(
+            FontPreset::Custom(vec!["D2Coding".to_owned()]),
+            "D2Coding",
+            bundled("Hack"),
+        );
+    let options = FontOptions::new().provider(provider);
+    let brand = || FontData::from_owned(bundled("Ubuntu-Light"));
+    let (kr, d2) = (key("Noto Sans KR", 0), key("D2Coding", 1));
+
+    let ctx = egui::Context::default();
+    let report =
+        setup_with_primary_with_options(&ctx, brand(), "brand", FontStyle::Sans, &options).unwrap();
+    assert_eq!(report.installed, ["Noto Sans KR", "D2Coding"]);
+
+    let defs = snapshot(&ctx).definitions().clone();
+    let mut proportional = vec!["brand".to_owned()];
+    proportional.extend(defaults.families[&FontFamily::Proportional].iter().cloned());
+    proportional.extend([kr, d2.clone()]);
+    assert_eq!(defs.families[&FontFamily::Proportional], proportional);
+    let mut monospace = defaults.families[&FontFamily::Monospace].clone();
+    monospace.push(d2);
+    assert_eq!(defs.families[&FontFamily::Monospace], monospace);
+
+    let options = options.primary_monospace(true);
+    setup_with_primary_with_options(&ctx, brand(), "brand", FontStyle::Sans, &options).unwrap();
+    assert_eq!(
+        snapshot(&ctx).definitions().families[&FontFamily::Monospace][0],
+        "brand"
+    );
+}
+
+#[test]
+fn split_styles_fill_each_family_in_one_application() {
+    let provider = StaticProvider::new()
+        .font(
+            FontPreset::Japanese,
+            "Noto Serif JP",
+            bundled("Ubuntu-Light"),
+        )
+        .font(FontPreset::Japanese, "Noto Sans JP", bundled("Hack"));
+    // A static provider serves every font of a preset; sort them by the built-in style lists.
+    let options = FontOptions::new()
+        .provider(provider)
+        .style_classifier(|_| None);
+
+    let ctx = egui::Context::default();
+    let reports = set_split_with_options(
+        &ctx,
+        FontRegion::Japanese,
+        FontStyle::Serif,
+        FontStyle::Sans,
+        &options,
+    )
+    .unwrap();
+    assert_eq!(
+        reports[&FontFamily::Proportional].installed[0],
+        "Noto Serif JP"
+    );
+    assert_eq!(reports[&FontFamily::Monospace].installed[0], "Noto Sans JP");
+    assert_eq!(reports[&FontFamily::Proportional].applications, 1);
+    assert_eq!(
+        system_keys(&ctx, FontFamily::Proportional)[0],
+        key("Noto Serif JP", 0)
+    );
+    assert_eq!(
+        system_keys(&ctx, FontFamily::Monospace)[0],
+        key("Noto Sans JP", 1)
+    );
+}
+
+#[cfg(feature = "testing")]
+#[test]
+fn merging_keeps_the_app_fonts_behind_the_system_fonts() {
+    let _lock = global_defaults_lock();
+    set_test_fonts(vec![found("Test Sans", 0)]);
+    let mut defs = FontDefinitions::default();
+    defs.font_data
+        .insert("my-font".to_owned(), defs.font_data["Hack"].clone());
+    defs.families
+        .get_mut(&FontFamily::Proportional)
+        .unwrap()
+        .push("my-font".to_owned());
+
+    let ctx = egui::Context::default();
+    assert_eq!(
+        set_auto_merging(&ctx, &mut defs, FontStyle::Sans),
+        ["Test Sans"]
+    );
+    clear_test_fonts();
+    let proportional = &defs.families[&FontFamily::Proportional];
+    assert_eq!(proportional[0], key("Test Sans", 0));
+    assert_eq!(proportional.last().map(String::as_str), Some("my-font"));
+}
+
+#[cfg(feature = "testing")]
+#[test]
+fn extending_at_the_front_twice_changes_nothing() {
+    let _lock = global_defaults_lock();
+    set_test_fonts(vec![
+        found("Noto Sans KR", 0),
+        found("Noto Sans JP", 1),
+        found("Noto Sans", 2),
+    ]);
+    let mut defs = FontDefinitions::default();
+    defs.font_data
+        .insert("my-font".to_owned(), defs.font_data["Hack"].clone());
+    defs.families
+        .get_mut(&FontFamily::Proportional)
+        .unwrap()
+        .insert(0, "my-font".to_owned());
+    let before = defs.families[&FontFamily::Proportional].clone();
+
+    let ctx = egui::Context::default();
+    let presets = [FontPreset::Korean, FontPreset::Japanese, FontPreset::Latin];
+    let installed = extend_with_presets_front(&ctx, &mut defs, presets.clone(), FontStyle::Sans);
+    assert_eq!(installed, ["Noto Sans KR", "Noto Sans JP", "Noto Sans"]);
+    let ours = [
+        key("Noto Sans KR", 0),
+        key("Noto Sans JP", 1),
+        key("Noto Sans", 2),
+    ];
+    assert_eq!(defs.families[&FontFamily::Proportional][..3], ours);
+    assert_eq!(defs.families[&FontFamily::Proportional][3..], before);
+
+    assert!(extend_with_presets_front(&ctx, &mut defs, presets, FontStyle::Sans).is_empty());
+    clear_test_fonts();
+    assert_eq!(defs.families[&FontFamily::Proportional][..3], ours);
+}
+
+#[test]
+fn resolved_bytes_and_shared_data_come_from_the_global_provider() {
+    let _lock = global_defaults_lock();
+    let hack = bundled("Hack");
+    set_global_defaults(FontOptions::new().provider(fonts(&[(FontPreset::Latin, "Hack")])));
+
+    let bytes = resolve_bytes(FontRegion::Latin, FontStyle::Sans);
+    let shared = resolve_shared(FontRegion::Latin, FontStyle::Sans);
+    set_global_defaults(FontOptions::new());
+    assert_eq!(bytes.len(), 1);
+    assert_eq!(bytes[0].0, key("Hack", 0));
+    assert_eq!(
+        (bytes[0].1.len(), bytes[0].2.as_str()),
+        (hack.len(), "Hack")
+    );
+
+    // The exporter gets the very bytes egui renders with.
+    let (key, data) = &shared[0];
+    let mut defs = FontDefinitions::default();
+    defs.font_data.insert(key.to_string(), Arc::clone(data));
+    assert!(std::ptr::eq(
+        &*data.font,
+        &*defs.font_data[key.as_str()].font
+    ));
+}
+
+#[test]
+fn target_family_overrides_the_style_slots() {
+    let _lock = global_defaults_lock();
+    let provider = fonts(&[(FontPreset::Korean, "Malgun Gothic")]);
+    set_global_defaults(FontOptions::new().provider(provider));
+    let ctx = egui::Context::default();
+
+    let mut only_proportional = FontDefinitions::default();
+    let target = Some(FontFamily::Proportional);
+    let presets = [FontPreset::Korean];
+    let installed = extend_with_presets_target(
+        &ctx,
+        &mut only_proportional,
+        presets.clone(),
+        FontStyle::Sans,
+        target,
+    );
+    // Without a target, serif fonts stay out of Monospace.
+    let mut serif = FontDefinitions::default();
+    extend_with_presets_target(&ctx, &mut serif, presets.clone(), FontStyle::Serif, None);
+    let mut only_monospace = FontDefinitions::default();
+    let target = Some(FontFamily::Monospace);
+    extend_with_presets_target(&ctx, &mut only_monospace, presets, FontStyle::Serif, target);
+    set_global_defaults(FontOptions::new());
+
+    assert_eq!(installed, ["Malgun Gothic"]);
+    let key = key("Malgun Gothic", 0);
+    let has = |defs: &FontDefinitions, family| defs.families[&family].contains(&key);
+    assert!(has(&only_proportional, FontFamily::Proportional));
+    assert!(!has(&only_proportional, FontFamily::Monospace));
+    assert!(has(&serif, FontFamily::Proportional));
+    assert!(!has(&serif, FontFamily::Monospace));
+    assert!(!has(&only_monospace, FontFamily::Proportional));
+    assert!(has(&only_monospace, FontFamily::Monospace));
+}
+
+#[test]
+fn chars_pull_in_the_best_covering_font_once() {
+    // Hack has box-drawing characters; Ubuntu Light does not.
+    let provider = StaticProvider::new()
+        .font(FontPreset::Latin, "Ubuntu Light", bundled("Ubuntu-Light"))
+        .font(FontPreset::Latin, "Hack", bundled("Hack"));
+    let chars = ['a', 'b', '─'];
+    let ranked: Vec<_> = provider
+        .find_for_chars(&chars, FontStyle::Sans)
+        .into_iter()
+        .map(|f| f.family)
+        .collect();
+    assert_eq!(ranked, ["Hack", "Ubuntu Light"]);
+
+    let options = FontOptions::new().provider(provider);
+    let ctx = egui::Context::default();
+    let mut defs = FontDefinitions::empty();
+    let report =
+        extend_for_chars_with_options(&ctx, &mut defs, &chars, FontStyle::Sans, &options).unwrap();
+    assert_eq!(report.installed, ["Hack"]);
+    let report =
+        extend_for_chars_with_options(&ctx, &mut defs, &chars, FontStyle::Sans, &options).unwrap();
+    assert!(report.installed.is_empty());
+}
+
+#[test]
+fn anchors_place_fonts_around_existing_keys() {
+    let provider = fonts(&[(FontPreset::Japanese, "Noto Sans JP")]).font(
+        FontPreset::Latin,
+        "Noto Sans",
+        bundled("Ubuntu-Light"),
+    );
+    let options = FontOptions::new().provider(provider).monospace(false);
+    let presets = || vec![FontPreset::Japanese, FontPreset::Latin];
+    // Brand font, egui's font, icon font.
+    let mut base = FontDefinitions::default();
+    for name in ["brand", "icons"] {
+        base.font_data
+            .insert(name.to_owned(), base.font_data["Hack"].clone());
+    }
+    let stack = ["brand", "Ubuntu-Light", "icons"]
+        .map(String::from)
+        .to_vec();
+    base.families.insert(FontFamily::Proportional, stack);
+    let (jp, latin) = (key("Noto Sans JP", 0), key("Noto Sans", 1));
+    let ctx = egui::Context::default();
+    let extend_at = |anchor| {
+        let mut defs = base.clone();
+        let report = extend_with_options_at(
+            &ctx,
+            &mut defs,
+            anchor,
+            presets(),
+            FontStyle::Sans,
+            &options,
+        )
+        .unwrap();
+        (defs.families[&FontFamily::Proportional].clone(), report)
+    };
+
+    let (after, _) = extend_at(Anchor::After("brand"));
+    assert_eq!(after, ["brand", &jp, &latin, "Ubuntu-Light", "icons"]);
+    let (before, _) = extend_at(Anchor::Before("icons"));
+    assert_eq!(before, ["brand", "Ubuntu-Light", &jp, &latin, "icons"]);
+    // A missing key falls back to the back, with a warning.
+    let (missing, report) = extend_at(Anchor::After("missing"));
+    assert_eq!(missing, ["brand", "Ubuntu-Light", "icons", &jp, &latin]);
+    assert_eq!(report.warnings.len(), 1);
+}
+
+#[test]
+fn extend_into_applies_the_moved_definitions() {
+    let options = FontOptions::new().provider(fonts(&[(FontPreset::Latin, "Hack")]));
+    let ctx = egui::Context::default();
+    let defs = FontDefinitions::default();
+    let report =
+        extend_with_options_into(&ctx, defs, FontRegion::Latin, FontStyle::Sans, &options).unwrap();
+    assert_eq!(report.installed, ["Hack"]);
+    assert!(snapshot(&ctx)
+        .definitions()
+        .font_data
+        .contains_key(&key("Hack", 0)));
+}
+
+#[test]
+fn found_fonts_are_appended_once_per_key() {
+    let ctx = egui::Context::default();
+    let mut defs = FontDefinitions::default();
+    let fonts = vec![found("Hack", 0)];
+    let report = apply_found_fonts(
+        &ctx,
+        fonts,
+        ApplyMode::Extend(&mut defs),
+        &FontOptions::new(),
+    );
+    assert_eq!(report.installed, ["Hack"]);
+    assert_eq!(
+        defs.families[&FontFamily::Proportional].last(),
+        Some(&key("Hack", 0))
+    );
+
+    // Another family under a key that is taken.
+    let mut defs = FontDefinitions::default();
+    let fonts = vec![
+        found("Hack", 0),
+        FoundFont {
+            family: "Hack Nerd".to_owned(),
+            ..found("Hack", 0)
+        },
+    ];
+    apply_found_fonts(
+        &ctx,
+        fonts,
+        ApplyMode::Extend(&mut defs),
+        &FontOptions::new(),
+    );
+    let ours: Vec<_> = defs.families[&FontFamily::Proportional]
+        .iter()
+        .filter(|k| is_system_font_key(k))
+        .collect();
+    assert_eq!(ours, [&key("Hack", 0)]);
+}
+
+#[test]
+fn removing_fonts_restores_the_defaults() {
+    let ctx = egui::Context::default();
+    let mut defs = FontDefinitions::default();
+    apply_found_fonts(
+        &ctx,
+        vec![found("Hack", 0)],
+        ApplyMode::Extend(&mut defs),
+        &FontOptions::new(),
+    );
+    assert!(remove_family(&ctx, &mut defs, "Hack"));
+    assert_eq!(defs, FontDefinitions::default());
+    assert!(!remove_family(&ctx, &mut defs, "Hack"));
+
+    let fonts = vec![found("Hack", 0)];
+    let report = apply_found_fonts(
+        &ctx,
+        fonts,
+        ApplyMode::Extend(&mut defs),
+        &FontOptions::new(),
+    );
+    assert_eq!(report.installed_keys, [key("Hack", 0)]);
+    assert!(remove_font(&ctx, &mut defs, &report.installed_keys[0]));
+    assert_eq!(defs, FontDefinitions::default());
+    // egui's own fonts are not this crate's to remove.
+    assert!(!remove_font(&ctx, &mut defs, &FontKey::from("Hack")));
+}
+
+#[test]
+fn reset_keeps_the_app_fonts() {
+    let ctx = egui::Context::default();
+    let mut defs = FontDefinitions::default();
+    defs.font_data
+        .insert("my-font".to_owned(), defs.font_data["Hack"].clone());
+    defs.families
+        .get_mut(&FontFamily::Proportional)
+        .unwrap()
+        .push("my-font".to_owned());
+    ctx.set_fonts(defs);
+    let _ = ctx.run(egui::RawInput::default(), |_| {});
+    let before = snapshot(&ctx);
+
+    let options = FontOptions::new().provider(fonts(&[(FontPreset::Latin, "Hack")]));
+    set_with_options(&ctx, FontRegion::Latin, FontStyle::Sans, &options).unwrap();
+    let mut defs = snapshot(&ctx).definitions().clone();
+    assert!(reset_system_fonts(&ctx, &mut defs));
+    assert_eq!(&defs, before.definitions());
+    assert!(defs.font_data.contains_key("my-font"));
+}
+
+#[test]
+fn metadata_is_read_from_the_name_and_os2_tables() {
+    let source = |name: &str| FoundFontSource::Bytes(bundled(name).into());
+    let hack = read_metadata(&source("Hack")).unwrap();
+    assert_eq!(hack.family.as_deref(), Some("Hack"));
+    assert_eq!(hack.subfamily.as_deref(), Some("Regular"));
+    assert_eq!(hack.weight, 400);
+    assert!(hack.is_monospace && !hack.is_italic);
+    assert_eq!(hack.num_faces, 1);
+
+    let ubuntu = read_metadata(&source("Ubuntu-Light")).unwrap();
+    assert_eq!(ubuntu.family.as_deref(), Some("Ubuntu"));
+    assert_eq!(ubuntu.weight, 300);
+    assert!(!ubuntu.is_monospace);
+
+    let garbage = FoundFontSource::Bytes(b"not a font".to_vec().into());
+    assert!(matches!(
+        read_metadata(&garbage),
+        Err(FontError::InvalidFont(_))
+    ));
+    let missing = FoundFontSource::Path("/nonexistent/font.ttf".into());
+    assert!(matches!(
+        read_metadata(&missing),
+        Err(FontError::ReadFailed(_))
+    ));
+}
+
+#[test]
+fn presets_are_installed_in_priority_order() {
+    let provider = fonts(&[
+        (FontPreset::SimplifiedChinese, "Noto Sans SC"),
+        (FontPreset::Japanese, "Noto Sans JP"),
+    ])
+    .font(FontPreset::Latin, "Noto Sans", bundled("Ubuntu-Light"));
+    let options = FontOptions::new().provider(provider);
+
+    let ctx = egui::Context::default();
+    let presets = vec![
+        FontPreset::Japanese,
+        FontPreset::SimplifiedChinese,
+        FontPreset::Latin,
+    ];
+    let report = set_with_options(&ctx, presets, FontStyle::Sans, &options).unwrap();
+    assert_eq!(
+        report.installed,
+        ["Noto Sans JP", "Noto Sans SC", "Noto Sans"]
+    );
+    let ours = [
+        key("Noto Sans JP", 1),
+        key("Noto Sans SC", 0),
+        key("Noto Sans", 2),
+    ];
+    assert_eq!(system_keys(&ctx, FontFamily::Proportional), ours);
+}
+
+#[test]
+fn size_limits_skip_or_trim_candidates() {
+    let hack_len = bundled("Hack").len() as u64;
+    let fonts = vec![
+        found("Hack", 0),
+        FoundFont {
+            source: FoundFontSource::Bytes(bundled("Ubuntu-Light").into()),
+            ..found("Ubuntu-Light", 0)
+        },
+    ];
+    let options = FontOptions::new().max_total_bytes(hack_len);
+    let mut defs = FontDefinitions::default();
+    let ctx = egui::Context::default();
+    let report = apply_found_fonts(&ctx, fonts, ApplyMode::Extend(&mut defs), &options);
+    assert_eq!(report.installed, ["Hack"]);
+    assert_eq!(report.installed_bytes, hack_len);
+    assert!(matches!(
+        report.skipped[0].reason,
+        SkipReason::OverBudget(_)
+    ));
+}
+
+#[test]
+fn family_cap_counts_families_added_before() {
+    let provider = fonts(&[(FontPreset::Korean, "Hack")])
+        .font(FontPreset::Latin, "Ubuntu-Light", bundled("Ubuntu-Light"))
+        .font(FontPreset::Japanese, "Hack JP", bundled("Hack"));
+    let options = FontOptions::new().provider(provider).max_families(2);
+    let ctx = egui::Context::default();
+    let mut defs = FontDefinitions::default();
+
+    let korean = extend_with_options(
+        &ctx,
+        &mut defs,
+        FontRegion::Korean,
+        FontStyle::Sans,
+        &options,
+    )
+    .unwrap();
+    assert_eq!(korean.installed, ["Hack", "Hack JP"]);
+    assert_eq!(korean.skipped[0].family, "Ubuntu-Light");
+    assert_eq!(korean.skipped[0].reason, SkipReason::TooManyFamilies(2));
+    let latin = extend_with_options(
+        &ctx,
+        &mut defs,
+        FontRegion::Latin,
+        FontStyle::Sans,
+        &options,
+    )
+    .unwrap();
+    assert!(latin.installed.is_empty());
+}
+
+#[test]
+fn trimmed_candidates_are_never_read() {
+    let hack = bundled("Hack");
+    let provider = StaticProvider::new()
+        .font(FontPreset::Latin, "Noto Sans", bundled("Ubuntu-Light"))
+        .font(
+            FontPreset::Latin,
+            "Broken Sans",
+            hack[..hack.len() / 2].to_vec(),
+        )
+        .font(FontPreset::Latin, "Hack", hack);
+    let options = FontOptions::new()
+        .provider(provider)
+        .max_fonts_per_preset(1);
+
+    let ctx = egui::Context::default();
+    let report = set_with_options(&ctx, FontRegion::Latin, FontStyle::Sans, &options).unwrap();
+    assert_eq!(report.installed, ["Noto Sans"]);
+    assert!(report.skipped.is_empty());
+    assert!(matches!(report.trimmed[..], [(FontPreset::Latin, 2)]));
+}
+
+#[test]
+fn strict_rejects_a_region_font_without_its_script() {
+    // Hack has no kana.
+    let provider = fonts(&[(FontPreset::Japanese, "Noto Sans JP")]);
+    let options = FontOptions::new().provider(provider).strict(true);
+    let ctx = egui::Context::default();
+    let result = set_with_options(&ctx, FontRegion::Japanese, FontStyle::Sans, &options);
+    assert!(matches!(
+        result,
+        Err(FontError::RegionNotCovered {
+            region: FontRegion::Japanese,
+            ..
+        })
+    ));
+    assert_eq!(snapshot(&ctx).definitions(), &FontDefinitions::default());
+}
+
+#[test]
+fn font_dirs_pick_the_first_copy_by_path() {
+    let dir = temp_dir("dirs");
+    for copy in ["nested", "copy"] {
+        std::fs::create_dir_all(dir.join(copy)).unwrap();
+        std::fs::write(dir.join(copy).join("Hack-Regular.ttf"), bundled("Hack")).unwrap();
+    }
+    let read = Arc::new(Mutex::new(None));
+    let sink = Arc::clone(&read);
+    let options = FontOptions::new()
+        .add_font_dirs([&dir, &dir.join("missing")])
+        .progress(move |progress| {
+            if let Progress::ReadingFont { path, .. } = progress {
+                *sink.lock().unwrap() = path.clone();
+            }
+        });
+
+    let mut defs = FontDefinitions::default();
+    let presets = vec![FontPreset::Custom(vec!["Hack".to_owned()])];
+    let ctx = egui::Context::default();
+    let report = extend_with_options(&ctx, &mut defs, presets, FontStyle::Sans, &options).unwrap();
+    assert_eq!(report.installed, ["Hack"]);
+    let path = read.lock().unwrap().clone().unwrap();
+    assert!(path.ends_with("copy/Hack-Regular.ttf"));
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn tweaks_default_per_preset_and_can_be_overridden() {
+    let provider = fonts(&[(FontPreset::Korean, "Malgun Gothic")]);
+    let ctx = egui::Context::default();
+    let options = FontOptions::new().provider(provider.clone());
+    let mut defs = FontDefinitions::default();
+    let report = extend_with_options(
+        &ctx,
+        &mut defs,
+        FontRegion::Korean,
+        FontStyle::Sans,
+        &options,
+    )
+    .unwrap();
+    assert_eq!(report.tweaks[0].1, default_tweak(&FontPreset::Korean));
+    assert_eq!(
+        defs.font_data[&key("Malgun Gothic", 0)].tweak,
+        report.tweaks[0].1
+    );
+
+    let custom = FontTweak {
+        scale: 0.8,
+        ..Default::default()
+    };
+    let options = FontOptions::new()
+        .provider(provider)
+        .tweak(move |f| (f.family == "Malgun Gothic").then_some(custom));
+    let mut defs = FontDefinitions::default();
+    let report = extend_with_options(
+        &ctx,
+        &mut defs,
+        FontRegion::Korean,
+        FontStyle::Sans,
+        &options,
+    )
+    .unwrap();
+    assert_eq!(report.tweaks[0].1, custom);
+}
+
+#[test]
+fn classified_styles_order_the_candidates() {
+    let provider = fonts(&[(FontPreset::Latin, "Hack")]).font(
+        FontPreset::Latin,
+        "Ubuntu-Light",
+        bundled("Ubuntu-Light"),
+    );
+    let options = FontOptions::new()
+        .provider(provider)
+        .style_classifier(|f| (f.family == "Hack").then_some(FontStyle::Serif));
+    let mut defs = FontDefinitions::default();
+    let ctx = egui::Context::default();
+    let presets = vec![FontPreset::Latin];
+    let report = extend_with_options(&ctx, &mut defs, presets, FontStyle::Sans, &options).unwrap();
+    assert_eq!(report.installed, ["Ubuntu-Light", "Hack"]);
+}
+
+#[test]
+fn scale_tweaks_apply_by_style_and_globally() {
+    let serif = FontTweak {
+        scale: 1.1,
+        y_offset_factor: -0.02,
+        ..Default::default()
+    };
+    let provider = StaticProvider::new()
+        .font(FontPreset::Latin, "Noto Serif", bundled("Ubuntu-Light"))
+        .font(FontPreset::Latin, "Noto Sans", bundled("Hack"));
+    let options = FontOptions::new()
+        .provider(provider)
+        .style_tweak(FontStyle::Serif, serif);
+    let ctx = egui::Context::default();
+    let report = set_with_options(&ctx, FontRegion::Latin, FontStyle::Serif, &options).unwrap();
+    assert_eq!(report.tweaks[0], ("Noto Serif".to_owned(), serif));
+    assert_eq!(
+        report.tweaks[1],
+        ("Noto Sans".to_owned(), FontTweak::default())
+    );
+
+    let provider = fonts(&[
+        (FontPreset::Korean, "Malgun Gothic"),
+        (FontPreset::Latin, "Noto Sans"),
+    ]);
+    let options = FontOptions::new().provider(provider).global_scale(1.1);
+    let mut defs = FontDefinitions::default();
+    let report = extend_with_options(
+        &ctx,
+        &mut defs,
+        FontRegion::Korean,
+        FontStyle::Sans,
+        &options,
+    )
+    .unwrap();
+    assert_eq!(
+        report.tweaks[0].1.scale,
+        default_tweak(&FontPreset::Korean).scale * 1.1
+    );
+    assert_eq!(report.tweaks[1].1.scale, 1.1);
+    assert_eq!(defs.font_data[&key("Noto Sans", 1)].tweak.scale, 1.1);
+}
+
+#[test]
+fn normalized_metrics_match_the_egui_line_box() {
+    let provider = StaticProvider::new()
+        .font(FontPreset::Korean, "Malgun Gothic", bundled("Ubuntu-Light"))
+        .font(FontPreset::Latin, "Noto Sans", bundled("Hack"));
+    let options = FontOptions::new()
+        .provider(provider)
+        .normalize_metrics(true);
+    let ctx = egui::Context::default();
+    let report = set_with_options(&ctx, FontRegion::Korean, FontStyle::Sans, &options).unwrap();
+    // egui's own font already has its line box, even under a CJK family name.
+    assert_eq!(
+        report.tweaks[0],
+        ("Malgun Gothic".to_owned(), FontTweak::default())
+    );
+    // Hack has no line gap, so it is drawn slightly larger to fill the same row height.
+    assert!(report.tweaks[1].1.scale > 1.0);
+}
+
+#[cfg(feature = "subset")]
+#[test]
+fn subsets_keep_exactly_the_requested_characters() {
+    let hack = bundled("Hack");
+    let chars = region_chars(FontRegion::Cyrillic);
+    assert!(chars.contains(&'Ж') && chars.contains(&'a'));
+    let provider = fonts(&[(FontPreset::Latin, "Hack")]);
+    let options = FontOptions::new()
+        .provider(provider)
+        .subset(chars.iter().copied());
+
+    let mut defs = FontDefinitions::default();
+    let ctx = egui::Context::default();
+    let presets = vec![FontPreset::Latin];
+    let report = extend_with_options(&ctx, &mut defs, presets, FontStyle::Sans, &options).unwrap();
+    assert!(report.installed_bytes < hack.len() as u64);
+    assert_eq!(report.subset[0].1, hack.len() as u64);
+    assert!(report.subset[0].2 < report.subset[0].1);
+
+    let full = ttf_parser::Face::parse(&hack, 0).unwrap();
+    let trimmed = ttf_parser::Face::parse(&defs.font_data[&key("Hack", 0)].font, 0).unwrap();
+    for &c in &chars {
+        assert_eq!(
+            full.glyph_index(c).is_some(),
+            trimmed.glyph_index(c).is_some(),
+            "{c:?}"
+        );
+    }
+    assert!(trimmed.glyph_index('─').is_none());
+}
+
+#[test]
+fn serif_fonts_join_monospace_only_when_asked() {
+    let options = FontOptions::new().provider(fonts(&[(FontPreset::Latin, "Noto Serif")]));
+    let key = key("Noto Serif", 0);
+    let ctx = egui::Context::default();
+
+    let mut defs = FontDefinitions::default();
+    let presets = vec![FontPreset::Latin];
+    extend_with_options(&ctx, &mut defs, presets.clone(), FontStyle::Serif, &options).unwrap();
+    assert!(defs.families[&FontFamily::Proportional].contains(&key));
+    assert!(!defs.families[&FontFamily::Monospace].contains(&key));
+
+    let mut defs = FontDefinitions::default();
+    let options = options.monospace(true);
+    extend_with_options(&ctx, &mut defs, presets, FontStyle::Serif, &options).unwrap();
+    assert!(defs.families[&FontFamily::Monospace].contains(&key));
+}
+
+#[test]
+fn order_policy_puts_fonts_behind_or_before_egui_fonts() {
+    let defaults = FontDefinitions::default();
+    let provider = fonts(&[(FontPreset::Japanese, "Noto Sans JP")]).font(
+        FontPreset::Latin,
+        "Noto Sans",
+        bundled("Ubuntu-Light"),
+    );
+    let added = [key("Noto Sans JP", 0), key("Noto Sans", 1)];
+    let egui_fonts = &defaults.families[&FontFamily::Proportional][..];
+    let ctx = egui::Context::default();
+
+    for (policy, expected) in [
+        (OrderPolicy::PreferDefaults, [egui_fonts, &added].concat()),
+        (OrderPolicy::PreferSystem, [&added, egui_fonts].concat()),
+    ] {
+        let options = FontOptions::new().provider(provider.clone()).order(policy);
+        let mut defs = defaults.clone();
+        let presets = vec![FontPreset::Japanese, FontPreset::Latin];
+        extend_with_options(&ctx, &mut defs, presets, FontStyle::Sans, &options).unwrap();
+        assert_eq!(
+            defs.families[&FontFamily::Proportional],
+            expected,
+            "{policy:?}"
+        );
+    }
+}
+
+#[test]
+fn primary_script_can_put_latin_first() {
+    let provider = StaticProvider::new()
+        .font(FontPreset::Korean, "Noto Sans KR", bundled("Ubuntu-Light"))
+        .font(FontPreset::Latin, "Noto Sans", bundled("Hack"));
+    let options = FontOptions::new().provider(provider);
+    let ctx = egui::Context::default();
+
+    let report = set_with_options(&ctx, FontRegion::Korean, FontStyle::Sans, &options).unwrap();
+    assert_eq!(report.installed, ["Noto Sans KR", "Noto Sans"]);
+    let options = options.primary_script(PrimaryScript::Latin);
+    let report = set_with_options(&ctx, FontRegion::Korean, FontStyle::Sans, &options).unwrap();
+    assert_eq!(report.installed, ["Noto Sans", "Noto Sans KR"]);
+}
+
+#[test]
+fn proportional_fonts_stay_out_of_a_fixed_pitch_monospace() {
+    let defaults = FontDefinitions::default();
+    let provider = StaticProvider::new()
+        .font(FontPreset::Korean, "Noto Sans KR", bundled("Ubuntu-Light"))
+        .font(
+            FontPreset::Custom(vec!["D2Coding".to_owned()]),
+            "D2Coding",
+            bundled("Hack"),
+        );
+    let options = FontOptions::new()
+        .provider(provider)
+        .monospace_fixed_pitch_only(true);
+    let ctx = egui::Context::default();
+    let mut defs = FontDefinitions::default();
+    let presets = vec![FontPreset::Korean];
+    let report = extend_with_options(&ctx, &mut defs, presets, FontStyle::Sans, &options).unwrap();
+    assert_eq!(report.installed, ["Noto Sans KR", "D2Coding"]);
+
+    let monospace = &defs.families[&FontFamily::Monospace];
+    assert!(!monospace.contains(&key("Noto Sans KR", 0)));
+    assert!(monospace.contains(&key("D2Coding", 1)));
+    assert_eq!(
+        monospace[..monospace.len() - 1],
+        defaults.families[&FontFamily::Monospace]
+    );
+}
+
+#[test]
+fn weight_installs_the_matching_faces_under_the_regular_keys() {
+    let hack = FontDefinitions::default().font_data["Hack"].font.clone();
+    // Stand-ins: Ubuntu Light as the regular faces, Hack as the bold ones.
+    let provider = StaticProvider::new()
+        .font(
+            FontPreset::Japanese,
+            "Noto Sans JP",
+            bundled("Ubuntu-Light"),
+        )
+        .weighted_font(FontPreset::Japanese, "Noto Sans JP", 700, bundled("Hack"))
+        .font(FontPreset::Latin, "Noto Sans", bundled("Ubuntu-Light"))
+        .weighted_font(FontPreset::Latin, "Noto Sans", 700, bundled("Hack"));
+    let options = FontOptions::new().provider(provider).weight(700);
+    let ctx = egui::Context::default();
+    let mut defs = FontDefinitions::default();
+    let presets = vec![FontPreset::Japanese, FontPreset::Latin];
+    let report = extend_with_options(&ctx, &mut defs, presets, FontStyle::Sans, &options).unwrap();
+    assert_eq!(report.installed, ["Noto Sans JP", "Noto Sans"]);
+    for key in [key("Noto Sans JP", 0), key("Noto Sans", 2)] {
+        assert_eq!(defs.font_data[&key].font, hack);
+    }
+}
+
+#[test]
+fn display_faces_replace_their_text_faces() {
+    let provider = fonts(&[(
+        FontPreset::Custom(vec!["Noto Serif Display".to_owned()]),
+        "Noto Serif Display",
+    )])
+    .font(FontPreset::Latin, "Noto Serif", bundled("Ubuntu-Light"));
+    let options = FontOptions::new().provider(provider).display_faces(true);
+    let mut defs = FontDefinitions::default();
+    let ctx = egui::Context::default();
+    let presets = vec![FontPreset::Latin];
+    let report = extend_with_options(&ctx, &mut defs, presets, FontStyle::Serif, &options).unwrap();
+    assert_eq!(report.installed, ["Noto Serif Display"]);
+}
+
+#[test]
+fn cache_file_serves_fonts_until_a_file_changes() {
+    let dir = temp_dir("cache");
+    let font_file = dir.join("Hack.ttf");
+    std::fs::write(&font_file, bundled("Hack")).unwrap();
+    let cache = dir.join("fonts.cache");
+    let run = |options: &FontOptions| {
+        let mut defs = FontDefinitions::default();
+        let presets = vec![FontPreset::Custom(vec!["Hack".to_owned()])];
+        let ctx = egui::Context::default();
+        extend_with_options(&ctx, &mut defs, presets, FontStyle::Sans, options)
+            .unwrap()
+            .installed
+    };
+
+    assert_eq!(
+        run(&FontOptions::new().add_font_dirs([&dir]).cache_file(&cache)),
+        ["Hack"]
+    );
+    // Served from the cache, even without the directory.
+    assert_eq!(run(&FontOptions::new().cache_file(&cache)), ["Hack"]);
+    // Stale: resolved again, and nothing is found.
+    std::fs::write(&font_file, bundled("Ubuntu-Light")).unwrap();
+    assert!(run(&FontOptions::new().cache_file(&cache)).is_empty());
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn definitions_in_effect_are_not_applied_again_unless_forced() {
+    let options = FontOptions::new().provider(fonts(&[(FontPreset::Latin, "Hack")]));
+    let ctx = egui::Context::default();
+
+    let first = set_with_options(&ctx, FontRegion::Latin, FontStyle::Sans, &options).unwrap();
+    assert_eq!((first.applications, first.unchanged), (1, false));
+    let second = set_with_options(&ctx, FontRegion::Latin, FontStyle::Sans, &options).unwrap();
+    assert_eq!((second.applications, second.unchanged), (0, true));
+    assert_eq!(second.installed, ["Hack"]);
+    let options = options.force(true);
+    let forced = set_with_options(&ctx, FontRegion::Latin, FontStyle::Sans, &options).unwrap();
+    assert_eq!((forced.applications, forced.unchanged), (1, false));
+}
+
+#[test]
+fn log_sink_receives_events_while_silent() {
+    let size = bundled("Hack").len() as u64;
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let sink = Arc::clone(&events);
+    let options = FontOptions::new()
+        .provider(fonts(&[(FontPreset::Latin, "Hack")]))
+        .verbosity(Verbosity::Silent)
+        .log_sink(move |event| sink.lock().unwrap().push(event.clone()));
+    let mut defs = FontDefinitions::default();
+    let ctx = egui::Context::default();
+    let presets = vec![FontPreset::Latin];
+    extend_with_options(&ctx, &mut defs, presets, FontStyle::Sans, &options).unwrap();
+
+    let events = events.lock().unwrap();
+    assert!(matches!(
+        &events[0],
+        LogEvent::Resolved { candidates: 1, .. }
+    ));
+    assert!(matches!(&events[1], LogEvent::Candidate { family, .. } if family == "Hack"));
+    assert!(matches!(&events[2], LogEvent::Installed { bytes, .. } if *bytes == size));
+}
+
+#[test]
+fn progress_reports_each_step() {
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let sink = Arc::clone(&seen);
+    let options = FontOptions::new()
+        .provider(fonts(&[(FontPreset::Latin, "Hack")]))
+        .progress(move |progress| sink.lock().unwrap().push(progress.clone()));
+    let ctx = egui::Context::default();
+    set_with_options(&ctx, FontRegion::Latin, FontStyle::Sans, &options).unwrap();
+
+    let seen = seen.lock().unwrap();
+    assert_eq!(seen[0], Progress::Resolving);
+    assert!(matches!(
+        &seen[1],
+        Progress::ReadingFont { family, index: 0, total: 1, path: None } if family == "Hack"
+    ));
+    assert_eq!(seen[2], Progress::Done { installed: 1 });
+}
+
+#[test]
+fn cancelling_leaves_everything_untouched() {
+    let defaults = FontDefinitions::default();
+    let provider = fonts(&[(FontPreset::Latin, "Hack")]).font(
+        FontPreset::Latin,
+        "Ubuntu Light",
+        bundled("Ubuntu-Light"),
+    );
+    let token = CancelToken::new();
+    let canceller = token.clone();
+    let options = FontOptions::new()
+        .provider(provider)
+        .cancel_token(token)
+        .progress(move |progress| {
+            if let Progress::ReadingFont { .. } = progress {
+                canceller.cancel();
+            }
+        });
+
+    let ctx = egui::Context::default();
+    let mut defs = FontDefinitions::default();
+    let presets = vec![FontPreset::Latin];
+    let result = extend_with_options(&ctx, &mut defs, presets, FontStyle::Sans, &options);
+    assert_eq!(result.unwrap_err(), FontError::Cancelled);
+    assert_eq!(defs.font_data.len(), defaults.font_data.len());
+    assert!(installed_fonts(&ctx).is_none());
+}
+
+#[test]
+fn global_defaults_apply_to_plain_calls_only() {
+    let _lock = global_defaults_lock();
+    let app_fonts = fonts(&[(FontPreset::Latin, "App Sans")]);
+    set_global_defaults(FontOptions::new().provider(app_fonts).monospace(false));
+    let debug = format!("{:?}", global_defaults());
+    let ctx = egui::Context::default();
+    let plain = set_with_region(&ctx, FontRegion::Latin, FontStyle::Sans);
+    let other = fonts(&[(FontPreset::Latin, "Other Sans")]);
+    let options = FontOptions::new().provider(other);
+    let report = set_with_options(&ctx, FontRegion::Latin, FontStyle::Sans, &options).unwrap();
+    set_global_defaults(FontOptions::new());
+
+    assert!(debug.contains("monospace: Some(false)"));
+    assert_eq!(plain, ["App Sans"]);
+    assert_eq!(report.installed, ["Other Sans"]);
+}
+
+#[test]
+fn static_provider_serves_fonts_in_preset_order() {
+    let provider = fonts(&[(FontPreset::Korean, "Hack")])
+        .with_locale("ko-KR")
+        .font(FontPreset::Latin, "Ubuntu-Light", bundled("Ubuntu-Light"));
+    let options = FontOptions::new().provider(provider);
+    let ctx = egui::Context::default();
+    let report = set_with_options(&ctx, FontRegion::Korean, FontStyle::Sans, &options).unwrap();
+    assert_eq!(report.installed, ["Hack", "Ubuntu-Light"]);
+
+    // Appending keeps egui's own fonts first and adds ours in priority order.
+    let mut defs = FontDefinitions::default();
+    extend_with_options(
+        &ctx,
+        &mut defs,
+        FontRegion::Korean,
+        FontStyle::Sans,
+        &options,
+    )
+    .unwrap();
+    let proportional = &defs.families[&FontFamily::Proportional];
+    let ours: Vec<_> = proportional
+        .iter()
+        .filter(|k| is_system_font_key(k))
+        .collect();
+    assert_eq!(ours, [&key("Hack", 0), &key("Ubuntu-Light", 1)]);
+    assert!(!is_system_font_key(&proportional[0]));
+}
+
+#[test]
+fn provider_without_fonts_leaves_the_definitions_alone() {
+    struct NoFonts;
+
+    impl FontProvider for NoFonts {
+        fn find(&self, _presets: &[FontPreset], _style: FontStyle) -> Vec<FoundFont> {
+            Vec::new()
+        }
+
+        fn locale(&self) -> Option<String> {
+            Some("ja-JP".to_owned())
+        }
+    }
+
+    let ctx = egui::Context::default();
+    let mut defs = FontDefinitions::default();
+    let options = FontOptions::new().provider(NoFonts);
+    let report = extend_with_options(
+        &ctx,
+        &mut defs,
+        FontSelection::Auto,
+        FontStyle::Sans,
+        &options,
+    )
+    .unwrap();
+    assert!(report.installed.is_empty());
+    assert_eq!(report.region, Some(FontRegion::Japanese));
+    assert_eq!(defs, FontDefinitions::default());
+}
+
+#[test]
+fn damaged_fonts_are_skipped() {
+    let hack = bundled("Hack");
+    let provider = StaticProvider::new()
+        .font(
+            FontPreset::Latin,
+            "Broken Sans",
+            hack[..hack.len() / 2].to_vec(),
+        )
+        .font(FontPreset::Latin, "Hack", hack);
+    let options = FontOptions::new().provider(provider);
+    let ctx = egui::Context::default();
+    let report = set_with_options(&ctx, FontRegion::Latin, FontStyle::Sans, &options).unwrap();
+    assert_eq!(report.installed, ["Hack"]);
+    assert_eq!(report.skipped[0].family, "Broken Sans");
+    assert!(matches!(
+        report.skipped[0].reason,
+        SkipReason::InvalidFont(_)
+    ));
+}
+
+#[test]
+fn the_same_font_file_is_installed_once() {
+    let dir = temp_dir("duplicates");
+    std::fs::write(dir.join("Hack.ttf"), bundled("Hack")).unwrap();
+    std::fs::write(dir.join("Hack copy.ttf"), bundled("Hack")).unwrap();
+    #[cfg(unix)]
+    std::os::unix::fs::symlink(dir.join("Hack.ttf"), dir.join("link.ttf")).unwrap();
+    #[cfg(not(unix))]
+    std::fs::copy(dir.join("Hack.ttf"), dir.join("link.ttf")).unwrap();
+    let font = |family: &str, file: &str| FoundFont {
+        source: FoundFontSource::Path(dir.join(file)),
+        ..found(family, 0)
+    };
+    let fonts = vec![
+        font("Hack", "Hack.ttf"),
+        font("Hack Link", "link.ttf"),
+        font("Hack Copy", "Hack copy.ttf"),
+    ];
+
+    let mut defs = FontDefinitions::default();
+    let ctx = egui::Context::default();
+    let report = apply_found_fonts(
+        &ctx,
+        fonts,
+        ApplyMode::Extend(&mut defs),
+        &FontOptions::new(),
+    );
+    assert_eq!(report.installed, ["Hack"]);
+    assert_eq!(report.skipped.len(), 2);
+    for skipped in &report.skipped {
+        assert_eq!(
+            skipped.reason,
+            SkipReason::DuplicateFile(report.installed_keys[0].clone())
+        );
+    }
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn session_applies_its_steps_once() -> Result<(), FontError> {
+    let emoji = FontPreset::Custom(vec!["Noto Color Emoji".to_owned()]);
+    let provider = fonts(&[
+        (FontPreset::Japanese, "Noto Sans JP"),
+        (emoji.clone(), "Noto Color Emoji"),
+    ])
+    .font(FontPreset::Latin, "Noto Sans", bundled("Ubuntu-Light"));
+    let options = FontOptions::new().provider(provider);
+
+    let ctx = egui::Context::default();
+    let mut session = FontSession::begin(FontDefinitions::default());
+    session
+        .extend(vec![FontPreset::Japanese], FontStyle::Sans, &options)?
+        .extend(vec![FontPreset::Latin], FontStyle::Sans, &options)?
+        .extend(vec![emoji], FontStyle::Sans, &options)?;
+    assert_eq!(snapshot(&ctx).definitions(), &FontDefinitions::default());
+
+    let report = session.commit(&ctx);
+    assert_eq!(
+        report.installed,
+        ["Noto Sans JP", "Noto Sans", "Noto Color Emoji"]
+    );
+    assert_eq!(report.applications, 1);
+    Ok(())
+}
+
+#[test]
+fn session_leaves_definitions_in_effect_alone() -> Result<(), FontError> {
+    let options = FontOptions::new().provider(fonts(&[(FontPreset::Latin, "Hack")]));
+    let ctx = egui::Context::default();
+    set_with_options(&ctx, FontRegion::Latin, FontStyle::Sans, &options)?;
+
+    let mut session = FontSession::begin(FontDefinitions::default());
+    session.set(FontRegion::Latin, FontStyle::Sans, &options)?;
+    let report = session.commit(&ctx);
+    assert_eq!((report.applications, report.unchanged), (0, true));
+
+    let mut session = FontSession::begin(FontDefinitions::default());
+    session.set(FontRegion::Latin, FontStyle::Sans, &options.force(true))?;
+    assert_eq!(session.commit(&ctx).applications, 1);
+    Ok(())
+}
+
+#[test]
+fn installed_fonts_follow_removals() {
+    let options = FontOptions::new().provider(fonts(&[(FontPreset::Latin, "Hack")]));
+    let ctx = egui::Context::default();
+    assert_eq!(installed_fonts(&ctx), None);
+
+    set_with_options(&ctx, FontRegion::Latin, FontStyle::Sans, &options).unwrap();
+    let installed = installed_fonts(&ctx).unwrap();
+    assert_eq!(installed.len(), 1);
+    assert_eq!(installed[0].key, key("Hack", 0));
+    assert_eq!(installed[0].family, "Hack");
+
+    let mut defs = snapshot(&ctx).definitions().clone();
+    remove_family(&ctx, &mut defs, "Hack");
+    assert_eq!(installed_fonts(&ctx), Some(Vec::new()));
+}
+
+#[test]
+fn restoring_a_snapshot_undoes_a_preview() {
+    let ctx = egui::Context::default();
+    let before = snapshot(&ctx);
+    let options = FontOptions::new().provider(fonts(&[(FontPreset::Latin, "Hack")]));
+    set_with_options(&ctx, FontRegion::Latin, FontStyle::Sans, &options).unwrap();
+    assert_ne!(snapshot(&ctx), before);
+
+    restore(&ctx, &before);
+    assert_eq!(snapshot(&ctx), before);
+}
+
+#[test]
+fn background_setup_applies_when_done() {
+    use std::time::Duration;
+
+    /// A provider that takes its time, like a slow disk.
+    struct Slow(StaticProvider);
+
+    impl FontProvider for Slow {
+        fn find(&self, presets: &[FontPreset], style: FontStyle) -> Vec<FoundFont> {
+            std::thread::sleep(Duration::from_millis(100));
+            self.0.find(presets, style)
+        }
+
+        fn locale(&self) -> Option<String> {
+            None
+        }
+    }
+
+    let options = FontOptions::new().provider(Slow(fonts(&[(FontPreset::Latin, "Hack")])));
+    let ctx = egui::Context::default();
+    let mut task = set_async_with_options(&ctx, FontRegion::Latin, FontStyle::Sans, &options);
+    while task.poll().is_none() {
+        std::thread::sleep(Duration::from_millis(10));
+    }
+    assert!(task.is_finished());
+    assert_eq!(task.wait().unwrap().installed, ["Hack"]);
+    assert!(snapshot(&ctx)
+        .definitions()
+        .font_data
+        .contains_key(&key("Hack", 0)));
+}
+
+#[cfg(feature = "testing")]
+#[test]
+fn injected_fonts_reach_plain_and_background_setups() {
+    let _lock = global_defaults_lock();
+    set_test_fonts(vec![found("Test Sans", 0)]);
+    let ctx = egui::Context::default();
+    let plain = set_auto(&ctx, FontStyle::Sans);
+    let background = set_auto_async(&ctx, FontStyle::Sans).wait();
+
+    // Strict setups still check for the region's own fonts among the injected ones.
+    let options = FontOptions::new().strict(true);
+    let strict = set_with_options(&ctx, FontRegion::Korean, FontStyle::Sans, &options);
+    set_test_fonts(Vec::new());
+    let none = set_with_region(&ctx, FontRegion::Korean, FontStyle::Sans);
+    clear_test_fonts();
+
+    assert_eq!(plain, ["Test Sans"]);
+    assert_eq!(background.unwrap().installed, ["Test Sans"]);
+    assert!(matches!(
+        strict,
+        Err(FontError::RegionUnsupportedOnSystem(FontRegion::Korean))
+    ));
+    assert!(none.is_empty());
+}
+
+#[cfg(feature = "testing")]
+#[test]
+fn progress_callback_follows_the_global_options() {
+    let _lock = global_defaults_lock();
+    set_test_fonts(vec![found("Test Sans", 0)]);
+    let korean = EnvOverrides::from_vars([(ENV_REGION, "Korean")]);
+    let ctx = egui::Context::default();
+    let mut calls = Vec::new();
+
+    set_global_defaults(FontOptions::new().env_overrides(korean.clone()));
+    let installed = set_auto_with_progress(&ctx, FontStyle::Sans, |family, index, total| {
+        calls.push((family.to_owned(), index, total));
+    });
+    assert_eq!(installed, ["Test Sans"]);
+    assert_eq!(calls, [("Test Sans".to_owned(), 0, 1)]);
+
+    // No Korean font among the injected ones: nothing is read or applied.
+    set_global_defaults(FontOptions::new().env_overrides(korean).strict(true));
+    let other = egui::Context::default();
+    let installed =
+        set_auto_with_progress(&other, FontStyle::Sans, |_, _, _| panic!("read a font"));
+    set_global_defaults(FontOptions::new());
+    clear_test_fonts();
+    assert!(installed.is_empty());
+    assert!(installed_fonts(&other).is_none());
+}
+
+#[test]
+fn locale_watcher_reapplies_on_region_changes() {
+    /// Fonts from a static provider, with a locale the test can change.
+    struct Switchable(StaticProvider, Arc<Mutex<String>>);
+
+    impl FontProvider for Switchable {
+        fn find(&self, presets: &[FontPreset], style: FontStyle) -> Vec<FoundFont> {
+            self.0.find(presets, style)
+        }
+
+        fn locale(&self) -> Option<String> {
+            Some(self.1.lock().unwrap().clone())
+        }
+    }
+
+    let locale = Arc::new(Mutex::new("en-US".to_owned()));
+    let provider = Switchable(
+        fonts(&[(FontPreset::Japanese, "Noto Sans JP")]),
+        locale.clone(),
+    );
+    let ctx = egui::Context::default();
+    let watcher = LocaleWatcher::new(
+        &ctx,
+        FontStyle::Sans,
+        &FontOptions::new().provider(provider),
+    );
+    assert!(watcher.check().is_none());
+
+    // Same region: nothing to do.
+    *locale.lock().unwrap() = "en-GB".to_owned();
+    assert!(watcher.check().is_none());
+
+    *locale.lock().unwrap() = "ja-JP".to_owned();
+    let report = watcher.check().unwrap();
+    assert_eq!(report.region, Some(FontRegion::Japanese));
+    assert_eq!(report.installed, ["Noto Sans JP"]);
+    assert!(watcher.check().is_none());
+}
+
+#[test]
+fn locale_watcher_retries_a_failed_setup() {
+    let locale = Arc::new(Mutex::new("en-US".to_owned()));
+    let switchable = |provider: StaticProvider| {
+        struct Switchable(StaticProvider, Arc<Mutex<String>>);
+
+        impl FontProvider for Switchable {
+            fn find(&self, presets: &[FontPreset], style: FontStyle) -> Vec<FoundFont> {
+                self.0.find(presets, style)
+            }
+
+            fn locale(&self) -> Option<String> {
+                Some(self.1.lock().unwrap().clone())
+            }
+        }
+
+        Switchable(provider, locale.clone())
+    };
+    let ctx = egui::Context::default();
+    // Hack has no kana, so a strict Japanese setup fails.
+    let provider = switchable(fonts(&[(FontPreset::Japanese, "Noto Sans JP")]));
+    let options = FontOptions::new().provider(provider).strict(true);
+    let watcher = LocaleWatcher::new(&ctx, FontStyle::Sans, &options);
+
+    *locale.lock().unwrap() = "ja-JP".to_owned();
+    assert!(watcher.check().is_none());
+    assert!(format!("{watcher:?}").contains("region: Some(Latin)"));
+}
+
+#[test]
+fn text_styles_get_named_families_and_keep_their_sizes() {
+    let ctx = egui::Context::default();
+    let before = ctx.style().text_styles.clone();
+    let assigned = apply_text_style_fonts(
+        &ctx,
+        [(
+            egui::TextStyle::Heading,
+            FontRegion::Korean,
+            FontStyle::Serif,
+        )],
+    );
+    let family = FontFamily::Name("egui-system-fonts/Korean-Serif".into());
+    assert_eq!(assigned, [(egui::TextStyle::Heading, family.clone())]);
+
+    let after = ctx.style().text_styles.clone();
+    assert_eq!(after[&egui::TextStyle::Heading].family, family);
+    assert_eq!(
+        after[&egui::TextStyle::Heading].size,
+        before[&egui::TextStyle::Heading].size
+    );
+    assert_eq!(
+        after[&egui::TextStyle::Body],
+        before[&egui::TextStyle::Body]
+    );
+}
+
+#[test]
+fn weight_variants_get_their_own_families() {
+    let defaults = FontDefinitions::default();
+    // Stand-ins: Ubuntu Light as the regular face, Hack as the bold one.
+    let provider = StaticProvider::new()
+        .font(FontPreset::Korean, "Noto Sans KR", bundled("Ubuntu-Light"))
+        .weighted_font(FontPreset::Korean, "Noto Sans KR", 700, bundled("Hack"));
+    let options = FontOptions::new().provider(provider);
+    let ctx = egui::Context::default();
+    let mut defs = FontDefinitions::default();
+    let weights = [FontWeight::Regular, FontWeight::Bold];
+    let families = install_weight_variants_with_options(
+        &ctx,
+        &mut defs,
+        FontRegion::Korean,
+        FontStyle::Sans,
+        &weights,
+        &options,
+    );
+
+    let bold = FontFamily::Name("egui-system-fonts/Korean-Sans-Bold".into());
+    assert_eq!(families[1], bold);
+    let first = |family: &FontFamily| &defs.font_data[&defs.families[family][0]].font;
+    assert_eq!(
+        first(&families[0]),
+        &defaults.font_data["Ubuntu-Light"].font
+    );
+    assert_eq!(first(&bold), &defaults.font_data["Hack"].font);
+}
+
+#[test]
+fn env_overrides_come_from_options() {
+    let provider = fonts(&[
+        (FontPreset::Japanese, "Noto Serif JP"),
+        (FontPreset::Korean, "Noto Sans KR"),
+    ])
+    .with_locale("ko-KR");
+    let env = EnvOverrides::from_vars([(ENV_REGION, "Japanese"), (ENV_STYLE, "serif")]);
+    let options = FontOptions::new().provider(provider).env_overrides(env);
+
+    let ctx = egui::Context::default();
+    let report = set_with_options(&ctx, FontSelection::Auto, FontStyle::Sans, &options).unwrap();
+    assert_eq!(report.locale, None);
+    assert_eq!(report.region, Some(FontRegion::Japanese));
+    // The Japanese font leads, not the one for the provider's Korean locale.
+    assert_eq!(report.installed, ["Noto Serif JP", "Noto Sans KR"]);
+    // Serif fonts stay out of the monospace family.
+    assert_eq!(system_keys(&ctx, FontFamily::Proportional).len(), 2);
+    assert!(system_keys(&ctx, FontFamily::Monospace).is_empty());
+
+    let disabled = EnvOverrides::from_vars([(ENV_DISABLE, "1")]);
+    let options = options.env_overrides(disabled);
+    let ctx = egui::Context::default();
+    let report = set_with_options(&ctx, FontSelection::Auto, FontStyle::Sans, &options).unwrap();
+    assert!(report.installed.is_empty());
+    assert_eq!(report.region, None);
+    assert_eq!(report.applications, 0);
+}
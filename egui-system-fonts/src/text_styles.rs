@@ -0,0 +1,108 @@
+//! Binding resolved fonts to individual `egui` text styles.
+
+use crate::names::{family_from_key, normalize_family_name};
+use crate::{
+    global_defaults, load_found_fonts, namespaced_key, resolve, snapshot, FontRegion, FontReport,
+    FontSelection, FontStyle, FoundFont, KEY_PREFIX,
+};
+use egui::{FontDefinitions, FontFamily, FontId, TextStyle};
+
+/// Makes each listed [`TextStyle`] use system fonts for a region and style, e.g. serif headings.
+///
+/// For every `(text_style, region, style)` mapping the fonts are resolved and registered under a named
+/// family (`egui-system-fonts/<Region>-<Style>`, followed by the current proportional fonts as fallback),
+/// and the text style is pointed at that family in both the dark and light styles, keeping its size.
+/// Text styles that are not listed are left alone. Fonts are added to the definitions currently applied
+/// to `ctx` (see [`snapshot`](crate::snapshot())).
+///
+/// Returns the named family assigned to each text style, so sizes can be tweaked afterwards. Call this
+/// during setup or between passes: the new families become available on the next pass.
+///
+/// # Examples
+///
+/// ```
+/// # use egui_system_fonts::{apply_text_style_fonts, FontRegion, FontStyle};
+/// # use egui::{FontFamily, TextStyle};
+/// let ctx = egui::Context::default();
+/// let before = ctx.style().text_styles.clone();
+///
+/// let assigned = apply_text_style_fonts(&ctx, [(TextStyle::Heading, FontRegion::Korean, FontStyle::Serif)]);
+/// let family = FontFamily::Name("egui-system-fonts/Korean-Serif".into());
+/// assert_eq!(assigned, [(TextStyle::Heading, family.clone())]);
+///
+/// let after = ctx.style().text_styles.clone();
+/// assert_eq!(after[&TextStyle::Heading].family, family);
+/// assert_eq!(after[&TextStyle::Heading].size, before[&TextStyle::Heading].size);
+/// assert_eq!(after[&TextStyle::Body], before[&TextStyle::Body]);
+/// ```
+pub fn apply_text_style_fonts(
+    ctx: &egui::Context,
+    mappings: impl IntoIterator<Item = (TextStyle, FontRegion, FontStyle)>,
+) -> Vec<(TextStyle, FontFamily)> {
+    let options = global_defaults();
+    let mut defs = snapshot::snapshot(ctx).definitions().clone();
+    let mut assigned = Vec::new();
+
+    for (text_style, region, style) in mappings {
+        let family = FontFamily::Name(format!("{KEY_PREFIX}{region:?}-{style:?}").into());
+        let fonts = resolve::resolve(FontSelection::Region(region), style, &options)
+            .map(|resolution| resolution.fonts)
+            .unwrap_or_default();
+
+        let mut report = FontReport::default();
+        load_found_fonts(
+            &mut defs,
+            fonts.clone(),
+            &options,
+            &mut report,
+            &mut |_, _, _| {},
+        );
+
+        let mut keys: Vec<String> = fonts
+            .iter()
+            .filter_map(|f| installed_key(&defs, f))
+            .collect();
+        let fallback = defs
+            .families
+            .get(&FontFamily::Proportional)
+            .cloned()
+            .unwrap_or_default();
+        keys.extend(fallback);
+        let mut list: Vec<String> = Vec::new();
+        for key in keys {
+            if !list.contains(&key) {
+                list.push(key);
+            }
+        }
+        defs.families.insert(family.clone(), list);
+        assigned.push((text_style, family));
+    }
+
+    snapshot::install(ctx, defs);
+    ctx.all_styles_mut(|s| {
+        let default_size = s.text_styles.get(&TextStyle::Body).map_or(12.5, |f| f.size);
+        for (text_style, family) in &assigned {
+            s.text_styles
+                .entry(text_style.clone())
+                .and_modify(|font_id| font_id.family = family.clone())
+                .or_insert_with(|| FontId::new(default_size, family.clone()));
+        }
+    });
+    assigned
+}
+
+/// Finds the `font_data` key holding `font`: its own key, or an installed font of the same family.
+fn installed_key(defs: &FontDefinitions, font: &FoundFont) -> Option<String> {
+    let key = namespaced_key(&font.key).into_string();
+    if defs.font_data.contains_key(&key) {
+        return Some(key);
+    }
+    if defs.font_data.contains_key(&font.key) {
+        return Some(font.key.clone());
+    }
+    let normalized = normalize_family_name(&font.family);
+    defs.font_data
+        .keys()
+        .find(|k| family_from_key(k).is_some_and(|f| normalize_family_name(f) == normalized))
+        .cloned()
+}
@@ -0,0 +1,107 @@
+//! Per-preset glyph tweaks for fallback fonts.
+
+use crate::candidates::builtin_preset_of;
+use crate::resolve::classify;
+use crate::{FontOptions, FontPreset, FoundFont};
+use egui::FontTweak;
+
+/// Caller-supplied tweak for a candidate; `None` keeps the default.
+pub type TweakOverride = dyn Fn(&FoundFont) -> Option<FontTweak> + Send + Sync;
+
+/// Returns the tweak applied by default to fonts of `preset`.
+///
+/// CJK fonts are drawn slightly smaller and lower so mixed lines line up with `egui`'s default Latin
+/// font; other presets are left untouched.
+///
+/// # Examples
+///
+/// ```
+/// # use egui_system_fonts::{default_tweak, FontPreset};
+/// assert!(default_tweak(&FontPreset::Korean).scale < 1.0);
+/// assert_eq!(default_tweak(&FontPreset::Latin), egui::FontTweak::default());
+/// ```
+pub fn default_tweak(preset: &FontPreset) -> FontTweak {
+    match preset {
+        FontPreset::Korean
+        | FontPreset::Japanese
+        | FontPreset::SimplifiedChinese
+        | FontPreset::TraditionalChinese => FontTweak {
+            scale: 0.92,
+            y_offset_factor: 0.06,
+            y_offset: 0.0,
+        },
+        _ => FontTweak::default(),
+    }
+}
+
+/// Picks the tweak for a candidate: the caller's override, else the default for its built-in preset
+/// adjusted for its style ([`FontOptions::style_tweak`]), scaled by [`FontOptions::global_scale`].
+///
+/// Also returns whether the tweak is still to be fitted to the font's metrics with [`fit_metrics`]
+/// ([`FontOptions::normalize_metrics`]); the fitted tweak takes the place of the preset default.
+pub(crate) fn tweak_for(font: &FoundFont, options: &FontOptions) -> (FontTweak, bool) {
+    let (mut tweak, fit) = base_tweak(font, options);
+    if let Some(scale) = options.global_scale {
+        tweak.scale *= scale;
+    }
+    (tweak, fit)
+}
+
+fn base_tweak(font: &FoundFont, options: &FontOptions) -> (FontTweak, bool) {
+    if let Some(hook) = &options.tweak {
+        if let Some(tweak) = (hook.0)(font) {
+            return (tweak, false);
+        }
+    }
+    let mut tweak = if options.no_default_tweaks || options.normalize_metrics {
+        FontTweak::default()
+    } else {
+        builtin_preset_of(&font.family)
+            .map(|preset| default_tweak(&preset))
+            .unwrap_or_default()
+    };
+    if options.style_tweaks.is_empty() {
+        return (tweak, options.normalize_metrics);
+    }
+    let style = classify(font, options);
+    if let Some((_, adjust)) = options.style_tweaks.iter().find(|(s, _)| Some(*s) == style) {
+        tweak.scale *= adjust.scale;
+        tweak.y_offset_factor += adjust.y_offset_factor;
+        tweak.y_offset += adjust.y_offset;
+    }
+    (tweak, options.normalize_metrics)
+}
+
+/// Vertical metrics of `egui`'s default proportional font (Ubuntu-Light), in font units: ascent, descent
+/// and line gap.
+const REFERENCE_METRICS: (f32, f32, f32) = (932.0, -189.0, 28.0);
+
+/// Adjusts `tweak` so `font` (a single face) gets the row height and baseline of `egui`'s default font.
+///
+/// `egui` sizes a font by its ascent minus descent and adds the line gap to get the row height, so fonts
+/// with a larger line gap get taller rows at the same size. The fitted scale evens that out, and the offset
+/// moves the baseline to where the default font has it. Scales multiply and offsets add, like
+/// [`FontOptions::style_tweak`]. Fonts that cannot be parsed keep `tweak`.
+pub(crate) fn fit_metrics(tweak: FontTweak, font: &[u8]) -> FontTweak {
+    let Ok(face) = ttf_parser::Face::parse(font, 0) else {
+        return tweak;
+    };
+    let (ascent, descent, line_gap) = (
+        f32::from(face.ascender()),
+        f32::from(face.descender()),
+        f32::from(face.line_gap()),
+    );
+    let height = ascent - descent;
+    if height <= 0.0 || line_gap < 0.0 {
+        return tweak;
+    }
+    let (ref_ascent, ref_descent, ref_line_gap) = REFERENCE_METRICS;
+    let ref_height = ref_ascent - ref_descent;
+    let scale = ((ref_height + ref_line_gap) / ref_height) / ((height + line_gap) / height);
+    let y_offset_factor = (ref_ascent / ref_height) / scale - ascent / height;
+    FontTweak {
+        scale: tweak.scale * scale,
+        y_offset_factor: tweak.y_offset_factor + y_offset_factor,
+        y_offset: tweak.y_offset,
+    }
+}
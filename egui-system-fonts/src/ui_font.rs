@@ -0,0 +1,78 @@
+//! Querying the font the operating system uses for its own UI.
+
+/// Family names of the platform's default UI font, best match first.
+///
+/// - Windows: Segoe UI Variable (Windows 11), then Segoe UI.
+/// - macOS: the system font (San Francisco) under its various installed names, then Helvetica Neue.
+/// - Android: Roboto.
+/// - Linux and other Unix systems: fontconfig's match for `sans-serif` (`fc-match`), which follows the
+///   desktop's configuration. Empty if `fc-match` is not available.
+/// - Elsewhere (e.g. the web): empty.
+pub(crate) fn system_ui_families() -> Vec<String> {
+    platform_families()
+}
+
+#[cfg(target_os = "windows")]
+fn platform_families() -> Vec<String> {
+    to_owned(&["Segoe UI Variable Text", "Segoe UI Variable", "Segoe UI"])
+}
+
+#[cfg(any(target_os = "macos", target_os = "ios"))]
+fn platform_families() -> Vec<String> {
+    to_owned(&[
+        ".AppleSystemUIFont",
+        "SF Pro Text",
+        "SF Pro",
+        ".SF NS",
+        "System Font",
+        "Helvetica Neue",
+    ])
+}
+
+#[cfg(all(
+    unix,
+    not(any(target_os = "macos", target_os = "ios", target_os = "android"))
+))]
+fn platform_families() -> Vec<String> {
+    let output = std::process::Command::new("fc-match")
+        .args(["-f", "%{family[0]}", "sans-serif"])
+        .output();
+    match output {
+        Ok(output) if output.status.success() => {
+            let family = String::from_utf8_lossy(&output.stdout).trim().to_owned();
+            if family.is_empty() {
+                Vec::new()
+            } else {
+                vec![family]
+            }
+        }
+        Ok(output) => {
+            log::debug!("fc-match failed: {}", output.status);
+            Vec::new()
+        }
+        Err(e) => {
+            log::debug!("Failed to run fc-match: {}", e);
+            Vec::new()
+        }
+    }
+}
+
+#[cfg(not(any(target_os = "windows", unix)))]
+fn platform_families() -> Vec<String> {
+    Vec::new()
+}
+
+#[cfg(target_os = "android")]
+fn platform_families() -> Vec<String> {
+    to_owned(&["Roboto"])
+}
+
+#[cfg(any(
+    target_os = "windows",
+    target_os = "macos",
+    target_os = "ios",
+    target_os = "android"
+))]
+fn to_owned(families: &[&str]) -> Vec<String> {
+    families.iter().map(|&f| f.to_owned()).collect()
+}
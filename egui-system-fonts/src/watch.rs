@@ -0,0 +1,186 @@
+//! Re-applying fonts when the system locale changes while the app runs.
+
+use crate::{
+    global_defaults, resolve, set_with_options, FontOptions, FontRegion, FontReport, FontSelection,
+    FontStyle,
+};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+/// How often [`watch_locale`] checks the locale.
+const DEFAULT_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Polls the system locale and replaces the fonts of `ctx` (like [`set_auto`](crate::set_auto)) whenever
+/// it maps to a different region, e.g. after the user changes the display language.
+///
+/// Checks every few seconds on a background thread and requests a repaint after re-applying. Changes that
+/// keep the region (`en-US` to `en-GB`) are ignored. The fonts are not touched until the region changes, so
+/// set them up as usual at startup. Dropping the returned watcher stops it.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use egui_system_fonts::{set_auto, watch_locale, FontStyle, LocaleWatcher};
+/// struct MyApp {
+///     _locale_watcher: LocaleWatcher,
+/// }
+///
+/// # fn demo(ctx: &egui::Context) -> MyApp {
+/// set_auto(ctx, FontStyle::Sans);
+/// MyApp {
+///     _locale_watcher: watch_locale(ctx, FontStyle::Sans),
+/// }
+/// # }
+/// ```
+pub fn watch_locale(ctx: &egui::Context, style: FontStyle) -> LocaleWatcher {
+    LocaleWatcher::new(ctx, style, &global_defaults()).with_interval(DEFAULT_INTERVAL)
+}
+
+/// Re-applies fonts when the locale maps to a new region; see [`watch_locale`].
+///
+/// A watcher made with [`new`](Self::new) only checks when [`check`](Self::check) is called, e.g. when the
+/// app window regains focus; [`with_interval`](Self::with_interval) adds a polling thread. The locale is read
+/// from the options' [`provider`](FontOptions::provider), so a provider with a changeable locale drives the
+/// watcher in tests. Environment overrides apply as for [`FontSelection::Auto`].
+///
+/// # Examples
+///
+/// ```no_run
+/// # use egui_system_fonts::{FontOptions, FontStyle, LocaleWatcher};
+/// # fn demo(ctx: &egui::Context) {
+/// let watcher = LocaleWatcher::new(ctx, FontStyle::Sans, &FontOptions::new());
+/// // Once in a while:
+/// if let Some(report) = watcher.check() {
+///     println!("switched to {:?}", report.region);
+/// }
+/// # }
+/// ```
+pub struct LocaleWatcher {
+    watch: Arc<Mutex<Watch>>,
+    stop: Option<mpsc::Sender<()>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+struct Watch {
+    ctx: egui::Context,
+    style: FontStyle,
+    options: FontOptions,
+    /// Region the current fonts were set up for; `None` if unknown or disabled by the environment.
+    region: Option<FontRegion>,
+}
+
+impl LocaleWatcher {
+    /// Creates a watcher for the current locale that checks only when asked to.
+    pub fn new(ctx: &egui::Context, style: FontStyle, options: &FontOptions) -> Self {
+        let mut watch = Watch {
+            ctx: ctx.clone(),
+            style,
+            options: options.clone(),
+            region: None,
+        };
+        watch.region = watch.current_region();
+        Self {
+            watch: Arc::new(Mutex::new(watch)),
+            stop: None,
+            thread: None,
+        }
+    }
+
+    /// Also checks every `interval` on a background thread, until the watcher is dropped.
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.stop_thread();
+        let (stop, stopped) = mpsc::channel();
+        let watch = Arc::clone(&self.watch);
+        let spawned = std::thread::Builder::new()
+            .name("egui-system-fonts-locale".to_owned())
+            .spawn(move || {
+                while let Err(RecvTimeoutError::Timeout) = stopped.recv_timeout(interval) {
+                    lock(&watch).check();
+                }
+            });
+        match spawned {
+            Ok(thread) => {
+                self.stop = Some(stop);
+                self.thread = Some(thread);
+            }
+            Err(e) => log::warn!("Failed to spawn locale watcher thread: {}", e),
+        }
+        self
+    }
+
+    /// Reads the locale now and, if it maps to a different region than before, replaces the fonts and
+    /// requests a repaint.
+    ///
+    /// Returns the report of the new setup, or `None` if the region did not change. With
+    /// [`FontOptions::strict`], a failed setup is reported through [`FontOptions::log_sink`], the
+    /// previous fonts stay and the next check tries again.
+    pub fn check(&self) -> Option<FontReport> {
+        lock(&self.watch).check()
+    }
+
+    fn stop_thread(&mut self) {
+        // Dropping the sender wakes the thread up and ends its loop.
+        self.stop = None;
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+impl Drop for LocaleWatcher {
+    fn drop(&mut self) {
+        self.stop_thread();
+    }
+}
+
+impl std::fmt::Debug for LocaleWatcher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LocaleWatcher")
+            .field("region", &lock(&self.watch).region)
+            .field("polling", &self.thread.is_some())
+            .finish()
+    }
+}
+
+impl Watch {
+    fn current_region(&self) -> Option<FontRegion> {
+        let provider = self.options.font_provider();
+        let env = self.options.env.as_ref();
+        resolve::plan(FontSelection::Auto, self.style, env, provider, false)
+            .and_then(|plan| plan.region)
+    }
+
+    fn check(&mut self) -> Option<FontReport> {
+        let region = self.current_region();
+        if region == self.region {
+            return None;
+        }
+        if self.options.logs() {
+            log::info!("Region changed from {:?} to {:?}", self.region, region);
+        }
+        if region.is_none() {
+            self.region = None;
+            return None;
+        }
+
+        match set_with_options(&self.ctx, FontSelection::Auto, self.style, &self.options) {
+            Ok(report) => {
+                // Only a successful setup moves on, so a failed one is retried on the next check.
+                self.region = region;
+                self.ctx.request_repaint();
+                Some(report)
+            }
+            Err(e) => {
+                self.options
+                    .warn(format!("Failed to re-apply fonts: {}", e));
+                None
+            }
+        }
+    }
+}
+
+fn lock(watch: &Mutex<Watch>) -> std::sync::MutexGuard<'_, Watch> {
+    watch.lock().unwrap_or_else(PoisonError::into_inner)
+}
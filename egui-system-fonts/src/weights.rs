@@ -0,0 +1,184 @@
+//! Registering other weights of the resolved fonts as named families, since `egui` has no synthetic bold.
+
+use crate::read::{read_found_fonts, Installed};
+use crate::{
+    global_defaults, resolve, snapshot, FontOptions, FontRegion, FontReport, FontSelection,
+    FontStyle, FoundFont, KEY_PREFIX,
+};
+use egui::{FontDefinitions, FontFamily};
+
+/// A standard font weight, as used in the `OS/2` table and CSS.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum FontWeight {
+    /// 100.
+    Thin,
+    /// 200.
+    ExtraLight,
+    /// 300.
+    Light,
+    /// 400.
+    Regular,
+    /// 500.
+    Medium,
+    /// 600.
+    SemiBold,
+    /// 700.
+    Bold,
+    /// 800.
+    ExtraBold,
+    /// 900.
+    Black,
+}
+
+impl FontWeight {
+    /// The numeric weight, e.g. 700 for [`FontWeight::Bold`].
+    pub fn to_number(self) -> u16 {
+        match self {
+            Self::Thin => 100,
+            Self::ExtraLight => 200,
+            Self::Light => 300,
+            Self::Regular => 400,
+            Self::Medium => 500,
+            Self::SemiBold => 600,
+            Self::Bold => 700,
+            Self::ExtraBold => 800,
+            Self::Black => 900,
+        }
+    }
+}
+
+/// Registers each of `weights` of the system fonts for a region and style as its own named family.
+///
+/// The fonts are resolved once, as for [`set_with_region`](crate::set_with_region); for every weight, each
+/// resolved family's face of that weight is installed under `egui-system-fonts/<Region>-<Style>-<Weight>`
+/// (e.g. `egui-system-fonts/Korean-Sans-Bold`), followed by the current proportional fonts as fallback. Only
+/// faces of the same family as the regular face are used, never an unrelated bold font; a family without
+/// that weight contributes its regular face so its script still renders. Proportional and Monospace are
+/// left alone. The definitions are applied to `ctx`.
+///
+/// Returns the named families in the order of `weights`; use them with
+/// [`RichText::family`](egui::RichText::family) or a [`FontId`](egui::FontId).
+///
+/// # Examples
+///
+/// ```no_run
+/// # use egui_system_fonts::{install_weight_variants, FontRegion, FontStyle, FontWeight};
+/// # fn demo(ctx: &egui::Context, ui: &mut egui::Ui) {
+/// let mut defs = egui::FontDefinitions::default();
+/// let weights = [FontWeight::Regular, FontWeight::Bold];
+/// let families = install_weight_variants(ctx, &mut defs, FontRegion::Korean, FontStyle::Sans, &weights);
+///
+/// // On the next pass:
+/// ui.label(egui::RichText::new("굵게").family(families[1].clone()));
+/// # }
+/// ```
+pub fn install_weight_variants(
+    ctx: &egui::Context,
+    defs: &mut FontDefinitions,
+    region: FontRegion,
+    style: FontStyle,
+    weights: &[FontWeight],
+) -> Vec<FontFamily> {
+    install_weight_variants_with_options(ctx, defs, region, style, weights, &global_defaults())
+}
+
+/// Like [`install_weight_variants`], with options. [`FontOptions::weight`] is ignored; faces are looked up
+/// through the options' [`provider`](FontOptions::provider).
+///
+/// # Examples
+///
+/// ```no_run
+/// # use egui_system_fonts::{install_weight_variants_with_options, snapshot, FontOptions, FontRegion, FontStyle, FontWeight};
+/// # fn demo(ctx: &egui::Context) {
+/// let mut defs = snapshot(ctx).definitions().clone();
+/// let weights = [FontWeight::Regular, FontWeight::Bold];
+/// let options = FontOptions::new();
+/// let families =
+///     install_weight_variants_with_options(ctx, &mut defs, FontRegion::Korean, FontStyle::Sans, &weights, &options);
+/// ctx.set_fonts(defs);
+/// println!("{families:?}");
+/// # }
+/// ```
+pub fn install_weight_variants_with_options(
+    ctx: &egui::Context,
+    defs: &mut FontDefinitions,
+    region: FontRegion,
+    style: FontStyle,
+    weights: &[FontWeight],
+    options: &FontOptions,
+) -> Vec<FontFamily> {
+    let mut options = options.clone();
+    options.weight = None;
+    let fonts = resolve::resolve(FontSelection::Region(region), style, &options)
+        .map(|resolution| resolution.fonts)
+        .unwrap_or_default();
+    let fallback = defs
+        .families
+        .get(&FontFamily::Proportional)
+        .cloned()
+        .unwrap_or_default();
+
+    let mut families = Vec::new();
+    for &weight in weights {
+        let family =
+            FontFamily::Name(format!("{KEY_PREFIX}{region:?}-{style:?}-{weight:?}").into());
+        let faces = fonts
+            .iter()
+            .map(|font| weighted_face(font, weight, &options))
+            .collect();
+
+        // Faces of one family at several weights share a normalized family name, so they are not
+        // deduplicated against each other or the fonts already in `defs`.
+        let mut report = FontReport::default();
+        let loaded = read_found_fonts(
+            faces,
+            &options,
+            &mut report,
+            &mut Installed::default(),
+            &mut |_, _, _| {},
+        );
+        let mut list: Vec<String> = Vec::new();
+        for font in loaded {
+            let key = font.key.into_string();
+            defs.font_data.insert(key.clone(), font.data);
+            list.push(key);
+        }
+        for key in &fallback {
+            if !list.contains(key) {
+                list.push(key.clone());
+            }
+        }
+        defs.families.insert(family.clone(), list);
+        families.push(family);
+    }
+
+    snapshot::install(ctx, defs.clone());
+    if options.logs() {
+        log::info!("Installed weight variants: {:?}", families);
+    }
+    families
+}
+
+/// `font`'s face of `weight` under its own key, or the regular face if the family has none.
+fn weighted_face(font: &FoundFont, weight: FontWeight, options: &FontOptions) -> FoundFont {
+    if weight == FontWeight::Regular {
+        return font.clone();
+    }
+    match options
+        .font_provider()
+        .find_weight(font, weight.to_number())
+    {
+        Some(face) => FoundFont {
+            key: format!("{}@{}", font.key, weight.to_number()),
+            ..face
+        },
+        None => {
+            log::debug!(
+                "No {:?} face of {:?}; using its regular face",
+                weight,
+                font.family
+            );
+            font.clone()
+        }
+    }
+}